@@ -0,0 +1,42 @@
+// counts the positions reachable in the given number of moves, e.g.
+// cargo run --release -p chess_core --bin perft -- 4 "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1"
+use chess_core::board::Board;
+use chess_core::fen::STARTING_FEN;
+use std::process::exit;
+use std::time::Instant;
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let divide = args.iter().any(|arg| arg == "--divide");
+    let args: Vec<&String> = args.iter().filter(|arg| *arg != "--divide").collect();
+
+    let Some(depth) = args.first().and_then(|depth| depth.parse::<u32>().ok()) else {
+        eprintln!("Usage: perft <depth> [fen] [--divide]");
+        exit(1);
+    };
+    let fen = args.get(1).map_or(STARTING_FEN, |fen| fen.as_str());
+    let board = Board::from_fen(fen).unwrap_or_else(|error| {
+        eprintln!("Invalid FEN: {}", error);
+        exit(1);
+    });
+
+    let start = Instant::now();
+    let nodes = if divide {
+        let mut nodes = 0;
+        for (chess_move, count) in board.divide(depth) {
+            println!("{}: {}", chess_move, count);
+            nodes += count;
+        }
+        println!();
+        nodes
+    } else {
+        board.perft(depth)
+    };
+    let seconds = start.elapsed().as_secs_f64();
+    println!("Nodes searched: {}", nodes);
+    println!(
+        "Time: {:.3} s ({:.0} nodes/s)",
+        seconds,
+        nodes as f64 / seconds.max(1e-9)
+    );
+}
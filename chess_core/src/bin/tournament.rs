@@ -0,0 +1,229 @@
+// plays a round robin between UCI engines without opening a window, e.g.
+// cargo run --release -p chess_core --bin tournament -- --engine sf=/usr/games/stockfish \
+//     --engine lc0=/usr/local/bin/lc0 --games 20 --openings openings.epd --movetime 100
+use chess_core::chess_pieces::PieceColor;
+use chess_core::engine::SearchLimits;
+use chess_core::game_result::{EndReason, GameResult};
+use chess_core::pgn::{result_tag, MoveLog, Pgn};
+use chess_core::tournament::{parse_epd, parse_pgn_openings, play_game, round_robin, Crosstable};
+use chess_core::uci::{UciEngine, UciError};
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use std::process::exit;
+use std::time::Duration;
+
+const USAGE: &str = "Usage: tournament --engine [name=]command --engine [name=]command ...
+    [--option name:option=value] [--games n] [--openings file.epd|file.pgn]
+    [--movetime ms] [--depth n] [--nodes n] [--pgn file] [--output file]";
+const DEFAULT_GAMES: usize = 2;
+const DEFAULT_MOVETIME: Duration = Duration::from_millis(100);
+const DEFAULT_PGN_FILE: &str = "tournament.pgn";
+
+struct EngineSpec {
+    name: String,
+    command: String,
+    options: Vec<(String, String)>,
+}
+
+struct Settings {
+    engines: Vec<EngineSpec>,
+    games: usize,
+    openings: Vec<MoveLog>,
+    limits: SearchLimits,
+    pgn_file: String,
+    // the crosstable, by default next to the PGN file, e.g. tournament.crosstable.txt
+    output_file: Option<String>,
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("{}", message);
+    exit(1);
+}
+
+fn read_openings(path: &str) -> Vec<MoveLog> {
+    let text = std::fs::read_to_string(path)
+        .unwrap_or_else(|error| fail(&format!("Can't read {}: {}", path, error)));
+    let openings = if path.ends_with(".pgn") {
+        parse_pgn_openings(&text).map_err(|error| error.to_string())
+    } else {
+        parse_epd(&text).map_err(|error| error.to_string())
+    };
+    openings.unwrap_or_else(|error| fail(&format!("Invalid openings in {}: {}", path, error)))
+}
+
+fn parse_args(args: &[String]) -> Settings {
+    let mut settings = Settings {
+        engines: Vec::new(),
+        games: DEFAULT_GAMES,
+        openings: Vec::new(),
+        limits: SearchLimits::default(),
+        pgn_file: DEFAULT_PGN_FILE.to_string(),
+        output_file: None,
+    };
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let value = args.next().unwrap_or_else(|| fail(USAGE));
+        let number = || value.parse::<u64>().unwrap_or_else(|_| fail(USAGE));
+        match arg.as_str() {
+            "--engine" => {
+                let (name, command) = match value.split_once('=') {
+                    Some((name, command)) => (name.to_string(), command.to_string()),
+                    None => (
+                        Path::new(value)
+                            .file_stem()
+                            .map_or(value.clone(), |name| name.to_string_lossy().to_string()),
+                        value.clone(),
+                    ),
+                };
+                settings.engines.push(EngineSpec {
+                    name,
+                    command,
+                    options: Vec::new(),
+                });
+            }
+            "--option" => {
+                let (engine, option) = value.split_once(':').unwrap_or_else(|| fail(USAGE));
+                let (name, option_value) = option.split_once('=').unwrap_or_else(|| fail(USAGE));
+                let spec = settings
+                    .engines
+                    .iter_mut()
+                    .find(|spec| spec.name == engine)
+                    .unwrap_or_else(|| fail(&format!("No engine named {}", engine)));
+                spec.options
+                    .push((name.to_string(), option_value.to_string()));
+            }
+            "--games" => settings.games = number() as usize,
+            "--openings" => settings.openings = read_openings(value),
+            "--movetime" => settings.limits.movetime = Some(Duration::from_millis(number())),
+            "--depth" => settings.limits.depth = Some(number() as u32),
+            "--nodes" => settings.limits.nodes = Some(number()),
+            "--pgn" => settings.pgn_file = value.clone(),
+            "--output" => settings.output_file = Some(value.clone()),
+            _ => fail(USAGE),
+        }
+    }
+    if settings.engines.len() < 2 {
+        fail(USAGE);
+    }
+    if settings.openings.is_empty() {
+        settings.openings.push(MoveLog::default());
+    }
+    let limits = settings.limits;
+    if limits.movetime.is_none() && limits.depth.is_none() && limits.nodes.is_none() {
+        settings.limits.movetime = Some(DEFAULT_MOVETIME);
+    }
+    settings
+}
+
+// rewritten after every game like the PGN, so that an interrupted tournament keeps
+// the standings so far
+fn write_crosstable(path: &str, crosstable: &Crosstable) {
+    std::fs::write(path, crosstable.to_string())
+        .unwrap_or_else(|error| fail(&format!("Can't write {}: {}", path, error)));
+}
+
+fn start_engine(spec: &EngineSpec) -> Result<UciEngine, UciError> {
+    let mut engine = UciEngine::start(&spec.command, &[])?;
+    for (name, value) in &spec.options {
+        engine.set_option(name, value)?;
+    }
+    engine.wait_until_ready()?;
+    Ok(engine)
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let settings = parse_args(&args);
+
+    // an engine that fails during a game is started again for its next one
+    let mut engines: Vec<Option<UciEngine>> = settings
+        .engines
+        .iter()
+        .map(|spec| {
+            let engine = start_engine(spec)
+                .unwrap_or_else(|error| fail(&format!("Can't start {}: {}", spec.name, error)));
+            Some(engine)
+        })
+        .collect();
+    let write_error = |error: std::io::Error| -> ! {
+        fail(&format!("Can't write {}: {}", settings.pgn_file, error))
+    };
+    let mut pgn_file = File::create(&settings.pgn_file).unwrap_or_else(|error| write_error(error));
+    let output_file = settings.output_file.clone().unwrap_or_else(|| {
+        Path::new(&settings.pgn_file)
+            .with_extension("crosstable.txt")
+            .to_string_lossy()
+            .to_string()
+    });
+
+    let names: Vec<String> = settings
+        .engines
+        .iter()
+        .map(|spec| spec.name.clone())
+        .collect();
+    let mut crosstable = Crosstable::new(names.clone());
+    let pairings = round_robin(names.len(), settings.games, settings.openings.len());
+    for pairing in &pairings {
+        let mut white = engines[pairing.white]
+            .take()
+            .map_or_else(|| start_engine(&settings.engines[pairing.white]), Ok);
+        let mut black = engines[pairing.black]
+            .take()
+            .map_or_else(|| start_engine(&settings.engines[pairing.black]), Ok);
+        let opening = &settings.openings[pairing.opening];
+        let (log, result) = match (&mut white, &mut black) {
+            (Ok(white), Ok(black)) => play_game(white, black, opening, &settings.limits),
+            (Err(_), _) => (
+                opening.clone(),
+                GameResult::win(PieceColor::Black, EndReason::EngineFailure),
+            ),
+            (_, Err(_)) => (
+                opening.clone(),
+                GameResult::win(PieceColor::White, EndReason::EngineFailure),
+            ),
+        };
+        let failed = match (result.reason, result.outcome.winner()) {
+            (EndReason::EngineFailure, Some(PieceColor::White)) => Some(pairing.black),
+            (EndReason::EngineFailure, Some(PieceColor::Black)) => Some(pairing.white),
+            _ => None,
+        };
+        for (index, engine) in [(pairing.white, white), (pairing.black, black)] {
+            if failed != Some(index) {
+                engines[index] = engine.ok();
+            }
+        }
+
+        crosstable.add(pairing, result.outcome);
+        let (white_name, black_name) = (&names[pairing.white], &names[pairing.black]);
+        println!(
+            "Game {}/{}: {} - {} {} ({})",
+            pairing.round,
+            pairings.len(),
+            white_name,
+            black_name,
+            result_tag(Some(result)),
+            result.reason
+        );
+
+        let mut pgn = Pgn::new(&log, white_name, black_name, Some(result));
+        pgn.set_tag("Event", "Chess Masters tournament");
+        pgn.set_tag("Round", &pairing.round.to_string());
+        let termination = match result.reason {
+            EndReason::IllegalMove | EndReason::EngineFailure => "rules infraction",
+            _ => "normal",
+        };
+        pgn.set_tag("Termination", termination);
+        // written after every game, so that an interrupted tournament keeps its games
+        writeln!(pgn_file, "{}", pgn).unwrap_or_else(|error| write_error(error));
+        write_crosstable(&output_file, &crosstable);
+    }
+
+    println!();
+    print!("{}", crosstable);
+    println!();
+    println!(
+        "Games written to {}, crosstable to {}",
+        settings.pgn_file, output_file
+    );
+}
@@ -0,0 +1,144 @@
+use crate::coordinates::Coordinates;
+
+// one bit per field, a1 is bit 0, b1 bit 1 and h8 bit 63
+pub type Bitboard = u64;
+
+pub fn square_index(coordinates: Coordinates) -> usize {
+    ((coordinates.y - 1) * 8 + (coordinates.x - 1)) as usize
+}
+
+pub fn square_coordinates(square: usize) -> Coordinates {
+    Coordinates {
+        x: (square % 8) as i32 + 1,
+        y: (square / 8) as i32 + 1,
+    }
+}
+
+pub fn square_bit(square: usize) -> Bitboard {
+    1 << square
+}
+
+// iterates over the indices of the set bits, lowest first
+pub fn squares(mut bitboard: Bitboard) -> impl Iterator<Item = usize> {
+    std::iter::from_fn(move || {
+        if bitboard == 0 {
+            return None;
+        }
+        let square = bitboard.trailing_zeros() as usize;
+        bitboard &= bitboard - 1;
+        Some(square)
+    })
+}
+
+const fn leaper_table(offsets: &[(i32, i32)]) -> [Bitboard; 64] {
+    let mut table = [0; 64];
+    let mut square = 0;
+    while square < 64 {
+        let x = (square % 8) as i32;
+        let y = (square / 8) as i32;
+        let mut i = 0;
+        while i < offsets.len() {
+            let (dx, dy) = offsets[i];
+            if x + dx >= 0 && x + dx < 8 && y + dy >= 0 && y + dy < 8 {
+                table[square] |= 1 << ((y + dy) * 8 + x + dx);
+            }
+            i += 1;
+        }
+        square += 1;
+    }
+    table
+}
+
+pub const KNIGHT_ATTACKS: [Bitboard; 64] = leaper_table(&[
+    (1, 2),
+    (2, 1),
+    (2, -1),
+    (1, -2),
+    (-1, -2),
+    (-2, -1),
+    (-2, 1),
+    (-1, 2),
+]);
+
+pub const KING_ATTACKS: [Bitboard; 64] = leaper_table(&[
+    (0, 1),
+    (1, 1),
+    (1, 0),
+    (1, -1),
+    (0, -1),
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+]);
+
+// fields attacked by a pawn standing on the square, indexed by color (white first)
+pub const PAWN_ATTACKS: [[Bitboard; 64]; 2] = [
+    leaper_table(&[(-1, 1), (1, 1)]),
+    leaper_table(&[(-1, -1), (1, -1)]),
+];
+
+// the first four directions go towards higher squares, the last four towards lower ones
+const DIRECTIONS: [(i32, i32); 8] = [
+    (0, 1),
+    (1, 1),
+    (1, 0),
+    (-1, 1),
+    (0, -1),
+    (-1, -1),
+    (-1, 0),
+    (1, -1),
+];
+
+const fn ray_table() -> [[Bitboard; 64]; 8] {
+    let mut table = [[0; 64]; 8];
+    let mut direction = 0;
+    while direction < 8 {
+        let (dx, dy) = DIRECTIONS[direction];
+        let mut square = 0;
+        while square < 64 {
+            let mut x = (square % 8) as i32 + dx;
+            let mut y = (square / 8) as i32 + dy;
+            while x >= 0 && x < 8 && y >= 0 && y < 8 {
+                table[direction][square] |= 1 << (y * 8 + x);
+                x += dx;
+                y += dy;
+            }
+            square += 1;
+        }
+        direction += 1;
+    }
+    table
+}
+
+// all fields from the square to the edge of the board in each direction
+const RAYS: [[Bitboard; 64]; 8] = ray_table();
+
+const ROOK_DIRECTIONS: [usize; 4] = [0, 2, 4, 6];
+const BISHOP_DIRECTIONS: [usize; 4] = [1, 3, 5, 7];
+
+// the ray up to and including the first occupied field
+fn ray_attacks(direction: usize, square: usize, occupied: Bitboard) -> Bitboard {
+    let ray = RAYS[direction][square];
+    let blockers = ray & occupied;
+    if blockers == 0 {
+        return ray;
+    }
+    let blocker = if direction < 4 {
+        blockers.trailing_zeros() as usize
+    } else {
+        63 - blockers.leading_zeros() as usize
+    };
+    ray ^ RAYS[direction][blocker]
+}
+
+pub fn rook_attacks(square: usize, occupied: Bitboard) -> Bitboard {
+    ROOK_DIRECTIONS.iter().fold(0, |attacks, direction| {
+        attacks | ray_attacks(*direction, square, occupied)
+    })
+}
+
+pub fn bishop_attacks(square: usize, occupied: Bitboard) -> Bitboard {
+    BISHOP_DIRECTIONS.iter().fold(0, |attacks, direction| {
+        attacks | ray_attacks(*direction, square, occupied)
+    })
+}
@@ -0,0 +1,669 @@
+use crate::bitboard::square_index;
+use crate::chess_pieces::*;
+use crate::coordinates::Coordinates;
+use crate::field::*;
+use crate::game_result::{EndReason, GameResult};
+use crate::moves::get_possible_moves;
+use crate::position::{color_index, kind_index, Position};
+use crate::zobrist::*;
+use crate::BOARD_SIZE;
+
+// everything a move changes, so that it can be taken back: the pieces on the fields it
+// touches (the captured piece, the moved flags, the castling rook and the promoted pawn
+// are all kept here), the king positions and the rest of the position state
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MoveRecord {
+    pub from: Coordinates,
+    pub to: Coordinates,
+    pub promotion: Option<PieceType>,
+    previous_fields: Vec<(Coordinates, Option<Piece>)>,
+    white_king_pos: Coordinates,
+    black_king_pos: Coordinates,
+    side_to_move: PieceColor,
+    en_passant: Option<Coordinates>,
+    halfmove_clock: u32,
+    full_move_number: u32,
+    hash: u64,
+}
+
+impl MoveRecord {
+    // the only piece of the opponent a move touches is the one it captures
+    pub fn captured(&self) -> Option<&Piece> {
+        self.previous_fields
+            .iter()
+            .filter_map(|(_, piece)| piece.as_ref())
+            .find(|piece| piece.piece_color != self.side_to_move)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Board {
+    pub fields: Vec<Vec<Field>>,
+    pub white_king_pos: Coordinates,
+    pub black_king_pos: Coordinates,
+    pub side_to_move: PieceColor,
+    pub en_passant: Option<Coordinates>,
+    pub(crate) halfmove_clock: u32,
+    pub(crate) full_move_number: u32,
+    // the Zobrist hash of the position, updated with every move
+    pub(crate) hash: u64,
+    // hashes of the positions reached so far, used to detect repetitions
+    position_history: Vec<u64>,
+}
+
+impl Board {
+    pub fn empty() -> Board {
+        let fields: Vec<Vec<Field>> = Vec::new();
+        Board {
+            fields,
+            white_king_pos: Coordinates { x: 5, y: 1 },
+            black_king_pos: Coordinates { x: 5, y: 8 },
+            side_to_move: PieceColor::White,
+            en_passant: None,
+            halfmove_clock: 0,
+            full_move_number: 1,
+            hash: 0,
+            position_history: Vec::new(),
+        }
+    }
+
+    pub fn print_board(&self) {
+        for i in 0..BOARD_SIZE {
+            for j in 0..BOARD_SIZE {
+                match &self.fields[i][j].piece {
+                    Some(piece) => print!("{}", piece),
+                    None => print!(" "),
+                }
+            }
+            println!();
+        }
+    }
+
+    fn placement_fen(&self) -> String {
+        let mut fen = String::new();
+        for i in (0..BOARD_SIZE).rev() {
+            let mut empty_fields = 0;
+            for j in 0..BOARD_SIZE {
+                match &self.fields[i][j].piece {
+                    Some(piece) => {
+                        if empty_fields > 0 {
+                            fen.push_str(&empty_fields.to_string());
+                            empty_fields = 0;
+                        }
+                        fen.push_str(&piece.to_fen());
+                    }
+                    None => empty_fields += 1,
+                }
+            }
+            if empty_fields > 0 {
+                fen.push_str(&empty_fields.to_string());
+            }
+            if i > 0 {
+                fen.push('/');
+            }
+        }
+        fen
+    }
+
+    fn position_fen(&self) -> String {
+        let side = match self.side_to_move {
+            PieceColor::White => "w",
+            PieceColor::Black => "b",
+        };
+        let en_passant = match self.en_passant {
+            Some(coords) => coords.to_algebraic(),
+            None => "-".to_string(),
+        };
+        format!(
+            "{} {} {} {}",
+            self.placement_fen(),
+            side,
+            self.castling_rights(),
+            en_passant
+        )
+    }
+
+    pub fn to_fen(&self) -> String {
+        format!(
+            "{} {} {}",
+            self.position_fen(),
+            self.halfmove_clock,
+            self.full_move_number
+        )
+    }
+
+    // castling availability in FEN notation, based on which kings and rooks haven't moved yet
+    fn castling_rights(&self) -> String {
+        let mut rights = String::new();
+        for (color, y, symbols) in [
+            (PieceColor::White, 1, ['K', 'Q']),
+            (PieceColor::Black, 8, ['k', 'q']),
+        ] {
+            let king_unmoved = self
+                .get_piece(Coordinates { x: 5, y })
+                .is_some_and(|piece| {
+                    piece.piece_color == color
+                        && piece.piece_type == (PieceType::King { moved: false })
+                });
+            if !king_unmoved {
+                continue;
+            }
+            for (rook_x, symbol) in [(8, symbols[0]), (1, symbols[1])] {
+                let rook_unmoved =
+                    self.get_piece(Coordinates { x: rook_x, y })
+                        .is_some_and(|piece| {
+                            piece.piece_color == color
+                                && piece.piece_type == (PieceType::Rook { moved: false })
+                        });
+                if rook_unmoved {
+                    rights.push(symbol);
+                }
+            }
+        }
+        if rights.is_empty() {
+            rights.push('-');
+        }
+        rights
+    }
+
+    // two positions are the same for the repetition rules if they have the same pieces
+    // on the same fields, the same side to move and the same castling and en passant rights,
+    // which is what the hash is made of
+    pub(crate) fn record_position(&mut self) {
+        self.position_history.push(self.hash);
+    }
+
+    pub fn hash(&self) -> u64 {
+        self.hash
+    }
+
+    pub(crate) fn position_history(&self) -> &[u64] {
+        &self.position_history
+    }
+
+    // the part of the hash made of the pieces on the given fields and the rest of the position
+    // state, so that a move only has to rehash the fields it touches
+    fn partial_hash(&self, fields: &[Coordinates]) -> u64 {
+        let mut hash = castling_key(self.castling_bits()) ^ self.en_passant_hash();
+        if self.side_to_move == PieceColor::Black {
+            hash ^= side_key();
+        }
+        for coords in fields {
+            if let Some(piece) = self.get_piece(*coords) {
+                hash ^= piece_key(
+                    color_index(piece.piece_color),
+                    kind_index(piece.piece_type),
+                    square_index(*coords),
+                );
+            }
+        }
+        hash
+    }
+
+    // like `Position`, hashes the en passant file only if a pawn of the side to move can
+    // capture there
+    fn en_passant_hash(&self) -> u64 {
+        let Some(target) = self.en_passant else {
+            return 0;
+        };
+        let pawn_y = match self.side_to_move {
+            PieceColor::White => target.y - 1,
+            PieceColor::Black => target.y + 1,
+        };
+        let can_capture = [target.x - 1, target.x + 1].into_iter().any(|x| {
+            self.get_piece(Coordinates { x, y: pawn_y })
+                .is_some_and(|pawn| {
+                    pawn.piece_color == self.side_to_move
+                        && matches!(pawn.piece_type, PieceType::Pawn { .. })
+                })
+        });
+        if can_capture {
+            en_passant_key(Some(square_index(target)))
+        } else {
+            0
+        }
+    }
+
+    fn repetition_count(&self) -> usize {
+        match self.position_history.last() {
+            Some(current) => self
+                .position_history
+                .iter()
+                .filter(|position| *position == current)
+                .count(),
+            None => 0,
+        }
+    }
+
+    // K vs K, K+B vs K, K+N vs K and any number of bishops all standing on the same color
+    pub fn insufficient_material(&self) -> bool {
+        let mut minor_pieces = Vec::new();
+        for row in &self.fields {
+            for field in row {
+                if let Some(piece) = &field.piece {
+                    match piece.piece_type {
+                        PieceType::King { .. } => {}
+                        PieceType::Bishop | PieceType::Knight => minor_pieces.push(field),
+                        _ => return false,
+                    }
+                }
+            }
+        }
+        if minor_pieces.len() <= 1 {
+            return true;
+        }
+        let all_bishops = minor_pieces.iter().all(|field| {
+            field
+                .piece
+                .as_ref()
+                .is_some_and(|piece| piece.piece_type == PieceType::Bishop)
+        });
+        all_bishops
+            && minor_pieces
+                .iter()
+                .all(|field| field.color == minor_pieces[0].color)
+    }
+
+    // whether the color can't checkmate by any series of legal moves, as FIDE rules it for
+    // flag falls: a lone king never can, and a king with a single bishop or knight only when
+    // the other king has pieces of its own that can block its way out
+    pub fn lacks_mating_material(&self, color: PieceColor) -> bool {
+        let mut minor_pieces = 0;
+        let mut opponent_pieces = 0;
+        for piece in self
+            .fields
+            .iter()
+            .flatten()
+            .filter_map(|field| field.piece.as_ref())
+        {
+            match piece.piece_type {
+                PieceType::King { .. } => {}
+                _ if piece.piece_color != color => opponent_pieces += 1,
+                PieceType::Bishop | PieceType::Knight => minor_pieces += 1,
+                _ => return false,
+            }
+        }
+        minor_pieces == 0 || (minor_pieces == 1 && opponent_pieces == 0)
+    }
+
+    // the result when the given color runs out of time
+    pub fn timeout_result(&self, flagged: PieceColor) -> GameResult {
+        let opponent = match flagged {
+            PieceColor::White => PieceColor::Black,
+            PieceColor::Black => PieceColor::White,
+        };
+        if self.insufficient_material() || self.lacks_mating_material(opponent) {
+            GameResult::draw(EndReason::TimeoutVsInsufficientMaterial)
+        } else {
+            GameResult::win(opponent, EndReason::Timeout)
+        }
+    }
+
+    // draws that a player may claim but which don't end the game on their own
+    pub fn claimable_draw(&self) -> Option<GameResult> {
+        if self.halfmove_clock >= 100 {
+            Some(GameResult::draw(EndReason::FiftyMoveRule))
+        } else if self.repetition_count() >= 3 {
+            Some(GameResult::draw(EndReason::ThreefoldRepetition))
+        } else {
+            None
+        }
+    }
+
+    // checks whether the game is over with the given color to move
+    pub fn game_result(&self, to_move: PieceColor) -> Option<GameResult> {
+        if self.no_possible_moves(to_move) {
+            if self.king_in_danger(to_move) {
+                let winner = match to_move {
+                    PieceColor::White => PieceColor::Black,
+                    PieceColor::Black => PieceColor::White,
+                };
+                return Some(GameResult::win(winner, EndReason::Checkmate));
+            }
+            return Some(GameResult::draw(EndReason::Stalemate));
+        }
+        if self.insufficient_material() {
+            Some(GameResult::draw(EndReason::InsufficientMaterial))
+        } else if self.halfmove_clock >= 150 {
+            Some(GameResult::draw(EndReason::SeventyFiveMoveRule))
+        } else if self.repetition_count() >= 5 {
+            Some(GameResult::draw(EndReason::FivefoldRepetition))
+        } else {
+            None
+        }
+    }
+
+    pub fn full_move_number(&self) -> u32 {
+        self.full_move_number
+    }
+
+    pub fn get_field(&self, coordinates: Coordinates) -> Option<&Field> {
+        if coordinates.x < 1 || coordinates.x > BOARD_SIZE as i32 {
+            return None;
+        }
+        if coordinates.y < 1 || coordinates.y > BOARD_SIZE as i32 {
+            return None;
+        }
+        Some(&self.fields[(coordinates.y - 1) as usize][(coordinates.x - 1) as usize])
+    }
+
+    pub fn get_field_mut(&mut self, coordinates: Coordinates) -> Option<&mut Field> {
+        if coordinates.x < 1 || coordinates.x > BOARD_SIZE as i32 {
+            return None;
+        }
+        if coordinates.y < 1 || coordinates.y > BOARD_SIZE as i32 {
+            return None;
+        }
+        Some(&mut self.fields[(coordinates.y - 1) as usize][(coordinates.x - 1) as usize])
+    }
+
+    pub fn get_piece(&self, coordinates: Coordinates) -> Option<&Piece> {
+        match self.get_field(coordinates) {
+            Some(field) => match &field.piece {
+                Some(piece) => Some(piece),
+                None => None,
+            },
+            None => None,
+        }
+    }
+
+    // the bitboards the moves are generated from
+    pub fn position(&self) -> Position {
+        Position::from_board(self)
+    }
+
+    pub fn field_in_danger(&self, my_color: PieceColor, coords: Coordinates) -> bool {
+        let opponent = match my_color {
+            PieceColor::White => PieceColor::Black,
+            PieceColor::Black => PieceColor::White,
+        };
+        self.position().is_attacked(square_index(coords), opponent)
+    }
+
+    pub fn king_in_danger(&self, my_color: PieceColor) -> bool {
+        self.position().in_check(my_color)
+    }
+
+    pub fn no_possible_moves(&self, my_color: PieceColor) -> bool {
+        self.legal_moves(my_color).is_empty()
+    }
+
+    pub fn is_check_after_move(
+        &self,
+        from: &Coordinates,
+        to: &Coordinates,
+        my_color: PieceColor,
+    ) -> bool {
+        // the move is tried on the bitboards and taken back; one the piece can't make at all
+        // is treated like a move into check
+        let mut position = self.position().with_side_to_move(my_color);
+        let Some(chess_move) = position
+            .pseudo_legal_moves()
+            .into_iter()
+            .find(|chess_move| chess_move.from == *from && chess_move.board_target() == *to)
+        else {
+            return true;
+        };
+        let unmake = position.make(&chess_move);
+        let in_check = position.in_check(my_color);
+        position.unmake(&chess_move, unmake);
+        in_check
+    }
+
+    pub fn remove_piece(&mut self, coordinates: Coordinates) -> Option<Piece> {
+        match self.get_field_mut(coordinates) {
+            Some(field) => {
+                let piece = field.piece.clone();
+                field.piece = None;
+                piece
+            }
+            None => None,
+        }
+    }
+
+    // if the move is an en passant capture, returns the field of the pawn
+    // that is being captured (it is not standing on the destination field)
+    pub fn en_passant_capture(&self, from: Coordinates, to: Coordinates) -> Option<Coordinates> {
+        let piece = self.get_piece(from)?;
+        if !matches!(piece.piece_type, PieceType::Pawn { .. }) {
+            return None;
+        }
+        if self.en_passant != Some(to) || from.x == to.x || self.get_piece(to).is_some() {
+            return None;
+        }
+        Some(Coordinates { x: to.x, y: from.y })
+    }
+
+    // castling is represented as the king moving onto its own rook
+    pub fn is_castling(&self, from: Coordinates, to: Coordinates) -> bool {
+        match (self.get_piece(from), self.get_piece(to)) {
+            (Some(king), Some(rook)) => {
+                king.piece_color == rook.piece_color
+                    && rook.piece_type == (PieceType::Rook { moved: false })
+                    && king.piece_type == (PieceType::King { moved: false })
+            }
+            _ => false,
+        }
+    }
+
+    // files the king and the rook end up on when the king castles with the rook on `to`
+    fn castling_files(to: Coordinates) -> Option<(i32, i32)> {
+        match to.x {
+            1 => Some((3, 4)),
+            8 => Some((7, 6)),
+            _ => None,
+        }
+    }
+
+    fn castling(&mut self, from: Coordinates, to: Coordinates) -> bool {
+        let Some((king_x, rook_x)) = Board::castling_files(to) else {
+            return false;
+        };
+        self.relocate_piece(
+            from,
+            Coordinates {
+                x: king_x,
+                y: from.y,
+            },
+            None,
+        ) && self.relocate_piece(to, Coordinates { x: rook_x, y: to.y }, None)
+    }
+
+    // pawns reaching the last rank are promoted to the given piece, or to a queen
+    // if none was chosen
+    pub fn move_piece(
+        &mut self,
+        from: Coordinates,
+        to: Coordinates,
+        promotion: Option<PieceType>,
+    ) -> bool {
+        let piece = match self.get_piece(from) {
+            Some(piece) => piece.clone(),
+            None => return false,
+        };
+        let touched = self.touched_fields(from, to);
+        let previous_hash = self.partial_hash(&touched);
+        let capture = self
+            .get_piece(to)
+            .is_some_and(|captured| captured.piece_color != piece.piece_color)
+            || self.en_passant_capture(from, to).is_some();
+
+        if !self.relocate_piece(from, to, promotion) {
+            return false;
+        }
+
+        if capture || matches!(piece.piece_type, PieceType::Pawn { .. }) {
+            self.halfmove_clock = 0;
+        } else {
+            self.halfmove_clock += 1;
+        }
+        self.side_to_move = match piece.piece_color {
+            PieceColor::White => PieceColor::Black,
+            PieceColor::Black => {
+                self.full_move_number += 1;
+                PieceColor::White
+            }
+        };
+        self.hash ^= previous_hash ^ self.partial_hash(&touched);
+        self.record_position();
+        true
+    }
+
+    // the fields whose pieces change when the move is made
+    fn touched_fields(&self, from: Coordinates, to: Coordinates) -> Vec<Coordinates> {
+        let mut touched = vec![from, to];
+        if self.is_castling(from, to) {
+            if let Some((king_x, rook_x)) = Board::castling_files(to) {
+                touched.push(Coordinates { x: king_x, y: to.y });
+                touched.push(Coordinates { x: rook_x, y: to.y });
+            }
+        } else if let Some(captured_coords) = self.en_passant_capture(from, to) {
+            touched.push(captured_coords);
+        }
+        touched
+    }
+
+    // makes the move like `move_piece` and returns what is needed to undo it
+    pub fn make_move(
+        &mut self,
+        from: Coordinates,
+        to: Coordinates,
+        promotion: Option<PieceType>,
+    ) -> Option<MoveRecord> {
+        let record = MoveRecord {
+            from,
+            to,
+            promotion,
+            previous_fields: self
+                .touched_fields(from, to)
+                .into_iter()
+                .map(|coords| (coords, self.get_piece(coords).cloned()))
+                .collect(),
+            white_king_pos: self.white_king_pos,
+            black_king_pos: self.black_king_pos,
+            side_to_move: self.side_to_move,
+            en_passant: self.en_passant,
+            halfmove_clock: self.halfmove_clock,
+            full_move_number: self.full_move_number,
+            hash: self.hash,
+        };
+        self.move_piece(from, to, promotion).then_some(record)
+    }
+
+    // takes back the last move made, which must be the one the record was made for
+    pub fn undo_move(&mut self, record: &MoveRecord) {
+        for (coords, piece) in &record.previous_fields {
+            if let Some(field) = self.get_field_mut(*coords) {
+                field.piece = piece.clone();
+            }
+        }
+        self.white_king_pos = record.white_king_pos;
+        self.black_king_pos = record.black_king_pos;
+        self.side_to_move = record.side_to_move;
+        self.en_passant = record.en_passant;
+        self.halfmove_clock = record.halfmove_clock;
+        self.full_move_number = record.full_move_number;
+        self.hash = record.hash;
+        self.position_history.pop();
+    }
+
+    fn relocate_piece(
+        &mut self,
+        from: Coordinates,
+        to: Coordinates,
+        promotion: Option<PieceType>,
+    ) -> bool {
+        if self.is_castling(from, to) {
+            return self.castling(from, to);
+        }
+
+        if let Some(captured_coords) = self.en_passant_capture(from, to) {
+            self.remove_piece(captured_coords);
+        }
+
+        let mut white_king_moved = false;
+        let mut black_king_moved = false;
+        let mut en_passant = None;
+        let mut ok = true;
+        let piece = self.remove_piece(from);
+        match piece {
+            Some(piece) => {
+                let field = self.get_field_mut(to);
+                match field {
+                    Some(field) => {
+                        let mut piece = piece;
+                        piece.coordinates = to;
+
+                        if piece.piece_type == (PieceType::Pawn { moved: false }) {
+                            piece.piece_type = PieceType::Pawn { moved: true };
+                            if (to.y - from.y).abs() == 2 {
+                                en_passant = Some(Coordinates {
+                                    x: from.x,
+                                    y: (from.y + to.y) / 2,
+                                });
+                            }
+                        }
+                        if piece.piece_type == (PieceType::Rook { moved: false }) {
+                            piece.piece_type = PieceType::Rook { moved: true };
+                        }
+
+                        if piece.piece_type == (PieceType::King { moved: false })
+                            || piece.piece_type == (PieceType::King { moved: true })
+                        {
+                            piece.piece_type = PieceType::King { moved: true };
+                            if piece.piece_color == PieceColor::White {
+                                white_king_moved = true;
+                            } else {
+                                black_king_moved = true;
+                            }
+                        }
+                        if piece.piece_type == (PieceType::Pawn { moved: true })
+                            && (piece.coordinates.y == 1 || piece.coordinates.y == 8)
+                        {
+                            piece.piece_type = promotion.unwrap_or(PieceType::Queen);
+                        }
+
+                        field.piece = Some(piece);
+                    }
+                    None => ok = false,
+                }
+            }
+            None => ok = false,
+        }
+        if white_king_moved {
+            self.white_king_pos = to;
+        }
+        if black_king_moved {
+            self.black_king_pos = to;
+        }
+        self.en_passant = en_passant;
+        ok
+    }
+
+    // fields the piece standing on the given field can legally move to
+    pub fn legal_moves_from(&self, from: Coordinates) -> Vec<Coordinates> {
+        match self.get_piece(from) {
+            Some(piece) => get_possible_moves(piece, self, true),
+            None => Vec::new(),
+        }
+    }
+
+    pub fn is_legal_move(&self, from: Coordinates, to: Coordinates) -> bool {
+        self.get_piece(from)
+            .is_some_and(|piece| piece.piece_color == self.side_to_move)
+            && self.legal_moves_from(from).contains(&to)
+    }
+
+    pub fn is_promotion(&self, from: Coordinates, to: Coordinates) -> bool {
+        match self.get_piece(from) {
+            Some(piece) => match piece.piece_type {
+                PieceType::Pawn { .. } => match piece.piece_color {
+                    PieceColor::White => to.y == BOARD_SIZE as i32,
+                    PieceColor::Black => to.y == 1,
+                },
+                _ => false,
+            },
+            None => false,
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use crate::board::{Board, MoveRecord};
+use crate::chess_pieces::*;
+use crate::coordinates::Coordinates;
+use crate::san::{parse_san, to_san};
+use std::fmt::{self, Display, Formatter};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Move {
+    pub from: Coordinates,
+    // for castling the field the king ends up on, e.g. g1
+    pub to: Coordinates,
+    pub promotion: Option<PieceType>,
+    pub capture: bool,
+    pub castle: bool,
+    pub en_passant: bool,
+    pub double_push: bool,
+}
+
+impl Move {
+    // the field `Board::move_piece` expects, which for castling is the field of the rook
+    pub fn board_target(&self) -> Coordinates {
+        if self.castle {
+            Coordinates {
+                x: if self.to.x > self.from.x { 8 } else { 1 },
+                y: self.from.y,
+            }
+        } else {
+            self.to
+        }
+    }
+
+    // e.g. "e2e4", "e1g1" or "e7e8q"
+    pub fn to_uci(&self) -> String {
+        let mut uci = self.from.to_algebraic() + &self.to.to_algebraic();
+        if let Some(c) = self
+            .promotion
+            .and_then(|piece_type| piece_type.promotion_char())
+        {
+            uci.push(c);
+        }
+        uci
+    }
+
+    // the legal move of the side to move given in UCI notation
+    pub fn from_uci(board: &Board, uci: &str) -> Option<Move> {
+        board
+            .legal_moves(board.side_to_move)
+            .into_iter()
+            .find(|legal_move| legal_move.to_uci() == uci)
+    }
+
+    // the board is the position before the move
+    pub fn to_san(&self, board: &Board) -> String {
+        to_san(board, self.from, self.board_target(), self.promotion)
+    }
+
+    pub fn from_san(board: &Board, san: &str) -> Option<Move> {
+        let (from, to, promotion) = parse_san(board, san)?;
+        Move::from_board_target(board, from, to, promotion)
+    }
+
+    // the legal move of the side to move given as the fields `Board::move_piece` takes;
+    // like there, a pawn promotes to a queen if no piece was chosen
+    pub fn from_board_target(
+        board: &Board,
+        from: Coordinates,
+        to: Coordinates,
+        promotion: Option<PieceType>,
+    ) -> Option<Move> {
+        let promotion = if board.is_promotion(from, to) {
+            promotion.or(Some(PieceType::Queen))
+        } else {
+            None
+        };
+        board
+            .legal_moves(board.side_to_move)
+            .into_iter()
+            .find(|legal_move| {
+                legal_move.from == from
+                    && legal_move.board_target() == to
+                    && legal_move.promotion == promotion
+            })
+    }
+}
+
+impl Display for Move {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_uci())
+    }
+}
+
+impl Board {
+    // all legal moves of the given color, promotions once for every piece they can promote to
+    pub fn legal_moves(&self, color: PieceColor) -> Vec<Move> {
+        self.position().with_side_to_move(color).legal_moves()
+    }
+
+    // makes the move and returns the record needed to take it back with `undo_move`
+    pub fn apply(&mut self, chess_move: Move) -> Option<MoveRecord> {
+        self.make_move(
+            chess_move.from,
+            chess_move.board_target(),
+            chess_move.promotion,
+        )
+    }
+}
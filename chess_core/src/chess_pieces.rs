@@ -1,5 +1,4 @@
 use crate::coordinates::*;
-use bevy::prelude::{Component, Entity};
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum PieceColor {
@@ -17,13 +16,53 @@ pub enum PieceType {
     Pawn { moved: bool },
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Component)]
+// pieces a pawn can be promoted to, in the order they are offered to the player
+pub const PROMOTION_PIECES: [PieceType; 4] = [
+    PieceType::Queen,
+    PieceType::Rook { moved: true },
+    PieceType::Bishop,
+    PieceType::Knight,
+];
+
+impl PieceType {
+    // parses the promotion suffix of a move in UCI notation, e.g. 'n' in "e7e8n"
+    pub fn from_promotion_char(c: char) -> Option<PieceType> {
+        match c.to_ascii_lowercase() {
+            'q' => Some(PieceType::Queen),
+            'r' => Some(PieceType::Rook { moved: true }),
+            'b' => Some(PieceType::Bishop),
+            'n' => Some(PieceType::Knight),
+            _ => None,
+        }
+    }
+
+    pub fn promotion_char(&self) -> Option<char> {
+        match self {
+            PieceType::Queen => Some('q'),
+            PieceType::Rook { .. } => Some('r'),
+            PieceType::Bishop => Some('b'),
+            PieceType::Knight => Some('n'),
+            _ => None,
+        }
+    }
+
+    pub fn get_value(&self) -> i32 {
+        match self {
+            PieceType::King { .. } => 1000,
+            PieceType::Queen => 9,
+            PieceType::Rook { .. } => 5,
+            PieceType::Bishop => 3,
+            PieceType::Knight => 3,
+            PieceType::Pawn { .. } => 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Piece {
-    pub entity: Option<Entity>,
     pub piece_type: PieceType,
     pub piece_color: PieceColor,
     pub coordinates: Coordinates,
-    pub border: bool,
 }
 
 impl core::fmt::Display for PieceColor {
@@ -69,11 +108,9 @@ impl core::fmt::Display for Piece {
 impl Piece {
     pub fn new(piece_type: PieceType, piece_color: PieceColor, coordinates: Coordinates) -> Self {
         Self {
-            entity: None,
             piece_type,
             piece_color,
             coordinates,
-            border: false,
         }
     }
 
@@ -101,23 +138,8 @@ impl Piece {
         }
     }
 
-    pub fn get_entity(&self) -> Option<Entity> {
-        self.entity
-    }
-
-    pub fn set_entity(&mut self, entity: Entity) {
-        self.entity = Some(entity);
-    }
-
     pub fn get_value(&self) -> i32 {
-        match self.piece_type {
-            PieceType::King { .. } => 1000,
-            PieceType::Queen => 9,
-            PieceType::Rook { .. } => 5,
-            PieceType::Bishop => 3,
-            PieceType::Knight => 3,
-            PieceType::Pawn { .. } => 1,
-        }
+        self.piece_type.get_value()
     }
 
     pub fn to_fen(&self) -> String {
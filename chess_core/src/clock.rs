@@ -0,0 +1,176 @@
+use crate::chess_pieces::PieceColor;
+use crate::engine::SearchLimits;
+use crate::position::color_index;
+use std::fmt::{self, Display, Formatter};
+use std::time::Duration;
+
+// what the second number of a time control means
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum IncrementMode {
+    // added to the clock after every move
+    #[default]
+    Fischer,
+    // the time spent on the move is added back, up to the increment
+    Bronstein,
+    // the clock only starts running once the delay has passed
+    SimpleDelay,
+}
+
+impl IncrementMode {
+    pub fn next(&self) -> IncrementMode {
+        match self {
+            IncrementMode::Fischer => IncrementMode::Bronstein,
+            IncrementMode::Bronstein => IncrementMode::SimpleDelay,
+            IncrementMode::SimpleDelay => IncrementMode::Fischer,
+        }
+    }
+}
+
+impl Display for IncrementMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            IncrementMode::Fischer => write!(f, "Fischer increment"),
+            IncrementMode::Bronstein => write!(f, "Bronstein delay"),
+            IncrementMode::SimpleDelay => write!(f, "simple delay"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TimeControl {
+    pub base: Duration,
+    pub increment: Duration,
+    pub mode: IncrementMode,
+}
+
+impl TimeControl {
+    pub const fn new(minutes: u64, increment_seconds: u64) -> TimeControl {
+        TimeControl {
+            base: Duration::from_secs(minutes * 60),
+            increment: Duration::from_secs(increment_seconds),
+            mode: IncrementMode::Fischer,
+        }
+    }
+
+    // the value of the PGN TimeControl tag, in seconds
+    pub fn pgn_tag(&self) -> String {
+        format!("{}+{}", self.base.as_secs(), self.increment.as_secs())
+    }
+}
+
+// minutes and seconds, e.g. "5+3", with the mode unless it is a Fischer increment
+impl Display for TimeControl {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}+{}",
+            self.base.as_secs_f64() / 60.0,
+            self.increment.as_secs_f64()
+        )?;
+        if self.mode != IncrementMode::Fischer && !self.increment.is_zero() {
+            write!(f, " {}", self.mode)?;
+        }
+        Ok(())
+    }
+}
+
+pub const TIME_CONTROL_PRESETS: [(&str, TimeControl); 4] = [
+    ("Bullet", TimeControl::new(1, 0)),
+    ("Blitz", TimeControl::new(5, 3)),
+    ("Rapid", TimeControl::new(15, 10)),
+    ("Classical", TimeControl::new(90, 30)),
+];
+
+// the time left to both sides; the clock of the side to move runs until it presses
+// the clock after its move
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChessClock {
+    pub control: TimeControl,
+    remaining: [Duration; 2],
+    running: Option<PieceColor>,
+    // how long the running side has been thinking about its move
+    thinking: Duration,
+}
+
+impl ChessClock {
+    pub fn new(control: TimeControl, to_move: PieceColor) -> ChessClock {
+        ChessClock {
+            control,
+            remaining: [control.base; 2],
+            running: Some(to_move),
+            thinking: Duration::ZERO,
+        }
+    }
+
+    pub fn remaining(&self, color: PieceColor) -> Duration {
+        self.remaining[color_index(color)]
+    }
+
+    pub fn running(&self) -> Option<PieceColor> {
+        self.running
+    }
+
+    pub fn tick(&mut self, elapsed: Duration) {
+        let Some(color) = self.running else {
+            return;
+        };
+        let delay = match self.control.mode {
+            IncrementMode::SimpleDelay => self.control.increment,
+            _ => Duration::ZERO,
+        };
+        let counted_before = self.thinking.saturating_sub(delay);
+        self.thinking += elapsed;
+        let counted = self.thinking.saturating_sub(delay) - counted_before;
+        let remaining = &mut self.remaining[color_index(color)];
+        *remaining = remaining.saturating_sub(counted);
+    }
+
+    // ends the move of the running side and starts the clock of its opponent
+    pub fn press(&mut self) {
+        let Some(color) = self.running else {
+            return;
+        };
+        let remaining = &mut self.remaining[color_index(color)];
+        // time that has run out isn't given back
+        if !remaining.is_zero() {
+            *remaining += match self.control.mode {
+                IncrementMode::Fischer => self.control.increment,
+                IncrementMode::Bronstein => self.control.increment.min(self.thinking),
+                IncrementMode::SimpleDelay => Duration::ZERO,
+            };
+        }
+        let opponent = match color {
+            PieceColor::White => PieceColor::Black,
+            PieceColor::Black => PieceColor::White,
+        };
+        self.start(opponent);
+    }
+
+    // starts the move of the given side, e.g. after moves were taken back
+    pub fn start(&mut self, color: PieceColor) {
+        self.running = Some(color);
+        self.thinking = Duration::ZERO;
+    }
+
+    pub fn stop(&mut self) {
+        self.running = None;
+    }
+
+    // the side to move, once it ran out of time
+    pub fn flagged(&self) -> Option<PieceColor> {
+        self.running
+            .filter(|color| self.remaining(*color).is_zero())
+    }
+
+    // UCI only knows Fischer increments; the delays are passed as increments as well, as
+    // with them a move that takes no longer than the increment doesn't cost any time either
+    pub fn search_limits(&self, limits: SearchLimits) -> SearchLimits {
+        SearchLimits {
+            wtime: Some(self.remaining(PieceColor::White)),
+            btime: Some(self.remaining(PieceColor::Black)),
+            winc: Some(self.control.increment),
+            binc: Some(self.control.increment),
+            ..limits
+        }
+    }
+}
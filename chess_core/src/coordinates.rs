@@ -1,10 +1,8 @@
 use crate::BOARD_SIZE;
-use crate::FIELD_SIZE;
-use bevy::prelude::*;
 use std::fmt::{self, Display, Formatter};
 use std::ops::Add;
 
-#[derive(Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Component)]
+#[derive(Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
 pub struct Coordinates {
     pub x: i32,
     pub y: i32,
@@ -14,6 +12,25 @@ impl Coordinates {
     pub fn in_board_bounds(&self) -> bool {
         self.x > 0 && self.y > 0 && self.x <= BOARD_SIZE as i32 && self.y <= BOARD_SIZE as i32
     }
+
+    // field name in algebraic notation, e.g. "e4"
+    pub fn to_algebraic(&self) -> String {
+        format!("{}{}", (b'a' + (self.x - 1) as u8) as char, self.y)
+    }
+
+    pub fn from_algebraic(square: &str) -> Option<Coordinates> {
+        let mut chars = square.chars();
+        let file = chars.next()?;
+        let rank = chars.next()?.to_digit(10)? as i32;
+        if chars.next().is_some() || !('a'..='h').contains(&file) {
+            return None;
+        }
+        let coords = Coordinates {
+            x: (file as u8 - b'a') as i32 + 1,
+            y: rank,
+        };
+        coords.in_board_bounds().then_some(coords)
+    }
 }
 
 impl Add for Coordinates {
@@ -32,12 +49,3 @@ impl Display for Coordinates {
         write!(f, "({}, {})", self.x, self.y)
     }
 }
-
-pub fn mouse_pos_to_coordinates(x: f32, y: f32, width: f32, height: f32) -> Coordinates {
-    let left_down_x = ((width as f32) / 2.0) - ((BOARD_SIZE as f32 * FIELD_SIZE) / 2.0);
-    let left_down_y = ((height as f32) / 2.0) - ((BOARD_SIZE as f32 * FIELD_SIZE) / 2.0);
-    Coordinates {
-        x: ((x - left_down_x) / FIELD_SIZE as f32).floor() as i32 + 1,
-        y: ((y - left_down_y) / FIELD_SIZE as f32).floor() as i32 + 1,
-    }
-}
@@ -0,0 +1,141 @@
+use crate::bitboard::squares;
+use crate::position::*;
+
+// bonuses for standing on a square, written from white's side with the eighth rank first
+// like a diagram, so white pieces look their square up mirrored
+#[rustfmt::skip]
+const PAWN_TABLE: [i32; 64] = [
+     0,  0,  0,  0,  0,  0,  0,  0,
+    50, 50, 50, 50, 50, 50, 50, 50,
+    10, 10, 20, 30, 30, 20, 10, 10,
+     5,  5, 10, 25, 25, 10,  5,  5,
+     0,  0,  0, 20, 20,  0,  0,  0,
+     5, -5,-10,  0,  0,-10, -5,  5,
+     5, 10, 10,-20,-20, 10, 10,  5,
+     0,  0,  0,  0,  0,  0,  0,  0,
+];
+
+#[rustfmt::skip]
+const KNIGHT_TABLE: [i32; 64] = [
+    -50,-40,-30,-30,-30,-30,-40,-50,
+    -40,-20,  0,  0,  0,  0,-20,-40,
+    -30,  0, 10, 15, 15, 10,  0,-30,
+    -30,  5, 15, 20, 20, 15,  5,-30,
+    -30,  0, 15, 20, 20, 15,  0,-30,
+    -30,  5, 10, 15, 15, 10,  5,-30,
+    -40,-20,  0,  5,  5,  0,-20,-40,
+    -50,-40,-30,-30,-30,-30,-40,-50,
+];
+
+#[rustfmt::skip]
+const BISHOP_TABLE: [i32; 64] = [
+    -20,-10,-10,-10,-10,-10,-10,-20,
+    -10,  0,  0,  0,  0,  0,  0,-10,
+    -10,  0,  5, 10, 10,  5,  0,-10,
+    -10,  5,  5, 10, 10,  5,  5,-10,
+    -10,  0, 10, 10, 10, 10,  0,-10,
+    -10, 10, 10, 10, 10, 10, 10,-10,
+    -10,  5,  0,  0,  0,  0,  5,-10,
+    -20,-10,-10,-10,-10,-10,-10,-20,
+];
+
+#[rustfmt::skip]
+const ROOK_TABLE: [i32; 64] = [
+     0,  0,  0,  0,  0,  0,  0,  0,
+     5, 10, 10, 10, 10, 10, 10,  5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+     0,  0,  0,  5,  5,  0,  0,  0,
+];
+
+#[rustfmt::skip]
+const QUEEN_TABLE: [i32; 64] = [
+    -20,-10,-10, -5, -5,-10,-10,-20,
+    -10,  0,  0,  0,  0,  0,  0,-10,
+    -10,  0,  5,  5,  5,  5,  0,-10,
+     -5,  0,  5,  5,  5,  5,  0, -5,
+      0,  0,  5,  5,  5,  5,  0, -5,
+    -10,  5,  5,  5,  5,  5,  0,-10,
+    -10,  0,  5,  0,  0,  0,  0,-10,
+    -20,-10,-10, -5, -5,-10,-10,-20,
+];
+
+// the king hides behind its pawns while there are pieces left to attack it...
+#[rustfmt::skip]
+const KING_MIDDLEGAME_TABLE: [i32; 64] = [
+    -30,-40,-40,-50,-50,-40,-40,-30,
+    -30,-40,-40,-50,-50,-40,-40,-30,
+    -30,-40,-40,-50,-50,-40,-40,-30,
+    -30,-40,-40,-50,-50,-40,-40,-30,
+    -20,-30,-30,-40,-40,-30,-30,-20,
+    -10,-20,-20,-20,-20,-20,-20,-10,
+     20, 20,  0,  0,  0,  0, 20, 20,
+     20, 30, 10,  0,  0, 10, 30, 20,
+];
+
+// ...and walks to the center once they are traded off
+#[rustfmt::skip]
+const KING_ENDGAME_TABLE: [i32; 64] = [
+    -50,-40,-30,-20,-20,-30,-40,-50,
+    -30,-20,-10,  0,  0,-10,-20,-30,
+    -30,-10, 20, 30, 30, 20,-10,-30,
+    -30,-10, 30, 40, 40, 30,-10,-30,
+    -30,-10, 30, 40, 40, 30,-10,-30,
+    -30,-10, 20, 30, 30, 20,-10,-30,
+    -30,-30,  0,  0,  0,  0,-30,-30,
+    -50,-30,-30,-30,-30,-30,-30,-50,
+];
+
+// how much each kind of piece counts towards the middlegame, all of them together make 24
+const PHASE_WEIGHTS: [i32; 6] = [0, 4, 2, 1, 1, 0];
+const FULL_PHASE: i32 = 24;
+
+// in centipawns, based on the values of the pieces in pawns
+pub(crate) fn piece_value(kind: usize) -> i32 {
+    kind_piece_type(kind).get_value() * 100
+}
+
+fn square_table(kind: usize) -> &'static [i32; 64] {
+    match kind {
+        QUEEN => &QUEEN_TABLE,
+        ROOK => &ROOK_TABLE,
+        BISHOP => &BISHOP_TABLE,
+        KNIGHT => &KNIGHT_TABLE,
+        _ => &PAWN_TABLE,
+    }
+}
+
+// the score of the position in centipawns from the point of view of the side to move
+pub(crate) fn evaluate(position: &Position) -> i32 {
+    let mut score = 0;
+    let mut king_middlegame = 0;
+    let mut king_endgame = 0;
+    let mut phase = 0;
+    for color in 0..2 {
+        let sign = if color == 0 { 1 } else { -1 };
+        // the tables are written with the eighth rank first
+        let table_square = |square: usize| if color == 0 { square ^ 56 } else { square };
+        for kind in [QUEEN, ROOK, BISHOP, KNIGHT, PAWN] {
+            let table = square_table(kind);
+            for square in squares(position.pieces(color, kind)) {
+                score += sign * (piece_value(kind) + table[table_square(square)]);
+                phase += PHASE_WEIGHTS[kind];
+            }
+        }
+        for square in squares(position.pieces(color, KING)) {
+            king_middlegame += sign * KING_MIDDLEGAME_TABLE[table_square(square)];
+            king_endgame += sign * KING_ENDGAME_TABLE[table_square(square)];
+        }
+    }
+    let phase = phase.min(FULL_PHASE);
+    score += (king_middlegame * phase + king_endgame * (FULL_PHASE - phase)) / FULL_PHASE;
+
+    if color_index(position.side_to_move) == 0 {
+        score
+    } else {
+        -score
+    }
+}
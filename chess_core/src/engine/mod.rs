@@ -0,0 +1,313 @@
+// a chess engine of our own, so that the game can be played against the computer without
+// installing one: iterative deepening alpha-beta search with quiescence search, a transposition
+// table and move ordering, on top of a material and piece-square-table evaluation
+mod evaluation;
+mod ordering;
+mod transposition;
+
+use crate::board::Board;
+use crate::chess_move::Move;
+use crate::chess_pieces::PieceColor;
+use crate::position::Position;
+use evaluation::evaluate;
+use ordering::MoveOrdering;
+use std::time::{Duration, Instant};
+use transposition::{Bound, TranspositionTable};
+
+// the score of being mated right now, mates further away score less
+pub const MATE_SCORE: i32 = 30_000;
+const INFINITY: i32 = 32_000;
+pub(crate) const MAX_PLY: usize = 64;
+const DEFAULT_TABLE_SIZE: usize = 16;
+// how often the limits are checked
+const NODES_BETWEEN_CHECKS: u64 = 1024;
+// with a clock, the time for a move is this part of the time left plus half the increment
+const MOVES_TO_GO: u32 = 30;
+
+// when to stop searching, the same limits as in the UCI `go` command; without any limit
+// the search goes on to the maximum depth
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SearchLimits {
+    pub depth: Option<u32>,
+    pub movetime: Option<Duration>,
+    pub nodes: Option<u64>,
+    // time left on the clocks and the increments per move
+    pub wtime: Option<Duration>,
+    pub btime: Option<Duration>,
+    pub winc: Option<Duration>,
+    pub binc: Option<Duration>,
+}
+
+impl SearchLimits {
+    // the time the side to move may spend on the move
+    pub fn time_for_move(&self, side_to_move: PieceColor) -> Option<Duration> {
+        let (time, increment) = match side_to_move {
+            PieceColor::White => (self.wtime, self.winc),
+            PieceColor::Black => (self.btime, self.binc),
+        };
+        let from_clock = time.map(|time| {
+            let increment = increment.unwrap_or_default();
+            (time / MOVES_TO_GO + increment / 2).min(time / 2)
+        });
+        match (self.movetime, from_clock) {
+            (Some(movetime), Some(from_clock)) => Some(movetime.min(from_clock)),
+            (movetime, from_clock) => movetime.or(from_clock),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SearchResult {
+    // none only if there are no legal moves
+    pub best_move: Option<Move>,
+    // in centipawns from the point of view of the side to move
+    pub score: i32,
+    pub depth: u32,
+    pub nodes: u64,
+}
+
+pub struct Engine {
+    table: TranspositionTable,
+    ordering: MoveOrdering,
+    limits: SearchLimits,
+    time_for_move: Option<Duration>,
+    start: Instant,
+    nodes: u64,
+    stopped: bool,
+    // hashes of the positions before the current one, in the game and in the searched line
+    hashes: Vec<u64>,
+    root_best_move: Option<Move>,
+}
+
+impl Default for Engine {
+    fn default() -> Self {
+        Engine::new()
+    }
+}
+
+impl Engine {
+    pub fn new() -> Engine {
+        Engine::with_table_size(DEFAULT_TABLE_SIZE)
+    }
+
+    // the size of the transposition table in megabytes
+    pub fn with_table_size(megabytes: usize) -> Engine {
+        Engine {
+            table: TranspositionTable::new(megabytes),
+            ordering: MoveOrdering::new(),
+            limits: SearchLimits::default(),
+            time_for_move: None,
+            start: Instant::now(),
+            nodes: 0,
+            stopped: false,
+            hashes: Vec::new(),
+            root_best_move: None,
+        }
+    }
+
+    // forgets everything learned in the previous game
+    pub fn new_game(&mut self) {
+        self.table.clear();
+        self.ordering.clear();
+    }
+
+    // searches deeper and deeper until a limit is reached and returns the result of the
+    // deepest search that was finished
+    pub fn search(&mut self, board: &Board, limits: SearchLimits) -> SearchResult {
+        let mut position = board.position();
+        let moves = position.legal_moves();
+        let mut result = SearchResult {
+            best_move: moves.first().copied(),
+            score: 0,
+            depth: 0,
+            nodes: 0,
+        };
+        if moves.len() <= 1 {
+            return result;
+        }
+
+        self.limits = limits;
+        self.time_for_move = limits.time_for_move(board.side_to_move);
+        self.start = Instant::now();
+        self.nodes = 0;
+        self.stopped = false;
+        self.hashes = board.position_history().to_vec();
+        // the current position is the last one in the history
+        self.hashes.pop();
+        self.ordering.new_search();
+
+        let max_depth = limits.depth.unwrap_or(MAX_PLY as u32).min(MAX_PLY as u32);
+        for depth in 1..=max_depth {
+            self.root_best_move = None;
+            let score = self.alpha_beta(&mut position, depth, 0, -INFINITY, INFINITY);
+            if self.stopped {
+                break;
+            }
+            result = SearchResult {
+                best_move: self.root_best_move.or(result.best_move),
+                score,
+                depth,
+                nodes: self.nodes,
+            };
+            if score.abs() >= MATE_SCORE - MAX_PLY as i32 || self.out_of_time(2) {
+                break;
+            }
+        }
+        result.nodes = self.nodes;
+        result
+    }
+
+    // whether more than the given fraction of the time for the move was used
+    fn out_of_time(&self, fraction: u32) -> bool {
+        self.time_for_move
+            .is_some_and(|time| self.start.elapsed() >= time / fraction)
+    }
+
+    fn check_limits(&mut self) {
+        if self.nodes.is_multiple_of(NODES_BETWEEN_CHECKS)
+            && (self.out_of_time(1) || self.limits.nodes.is_some_and(|nodes| self.nodes >= nodes))
+        {
+            self.stopped = true;
+        }
+    }
+
+    fn alpha_beta(
+        &mut self,
+        position: &mut Position,
+        depth: u32,
+        ply: usize,
+        mut alpha: i32,
+        beta: i32,
+    ) -> i32 {
+        self.nodes += 1;
+        self.check_limits();
+        if self.stopped {
+            return 0;
+        }
+        let hash = position.hash();
+        if ply > 0 && self.hashes.contains(&hash) {
+            return 0;
+        }
+        if ply >= MAX_PLY {
+            return evaluate(position);
+        }
+
+        let in_check = position.in_check(position.side_to_move);
+        // checks are searched one move deeper, so that they aren't cut off just before the mate
+        let depth = if in_check { depth + 1 } else { depth };
+        if depth == 0 {
+            return self.quiescence(position, ply, alpha, beta);
+        }
+
+        let entry = self.table.probe(hash, ply);
+        if let Some(entry) = entry.filter(|entry| ply > 0 && entry.depth >= depth) {
+            let cutoff = match entry.bound {
+                Bound::Exact => true,
+                Bound::Lower => entry.score >= beta,
+                Bound::Upper => entry.score <= alpha,
+            };
+            if cutoff {
+                return entry.score;
+            }
+        }
+
+        let mut moves = position.legal_moves();
+        if moves.is_empty() {
+            return if in_check {
+                -MATE_SCORE + ply as i32
+            } else {
+                0
+            };
+        }
+        self.ordering.sort(
+            position,
+            &mut moves,
+            entry.and_then(|entry| entry.best_move),
+            ply,
+        );
+
+        let mut best_score = -INFINITY;
+        let mut best_move = None;
+        let mut bound = Bound::Upper;
+        self.hashes.push(hash);
+        for chess_move in moves {
+            let unmake = position.make(&chess_move);
+            let score = -self.alpha_beta(position, depth - 1, ply + 1, -beta, -alpha);
+            position.unmake(&chess_move, unmake);
+            if self.stopped {
+                break;
+            }
+
+            if score > best_score {
+                best_score = score;
+                best_move = Some(chess_move);
+                if ply == 0 {
+                    self.root_best_move = best_move;
+                }
+            }
+            if score > alpha {
+                alpha = score;
+                bound = Bound::Exact;
+            }
+            if alpha >= beta {
+                bound = Bound::Lower;
+                if !chess_move.capture && chess_move.promotion.is_none() {
+                    self.ordering.add_cutoff(position, chess_move, depth, ply);
+                }
+                break;
+            }
+        }
+        self.hashes.pop();
+
+        if self.stopped {
+            return 0;
+        }
+        self.table
+            .store(hash, depth, best_score, bound, best_move, ply);
+        best_score
+    }
+
+    // searches captures and promotions only until the position is quiet, so that the
+    // evaluation isn't taken in the middle of an exchange
+    fn quiescence(
+        &mut self,
+        position: &mut Position,
+        ply: usize,
+        mut alpha: i32,
+        beta: i32,
+    ) -> i32 {
+        self.nodes += 1;
+        self.check_limits();
+        if self.stopped {
+            return 0;
+        }
+
+        // the side to move doesn't have to capture, so it gets at least the current evaluation
+        let stand_pat = evaluate(position);
+        if stand_pat >= beta || ply >= MAX_PLY {
+            return stand_pat;
+        }
+        alpha = alpha.max(stand_pat);
+
+        let mut moves: Vec<Move> = position
+            .legal_moves()
+            .into_iter()
+            .filter(|chess_move| chess_move.capture || chess_move.promotion.is_some())
+            .collect();
+        self.ordering.sort(position, &mut moves, None, ply);
+
+        for chess_move in moves {
+            let unmake = position.make(&chess_move);
+            let score = -self.quiescence(position, ply + 1, -beta, -alpha);
+            position.unmake(&chess_move, unmake);
+            if self.stopped {
+                return 0;
+            }
+            if score >= beta {
+                return score;
+            }
+            alpha = alpha.max(score);
+        }
+        alpha
+    }
+}
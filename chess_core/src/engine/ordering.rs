@@ -0,0 +1,112 @@
+use crate::bitboard::square_index;
+use crate::chess_move::Move;
+use crate::engine::evaluation::piece_value;
+use crate::engine::MAX_PLY;
+use crate::position::*;
+
+const TABLE_MOVE_SCORE: i32 = 2_000_000;
+const CAPTURE_SCORE: i32 = 1_000_000;
+const KILLER_SCORES: [i32; 2] = [900_000, 800_000];
+// history scores are halved before they could reach the killers
+const HISTORY_LIMIT: i32 = 400_000;
+
+// what the search learned about which moves are good, used to try them first:
+// quiet moves that caused a cutoff at the same depth (killers) and in the whole search (history)
+pub(crate) struct MoveOrdering {
+    killers: [[Option<Move>; 2]; MAX_PLY],
+    history: [[[i32; 64]; 64]; 2],
+}
+
+impl MoveOrdering {
+    pub fn new() -> MoveOrdering {
+        MoveOrdering {
+            killers: [[None; 2]; MAX_PLY],
+            history: [[[0; 64]; 64]; 2],
+        }
+    }
+
+    pub fn clear(&mut self) {
+        *self = MoveOrdering::new();
+    }
+
+    // killers only make sense within one search, the history is kept but counts less
+    pub fn new_search(&mut self) {
+        self.killers = [[None; 2]; MAX_PLY];
+        self.history
+            .iter_mut()
+            .flatten()
+            .flatten()
+            .for_each(|score| *score /= 8);
+    }
+
+    pub fn add_cutoff(&mut self, position: &Position, chess_move: Move, depth: u32, ply: usize) {
+        if self.killers[ply][0] != Some(chess_move) {
+            self.killers[ply][1] = self.killers[ply][0];
+            self.killers[ply][0] = Some(chess_move);
+        }
+        let color = color_index(position.side_to_move);
+        let score =
+            &mut self.history[color][square_index(chess_move.from)][square_index(chess_move.to)];
+        *score += (depth * depth) as i32;
+        if *score > HISTORY_LIMIT {
+            self.history
+                .iter_mut()
+                .flatten()
+                .flatten()
+                .for_each(|score| *score /= 2);
+        }
+    }
+
+    // captures by most valuable victim and least valuable attacker (MVV-LVA), then killers,
+    // then the rest by history
+    fn score(
+        &self,
+        position: &Position,
+        chess_move: &Move,
+        table_move: Option<Move>,
+        ply: usize,
+    ) -> i32 {
+        if table_move == Some(*chess_move) {
+            return TABLE_MOVE_SCORE;
+        }
+        let us = color_index(position.side_to_move);
+        let from = square_index(chess_move.from);
+        let to = square_index(chess_move.to);
+        let promotion = chess_move
+            .promotion
+            .map_or(0, |piece_type| piece_value(kind_index(piece_type)));
+        if chess_move.capture || promotion > 0 {
+            let victim = if chess_move.en_passant {
+                PAWN
+            } else {
+                position.kind_at(1 - us, to).unwrap_or(PAWN)
+            };
+            let attacker = position.kind_at(us, from).unwrap_or(PAWN);
+            let victim_value = if chess_move.capture {
+                piece_value(victim)
+            } else {
+                0
+            };
+            return CAPTURE_SCORE + (victim_value + promotion) * 100 - piece_value(attacker) / 100;
+        }
+        if let Some(killer) = self.killers[ply]
+            .iter()
+            .position(|killer| *killer == Some(*chess_move))
+        {
+            return KILLER_SCORES[killer];
+        }
+        self.history[us][from][to]
+    }
+
+    pub fn sort(
+        &self,
+        position: &Position,
+        moves: &mut [Move],
+        table_move: Option<Move>,
+        ply: usize,
+    ) {
+        moves.sort_by_cached_key(|chess_move| {
+            std::cmp::Reverse(self.score(position, chess_move, table_move, ply))
+        });
+    }
+}
@@ -0,0 +1,102 @@
+use crate::chess_move::Move;
+use crate::engine::MATE_SCORE;
+use crate::engine::MAX_PLY;
+
+// whether the stored score is exact or only a bound because of an alpha-beta cutoff
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Bound {
+    Exact,
+    Lower,
+    Upper,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Entry {
+    hash: u64,
+    pub depth: u32,
+    pub score: i32,
+    pub bound: Bound,
+    pub best_move: Option<Move>,
+}
+
+// results of searches indexed by the Zobrist hash of the position, so that positions reached
+// by different move orders are searched only once
+pub(crate) struct TranspositionTable {
+    entries: Vec<Option<Entry>>,
+}
+
+// mate scores are stored relative to the position instead of the root of the search
+fn score_to_table(score: i32, ply: usize) -> i32 {
+    if score >= MATE_SCORE - MAX_PLY as i32 {
+        score + ply as i32
+    } else if score <= -MATE_SCORE + MAX_PLY as i32 {
+        score - ply as i32
+    } else {
+        score
+    }
+}
+
+fn score_from_table(score: i32, ply: usize) -> i32 {
+    if score >= MATE_SCORE - MAX_PLY as i32 {
+        score - ply as i32
+    } else if score <= -MATE_SCORE + MAX_PLY as i32 {
+        score + ply as i32
+    } else {
+        score
+    }
+}
+
+impl TranspositionTable {
+    pub fn new(megabytes: usize) -> TranspositionTable {
+        let count = megabytes * 1024 * 1024 / std::mem::size_of::<Option<Entry>>();
+        // a power of two, so that the index is just the low bits of the hash
+        let count = if count == 0 {
+            1
+        } else {
+            1 << (usize::BITS - 1 - count.leading_zeros())
+        };
+        TranspositionTable {
+            entries: vec![None; count],
+        }
+    }
+
+    fn index(&self, hash: u64) -> usize {
+        hash as usize & (self.entries.len() - 1)
+    }
+
+    pub fn probe(&self, hash: u64, ply: usize) -> Option<Entry> {
+        self.entries[self.index(hash)]
+            .filter(|entry| entry.hash == hash)
+            .map(|entry| Entry {
+                score: score_from_table(entry.score, ply),
+                ..entry
+            })
+    }
+
+    // a deeper search of the same position is kept, anything else is replaced
+    pub fn store(
+        &mut self,
+        hash: u64,
+        depth: u32,
+        score: i32,
+        bound: Bound,
+        best_move: Option<Move>,
+        ply: usize,
+    ) {
+        let index = self.index(hash);
+        if self.entries[index].is_some_and(|entry| entry.hash == hash && entry.depth > depth) {
+            return;
+        }
+        self.entries[index] = Some(Entry {
+            hash,
+            depth,
+            score: score_to_table(score, ply),
+            bound,
+            best_move,
+        });
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.iter_mut().for_each(|entry| *entry = None);
+    }
+}
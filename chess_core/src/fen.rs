@@ -0,0 +1,310 @@
+use crate::board::Board;
+use crate::chess_pieces::*;
+use crate::coordinates::Coordinates;
+use crate::field::*;
+use crate::BOARD_SIZE;
+use std::fmt::{self, Display, Formatter};
+
+pub const STARTING_FEN: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FenError {
+    MissingField(&'static str),
+    TooManyFields(usize),
+    WrongRankCount(usize),
+    WrongRankLength { rank: i32, length: i32 },
+    InvalidPiece { rank: i32, symbol: char },
+    PawnOnBackRank(Coordinates),
+    WrongKingCount { color: PieceColor, count: usize },
+    InvalidSideToMove(String),
+    InvalidCastling(String),
+    InvalidEnPassant(String),
+    InvalidHalfmoveClock(String),
+    InvalidFullMoveNumber(String),
+    OpponentInCheck,
+}
+
+impl Display for FenError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            FenError::MissingField(field) => write!(f, "missing {} field", field),
+            FenError::TooManyFields(count) => {
+                write!(f, "expected at most 6 fields, found {}", count)
+            }
+            FenError::WrongRankCount(count) => write!(f, "expected 8 ranks, found {}", count),
+            FenError::WrongRankLength { rank, length } => {
+                write!(f, "rank {} describes {} fields instead of 8", rank, length)
+            }
+            FenError::InvalidPiece { rank, symbol } => {
+                write!(f, "invalid piece '{}' on rank {}", symbol, rank)
+            }
+            FenError::PawnOnBackRank(coords) => {
+                write!(
+                    f,
+                    "pawn on {} can't stand on the back rank",
+                    coords.to_algebraic()
+                )
+            }
+            FenError::WrongKingCount { color, count } => {
+                write!(f, "{} has {} kings instead of one", color, count)
+            }
+            FenError::InvalidSideToMove(side) => {
+                write!(f, "invalid side to move '{}', expected 'w' or 'b'", side)
+            }
+            FenError::InvalidCastling(castling) => {
+                write!(f, "invalid castling availability '{}'", castling)
+            }
+            FenError::InvalidEnPassant(square) => {
+                write!(f, "invalid en passant target '{}'", square)
+            }
+            FenError::InvalidHalfmoveClock(clock) => {
+                write!(f, "invalid halfmove clock '{}'", clock)
+            }
+            FenError::InvalidFullMoveNumber(number) => {
+                write!(f, "invalid full move number '{}'", number)
+            }
+            FenError::OpponentInCheck => write!(f, "the side not to move is in check"),
+        }
+    }
+}
+
+impl std::error::Error for FenError {}
+
+fn piece_type_from_symbol(symbol: char) -> Option<PieceType> {
+    match symbol.to_ascii_lowercase() {
+        'k' => Some(PieceType::King { moved: true }),
+        'q' => Some(PieceType::Queen),
+        'r' => Some(PieceType::Rook { moved: true }),
+        'b' => Some(PieceType::Bishop),
+        'n' => Some(PieceType::Knight),
+        'p' => Some(PieceType::Pawn { moved: true }),
+        _ => None,
+    }
+}
+
+fn parse_placement(placement: &str) -> Result<Vec<Vec<Field>>, FenError> {
+    let ranks: Vec<&str> = placement.split('/').collect();
+    if ranks.len() != BOARD_SIZE {
+        return Err(FenError::WrongRankCount(ranks.len()));
+    }
+
+    let mut fields: Vec<Vec<Field>> = Vec::new();
+    // FEN lists the ranks from the 8th down to the 1st
+    for (i, rank_str) in ranks.iter().rev().enumerate() {
+        let y = (i + 1) as i32;
+        let mut row: Vec<Field> = Vec::new();
+        for symbol in rank_str.chars() {
+            if let Some(empty) = symbol.to_digit(10) {
+                if empty == 0 || empty as usize > BOARD_SIZE {
+                    return Err(FenError::InvalidPiece { rank: y, symbol });
+                }
+                for _ in 0..empty {
+                    row.push(empty_field(row.len() as i32 + 1, y));
+                }
+                continue;
+            }
+
+            let piece_type =
+                piece_type_from_symbol(symbol).ok_or(FenError::InvalidPiece { rank: y, symbol })?;
+            let piece_color = if symbol.is_ascii_uppercase() {
+                PieceColor::White
+            } else {
+                PieceColor::Black
+            };
+            let mut field = empty_field(row.len() as i32 + 1, y);
+            field.piece = Some(Piece::new(piece_type, piece_color, field.coordinates));
+            row.push(field);
+        }
+        if row.len() != BOARD_SIZE {
+            return Err(FenError::WrongRankLength {
+                rank: y,
+                length: row.len() as i32,
+            });
+        }
+        fields.push(row);
+    }
+    Ok(fields)
+}
+
+fn empty_field(x: i32, y: i32) -> Field {
+    let color = if (x + y) % 2 == 0 {
+        FieldColor::Black
+    } else {
+        FieldColor::White
+    };
+    Field::new(Coordinates { x, y }, color, None)
+}
+
+// marks the king and rooks that are still allowed to castle as not moved
+fn apply_castling(board: &mut Board, castling: &str) -> Result<(), FenError> {
+    if castling == "-" {
+        return Ok(());
+    }
+    let error = || FenError::InvalidCastling(castling.to_string());
+    for (index, symbol) in castling.char_indices() {
+        // every right is listed once at most, e.g. "KKq" is no castling availability
+        if castling[..index].contains(symbol) {
+            return Err(error());
+        }
+        let (color, y) = if symbol.is_ascii_uppercase() {
+            (PieceColor::White, 1)
+        } else {
+            (PieceColor::Black, BOARD_SIZE as i32)
+        };
+        let rook_x = match symbol.to_ascii_lowercase() {
+            'k' => BOARD_SIZE as i32,
+            'q' => 1,
+            _ => return Err(error()),
+        };
+        for (x, unmoved) in [
+            (5, PieceType::King { moved: false }),
+            (rook_x, PieceType::Rook { moved: false }),
+        ] {
+            let field = board
+                .get_field_mut(Coordinates { x, y })
+                .expect("Castling fields are on the board");
+            match &mut field.piece {
+                Some(piece)
+                    if piece.piece_color == color
+                        && std::mem::discriminant(&piece.piece_type)
+                            == std::mem::discriminant(&unmoved) =>
+                {
+                    piece.piece_type = unmoved;
+                }
+                _ => return Err(error()),
+            }
+        }
+    }
+    Ok(())
+}
+
+impl Board {
+    pub fn starting() -> Board {
+        Board::from_fen(STARTING_FEN).expect("The starting position is a valid FEN")
+    }
+
+    // the halfmove clock and full move number may be omitted, as in EPD records
+    pub fn from_fen(fen: &str) -> Result<Board, FenError> {
+        let parts: Vec<&str> = fen.split_whitespace().collect();
+        if parts.len() > 6 {
+            return Err(FenError::TooManyFields(parts.len()));
+        }
+        let placement = parts
+            .first()
+            .ok_or(FenError::MissingField("piece placement"))?;
+        let side = parts.get(1).ok_or(FenError::MissingField("side to move"))?;
+        let castling = parts.get(2).ok_or(FenError::MissingField("castling"))?;
+        let en_passant = parts.get(3).ok_or(FenError::MissingField("en passant"))?;
+        let halfmove_clock = parts.get(4).unwrap_or(&"0");
+        let full_move_number = parts.get(5).unwrap_or(&"1");
+
+        let mut board = Board::empty();
+        board.fields = parse_placement(placement)?;
+
+        for color in [PieceColor::White, PieceColor::Black] {
+            let kings: Vec<Coordinates> = board
+                .fields
+                .iter()
+                .flatten()
+                .filter_map(|field| field.piece.as_ref())
+                .filter(|piece| {
+                    piece.piece_color == color && matches!(piece.piece_type, PieceType::King { .. })
+                })
+                .map(|piece| piece.coordinates)
+                .collect();
+            if kings.len() != 1 {
+                return Err(FenError::WrongKingCount {
+                    color,
+                    count: kings.len(),
+                });
+            }
+            match color {
+                PieceColor::White => board.white_king_pos = kings[0],
+                PieceColor::Black => board.black_king_pos = kings[0],
+            }
+        }
+
+        for field in board.fields.iter_mut().flatten() {
+            if let Some(piece) = &mut field.piece {
+                if let PieceType::Pawn { .. } = piece.piece_type {
+                    let start_rank = match piece.piece_color {
+                        PieceColor::White => 2,
+                        PieceColor::Black => BOARD_SIZE as i32 - 1,
+                    };
+                    if field.coordinates.y == 1 || field.coordinates.y == BOARD_SIZE as i32 {
+                        return Err(FenError::PawnOnBackRank(field.coordinates));
+                    }
+                    piece.piece_type = PieceType::Pawn {
+                        moved: field.coordinates.y != start_rank,
+                    };
+                }
+            }
+        }
+
+        board.side_to_move = match *side {
+            "w" => PieceColor::White,
+            "b" => PieceColor::Black,
+            _ => return Err(FenError::InvalidSideToMove(side.to_string())),
+        };
+
+        apply_castling(&mut board, castling)?;
+
+        if *en_passant != "-" {
+            let error = || FenError::InvalidEnPassant(en_passant.to_string());
+            let coords = Coordinates::from_algebraic(en_passant).ok_or_else(error)?;
+            let (target_rank, pawn_color) = match board.side_to_move {
+                PieceColor::White => (6, PieceColor::Black),
+                PieceColor::Black => (3, PieceColor::White),
+            };
+            let pawn_y = if pawn_color == PieceColor::White {
+                coords.y + 1
+            } else {
+                coords.y - 1
+            };
+            let pushed_pawn = board.get_piece(Coordinates {
+                x: coords.x,
+                y: pawn_y,
+            });
+            // the pawn has just passed the target square, coming from the one behind it
+            let passed_fields_empty = [coords.y, 2 * coords.y - pawn_y].iter().all(|y| {
+                board
+                    .get_piece(Coordinates { x: coords.x, y: *y })
+                    .is_none()
+            });
+            if coords.y != target_rank
+                || !passed_fields_empty
+                || !pushed_pawn.is_some_and(|pawn| {
+                    pawn.piece_color == pawn_color
+                        && matches!(pawn.piece_type, PieceType::Pawn { .. })
+                })
+            {
+                return Err(error());
+            }
+            board.en_passant = Some(coords);
+        }
+
+        board.halfmove_clock = halfmove_clock
+            .parse()
+            .map_err(|_| FenError::InvalidHalfmoveClock(halfmove_clock.to_string()))?;
+        board.full_move_number = match full_move_number.parse() {
+            Ok(number) if number > 0 => number,
+            _ => {
+                return Err(FenError::InvalidFullMoveNumber(
+                    full_move_number.to_string(),
+                ))
+            }
+        };
+
+        let opponent = match board.side_to_move {
+            PieceColor::White => PieceColor::Black,
+            PieceColor::Black => PieceColor::White,
+        };
+        if board.king_in_danger(opponent) {
+            return Err(FenError::OpponentInCheck);
+        }
+
+        board.hash = board.position().hash();
+        board.record_position();
+        Ok(board)
+    }
+}
@@ -0,0 +1,95 @@
+use crate::chess_pieces::PieceColor;
+use std::fmt::{self, Display, Formatter};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Outcome {
+    WhiteWins,
+    BlackWins,
+    Draw,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EndReason {
+    Checkmate,
+    Stalemate,
+    FiftyMoveRule,
+    SeventyFiveMoveRule,
+    ThreefoldRepetition,
+    FivefoldRepetition,
+    InsufficientMaterial,
+    // the games between engines are lost by the engine that broke them
+    IllegalMove,
+    EngineFailure,
+    // the side that ran out of time loses, unless its opponent can't checkmate
+    Timeout,
+    TimeoutVsInsufficientMaterial,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GameResult {
+    pub outcome: Outcome,
+    pub reason: EndReason,
+}
+
+impl Outcome {
+    pub fn win_for(color: PieceColor) -> Outcome {
+        match color {
+            PieceColor::White => Outcome::WhiteWins,
+            PieceColor::Black => Outcome::BlackWins,
+        }
+    }
+
+    pub fn winner(&self) -> Option<PieceColor> {
+        match self {
+            Outcome::WhiteWins => Some(PieceColor::White),
+            Outcome::BlackWins => Some(PieceColor::Black),
+            Outcome::Draw => None,
+        }
+    }
+}
+
+impl GameResult {
+    pub fn win(color: PieceColor, reason: EndReason) -> GameResult {
+        GameResult {
+            outcome: Outcome::win_for(color),
+            reason,
+        }
+    }
+
+    pub fn draw(reason: EndReason) -> GameResult {
+        GameResult {
+            outcome: Outcome::Draw,
+            reason,
+        }
+    }
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::WhiteWins => write!(f, "WHITE WINS!"),
+            Outcome::BlackWins => write!(f, "BLACK WINS!"),
+            Outcome::Draw => write!(f, "DRAW"),
+        }
+    }
+}
+
+impl Display for EndReason {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            EndReason::Checkmate => write!(f, "checkmate"),
+            EndReason::Stalemate => write!(f, "stalemate"),
+            EndReason::FiftyMoveRule => write!(f, "fifty-move rule"),
+            EndReason::SeventyFiveMoveRule => write!(f, "seventy-five-move rule"),
+            EndReason::ThreefoldRepetition => write!(f, "threefold repetition"),
+            EndReason::FivefoldRepetition => write!(f, "fivefold repetition"),
+            EndReason::InsufficientMaterial => write!(f, "insufficient material"),
+            EndReason::IllegalMove => write!(f, "illegal move"),
+            EndReason::EngineFailure => write!(f, "engine failure"),
+            EndReason::Timeout => write!(f, "timeout"),
+            EndReason::TimeoutVsInsufficientMaterial => {
+                write!(f, "timeout vs insufficient material")
+            }
+        }
+    }
+}
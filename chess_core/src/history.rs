@@ -0,0 +1,99 @@
+use crate::board::{Board, MoveRecord};
+use crate::clock::ChessClock;
+use crate::pgn::{LoggedMove, MoveLog};
+
+// a move that can be taken back, with the clock as it stood right before the move was made
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct HistoryEntry {
+    record: MoveRecord,
+    clock: Option<ChessClock>,
+}
+
+// moves that can be taken back, and moves that were taken back and can be played again
+#[derive(Debug, Clone, Default)]
+pub struct MoveHistory {
+    undo: Vec<HistoryEntry>,
+    redo: Vec<HistoryEntry>,
+    // the clock when the first of the moves to play again was taken back, which is given
+    // back once they are all played again
+    clock_before_undo: Option<ChessClock>,
+}
+
+impl MoveHistory {
+    // a new move makes the moves taken back before it unavailable; the clock is the one
+    // from before the move
+    pub fn push(&mut self, record: MoveRecord, clock: Option<ChessClock>) {
+        self.undo.push(HistoryEntry { record, clock });
+        self.redo.clear();
+    }
+
+    // the moves of the game up to the current position, in the order they were played
+    pub fn played(&self) -> Vec<&MoveRecord> {
+        self.undo.iter().map(|entry| &entry.record).collect()
+    }
+
+    pub fn can_undo(&self, count: usize) -> bool {
+        self.undo.len() >= count
+    }
+
+    pub fn can_redo(&self, count: usize) -> bool {
+        self.redo.len() >= count
+    }
+
+    // takes back the last `count` moves, or nothing if there are fewer; the clock goes back
+    // to how it was before the first of them
+    pub fn undo(
+        &mut self,
+        count: usize,
+        board: &mut Board,
+        log: &mut MoveLog,
+        clock: &mut Option<ChessClock>,
+    ) -> bool {
+        if !self.can_undo(count) {
+            return false;
+        }
+        if self.redo.is_empty() {
+            self.clock_before_undo = *clock;
+        }
+        for _ in 0..count {
+            let entry = self.undo.pop().expect("Checked above");
+            board.undo_move(&entry.record);
+            log.moves.pop();
+            *clock = entry.clock;
+            self.redo.push(entry);
+        }
+        true
+    }
+
+    // plays the last `count` moves taken back again, or nothing if there are fewer; the clock
+    // is set to how it was before the next move taken back, or before any were taken back
+    pub fn redo(
+        &mut self,
+        count: usize,
+        board: &mut Board,
+        log: &mut MoveLog,
+        clock: &mut Option<ChessClock>,
+    ) -> bool {
+        if !self.can_redo(count) {
+            return false;
+        }
+        for _ in 0..count {
+            let undone = self.redo.pop().expect("Checked above");
+            let record = &undone.record;
+            let logged_move = LoggedMove::new(board, record.from, record.to, record.promotion);
+            let record = board
+                .make_move(record.from, record.to, record.promotion)
+                .expect("Moves taken back can be played again");
+            log.push(logged_move);
+            self.undo.push(HistoryEntry {
+                record,
+                clock: undone.clock,
+            });
+        }
+        *clock = match self.redo.last() {
+            Some(next) => next.clock,
+            None => self.clock_before_undo,
+        };
+        true
+    }
+}
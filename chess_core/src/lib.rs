@@ -0,0 +1,23 @@
+// chess rules without any dependency on the game engine, so that they can be used
+// by the game as well as by command line tools and tests
+pub mod bitboard;
+pub mod board;
+pub mod chess_move;
+pub mod chess_pieces;
+pub mod clock;
+pub mod coordinates;
+pub mod engine;
+pub mod fen;
+pub mod field;
+pub mod game_result;
+pub mod history;
+pub mod moves;
+pub mod perft;
+pub mod pgn;
+pub mod position;
+pub mod san;
+pub mod tournament;
+pub mod uci;
+pub mod zobrist;
+
+pub const BOARD_SIZE: usize = 8;
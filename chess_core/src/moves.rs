@@ -0,0 +1,22 @@
+use crate::board::*;
+use crate::chess_pieces::*;
+use crate::coordinates::*;
+
+// fields the piece can move to, castling being represented as the field of the rook;
+// without `filter_check` also the ones that would leave the own king in check
+pub fn get_possible_moves(piece: &Piece, board: &Board, filter_check: bool) -> Vec<Coordinates> {
+    let position = board.position().with_side_to_move(piece.piece_color);
+    let moves = if filter_check {
+        position.legal_moves()
+    } else {
+        position.pseudo_legal_moves()
+    };
+    let mut result: Vec<Coordinates> = moves
+        .into_iter()
+        .filter(|chess_move| chess_move.from == piece.coordinates)
+        .map(|chess_move| chess_move.board_target())
+        .collect();
+    // promotions to different pieces go to the same field
+    result.dedup();
+    result
+}
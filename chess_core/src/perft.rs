@@ -0,0 +1,22 @@
+use crate::board::Board;
+use crate::chess_move::Move;
+
+impl Board {
+    // number of move sequences of the given length from this position, used to check
+    // the move generator against known counts
+    pub fn perft(&self, depth: u32) -> u64 {
+        self.position().perft(depth)
+    }
+
+    // perft split by the first move, to find where a wrong count comes from
+    pub fn divide(&self, depth: u32) -> Vec<(Move, u64)> {
+        let mut position = self.position();
+        let mut result = Vec::new();
+        for chess_move in position.legal_moves() {
+            let unmake = position.make(&chess_move);
+            result.push((chess_move, position.perft(depth.saturating_sub(1))));
+            position.unmake(&chess_move, unmake);
+        }
+        result
+    }
+}
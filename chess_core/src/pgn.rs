@@ -0,0 +1,440 @@
+use crate::board::Board;
+use crate::chess_move::Move;
+use crate::chess_pieces::*;
+use crate::coordinates::Coordinates;
+use crate::fen::{FenError, STARTING_FEN};
+use crate::game_result::{GameResult, Outcome};
+use crate::san::{parse_san, to_san};
+use std::fmt::Write;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const PGN_DIRECTORY: &str = "games";
+const MAX_LINE_LENGTH: usize = 80;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoggedMove {
+    pub from: Coordinates,
+    pub to: Coordinates,
+    pub promotion: Option<PieceType>,
+    pub san: String,
+}
+
+impl LoggedMove {
+    // the board is the position before the move is made
+    pub fn new(
+        board: &Board,
+        from: Coordinates,
+        to: Coordinates,
+        promotion: Option<PieceType>,
+    ) -> LoggedMove {
+        LoggedMove {
+            from,
+            to,
+            promotion,
+            san: to_san(board, from, to, promotion),
+        }
+    }
+}
+
+// moves played so far together with the position the game started from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MoveLog {
+    pub start_fen: String,
+    pub moves: Vec<LoggedMove>,
+}
+
+impl Default for MoveLog {
+    fn default() -> Self {
+        MoveLog::new(STARTING_FEN.to_string())
+    }
+}
+
+impl MoveLog {
+    pub fn new(start_fen: String) -> MoveLog {
+        MoveLog {
+            start_fen,
+            moves: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, logged_move: LoggedMove) {
+        self.moves.push(logged_move);
+    }
+
+    // SAN moves with move numbers, e.g. "1. e4 e5 2. Nf3"
+    pub fn movetext(&self) -> Vec<String> {
+        let (mut number, mut color) = match Board::from_fen(&self.start_fen) {
+            Ok(board) => (board.full_move_number, board.side_to_move),
+            Err(_) => (1, PieceColor::White),
+        };
+        let mut tokens = Vec::new();
+        for (i, logged_move) in self.moves.iter().enumerate() {
+            match color {
+                PieceColor::White => tokens.push(format!("{}.", number)),
+                PieceColor::Black if i == 0 => tokens.push(format!("{}...", number)),
+                PieceColor::Black => {}
+            }
+            tokens.push(logged_move.san.clone());
+            color = match color {
+                PieceColor::White => PieceColor::Black,
+                PieceColor::Black => {
+                    number += 1;
+                    PieceColor::White
+                }
+            };
+        }
+        tokens
+    }
+
+    // the moves in UCI notation, e.g. "e1g1" for castling, as engines expect them
+    pub fn uci_moves(&self) -> Vec<String> {
+        let Ok(mut board) = Board::from_fen(&self.start_fen) else {
+            return Vec::new();
+        };
+        let mut uci_moves = Vec::new();
+        for logged_move in &self.moves {
+            let Some(chess_move) = Move::from_board_target(
+                &board,
+                logged_move.from,
+                logged_move.to,
+                logged_move.promotion,
+            ) else {
+                break;
+            };
+            uci_moves.push(chess_move.to_uci());
+            board.apply(chess_move);
+        }
+        uci_moves
+    }
+}
+
+pub fn result_tag(result: Option<GameResult>) -> &'static str {
+    match result.map(|result| result.outcome) {
+        Some(Outcome::WhiteWins) => "1-0",
+        Some(Outcome::BlackWins) => "0-1",
+        Some(Outcome::Draw) => "1/2-1/2",
+        None => "*",
+    }
+}
+
+// converts days since 1970-01-01 to a (year, month, day) civil date
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+pub fn today() -> String {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs());
+    let (year, month, day) = civil_from_days((seconds / 86400) as i64);
+    format!("{:04}.{:02}.{:02}", year, month, day)
+}
+
+pub struct Pgn {
+    pub tags: Vec<(String, String)>,
+    pub movetext: Vec<String>,
+    pub result: String,
+}
+
+impl Pgn {
+    // the Seven Tag Roster, plus SetUp and FEN for games that didn't start from the initial position
+    pub fn new(log: &MoveLog, white: &str, black: &str, result: Option<GameResult>) -> Pgn {
+        let result = result_tag(result).to_string();
+        let mut tags = vec![
+            ("Event".to_string(), "Chess Masters game".to_string()),
+            ("Site".to_string(), "?".to_string()),
+            ("Date".to_string(), today()),
+            ("Round".to_string(), "-".to_string()),
+            ("White".to_string(), white.to_string()),
+            ("Black".to_string(), black.to_string()),
+            ("Result".to_string(), result.clone()),
+        ];
+        if log.start_fen != STARTING_FEN {
+            tags.push(("SetUp".to_string(), "1".to_string()));
+            tags.push(("FEN".to_string(), log.start_fen.clone()));
+        }
+        Pgn {
+            tags,
+            movetext: log.movetext(),
+            result,
+        }
+    }
+
+    // replaces the value of a tag, or adds the tag after the others
+    pub fn set_tag(&mut self, name: &str, value: &str) {
+        match self.tags.iter_mut().find(|(tag_name, _)| tag_name == name) {
+            Some((_, tag_value)) => *tag_value = value.to_string(),
+            None => self.tags.push((name.to_string(), value.to_string())),
+        }
+    }
+}
+
+impl std::fmt::Display for Pgn {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (name, value) in &self.tags {
+            let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+            writeln!(f, "[{} \"{}\"]", name, value)?;
+        }
+        writeln!(f)?;
+
+        let mut line = String::new();
+        for token in self.movetext.iter().chain(std::iter::once(&self.result)) {
+            if !line.is_empty() && line.len() + 1 + token.len() > MAX_LINE_LENGTH {
+                writeln!(f, "{}", line)?;
+                line.clear();
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.write_str(token)?;
+        }
+        writeln!(f, "{}", line)
+    }
+}
+
+// writes the game to a new file in the games directory and returns its path
+pub fn save_pgn(pgn: &Pgn) -> std::io::Result<PathBuf> {
+    std::fs::create_dir_all(PGN_DIRECTORY)?;
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs());
+    let path = PathBuf::from(PGN_DIRECTORY).join(format!("chess_masters_{}.pgn", timestamp));
+    std::fs::write(&path, pgn.to_string())?;
+    Ok(path)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PgnErrorKind {
+    UnterminatedTag,
+    UnterminatedComment,
+    UnbalancedVariation,
+    InvalidFen(FenError),
+    IllegalMove {
+        move_number: u32,
+        color: PieceColor,
+        san: String,
+    },
+}
+
+// games are numbered from 1, in the order they appear in the file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PgnError {
+    pub game: usize,
+    pub kind: PgnErrorKind,
+}
+
+impl std::fmt::Display for PgnError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "game {}: ", self.game)?;
+        match &self.kind {
+            PgnErrorKind::UnterminatedTag => write!(f, "unterminated tag pair"),
+            PgnErrorKind::UnterminatedComment => write!(f, "unterminated comment"),
+            PgnErrorKind::UnbalancedVariation => write!(f, "unbalanced parentheses"),
+            PgnErrorKind::InvalidFen(error) => write!(f, "invalid FEN tag: {}", error),
+            PgnErrorKind::IllegalMove {
+                move_number,
+                color,
+                san,
+            } => match color {
+                PieceColor::White => write!(f, "illegal move {}. {}", move_number, san),
+                PieceColor::Black => write!(f, "illegal move {}... {}", move_number, san),
+            },
+        }
+    }
+}
+
+impl std::error::Error for PgnError {}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PgnGame {
+    pub tags: Vec<(String, String)>,
+    // SAN moves of the mainline, without comments, NAGs and variations
+    pub moves: Vec<String>,
+    pub result: String,
+}
+
+impl PgnGame {
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(tag_name, _)| tag_name == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    // plays the mainline from the starting position, returning every position reached
+    // together with the moves leading to them
+    pub fn replay(&self, game: usize) -> Result<(Vec<Board>, MoveLog), PgnError> {
+        let error = |kind| PgnError { game, kind };
+        let start_fen = self.tag("FEN").unwrap_or(STARTING_FEN);
+        let mut board = Board::from_fen(start_fen)
+            .map_err(|fen_error| error(PgnErrorKind::InvalidFen(fen_error)))?;
+        let mut log = MoveLog::new(board.to_fen());
+        let mut positions = vec![board.clone()];
+
+        for san in &self.moves {
+            let Some((from, to, promotion)) = parse_san(&board, san) else {
+                return Err(error(PgnErrorKind::IllegalMove {
+                    move_number: board.full_move_number,
+                    color: board.side_to_move,
+                    san: san.clone(),
+                }));
+            };
+            log.push(LoggedMove::new(&board, from, to, promotion));
+            board.move_piece(from, to, promotion);
+            positions.push(board.clone());
+        }
+        Ok((positions, log))
+    }
+}
+
+fn is_result(token: &str) -> bool {
+    matches!(token, "1-0" | "0-1" | "1/2-1/2" | "*")
+}
+
+fn parse_tag(chars: &mut std::str::Chars) -> Option<(String, String)> {
+    let mut name = String::new();
+    for c in chars.by_ref() {
+        if c == '"' {
+            break;
+        }
+        if !c.is_whitespace() {
+            name.push(c);
+        }
+    }
+    let mut value = String::new();
+    loop {
+        match chars.next()? {
+            '\\' => value.push(chars.next()?),
+            '"' => break,
+            c => value.push(c),
+        }
+    }
+    for c in chars.by_ref() {
+        if c == ']' {
+            return Some((name, value));
+        }
+    }
+    None
+}
+
+fn skip_line(chars: &mut std::str::Chars) {
+    for c in chars.by_ref() {
+        if c == '\n' {
+            break;
+        }
+    }
+}
+
+// the move after a move number like "12." or "12...", which may be written without a space;
+// castling written with zeros, "0-0", has no move number
+fn strip_move_number(word: &str) -> &str {
+    let after_digits = word.trim_start_matches(|c: char| c.is_ascii_digit());
+    if after_digits.len() < word.len() && after_digits.starts_with('.') {
+        after_digits.trim_start_matches('.')
+    } else {
+        word
+    }
+}
+
+// adds a finished movetext token to the game, skipping move numbers and NAGs
+fn finish_token(token: &mut String, game: &mut PgnGame, games: &mut Vec<PgnGame>) {
+    let word = std::mem::take(token);
+    if is_result(&word) {
+        game.result = word;
+        games.push(std::mem::take(game));
+        return;
+    }
+    let word = strip_move_number(&word);
+    // a lone "..." stands for the move of white left out
+    let only_dots = word.chars().all(|c| c == '.');
+    if !only_dots && !word.starts_with('$') && word != "e.p." {
+        game.moves.push(word.to_string());
+    }
+}
+
+// reads every game in a PGN file, skipping comments, NAGs and variations
+pub fn parse_pgn(text: &str) -> Result<Vec<PgnGame>, PgnError> {
+    let mut games = Vec::new();
+    let mut game = PgnGame::default();
+    let mut variation_depth = 0;
+    let mut token = String::new();
+    let mut chars = text.chars();
+    let mut line_start = true;
+
+    while let Some(c) = chars.next() {
+        let game_number = games.len() + 1;
+        let error = |kind| PgnError {
+            game: game_number,
+            kind,
+        };
+        let at_line_start = line_start;
+        line_start = c == '\n';
+        if matches!(c, '{' | ';' | '(') && variation_depth == 0 {
+            finish_token(&mut token, &mut game, &mut games);
+        }
+        match c {
+            '%' if at_line_start => {
+                skip_line(&mut chars);
+                line_start = true;
+            }
+            ';' => {
+                skip_line(&mut chars);
+                line_start = true;
+            }
+            '{' => {
+                if !chars.by_ref().any(|c| c == '}') {
+                    return Err(error(PgnErrorKind::UnterminatedComment));
+                }
+            }
+            '(' => variation_depth += 1,
+            ')' => {
+                if variation_depth == 0 {
+                    return Err(error(PgnErrorKind::UnbalancedVariation));
+                }
+                variation_depth -= 1;
+            }
+            '[' if variation_depth == 0 && token.is_empty() => {
+                // tags after movetext without a result start a new game
+                if !game.moves.is_empty() {
+                    game.result = "*".to_string();
+                    games.push(std::mem::take(&mut game));
+                }
+                let tag = parse_tag(&mut chars).ok_or(error(PgnErrorKind::UnterminatedTag))?;
+                game.tags.push(tag);
+            }
+            c if c.is_whitespace() => {
+                if variation_depth == 0 {
+                    finish_token(&mut token, &mut game, &mut games);
+                }
+                token.clear();
+            }
+            c => {
+                if variation_depth == 0 {
+                    token.push(c);
+                }
+            }
+        }
+    }
+    finish_token(&mut token, &mut game, &mut games);
+    if variation_depth > 0 {
+        return Err(PgnError {
+            game: games.len() + 1,
+            kind: PgnErrorKind::UnbalancedVariation,
+        });
+    }
+    if !game.moves.is_empty() || !game.tags.is_empty() {
+        game.result = "*".to_string();
+        games.push(game);
+    }
+    Ok(games)
+}
@@ -0,0 +1,464 @@
+use crate::bitboard::*;
+use crate::board::Board;
+use crate::chess_move::Move;
+use crate::chess_pieces::*;
+use crate::coordinates::Coordinates;
+use crate::zobrist::*;
+
+pub(crate) const KING: usize = 0;
+pub(crate) const QUEEN: usize = 1;
+pub(crate) const ROOK: usize = 2;
+pub(crate) const BISHOP: usize = 3;
+pub(crate) const KNIGHT: usize = 4;
+pub(crate) const PAWN: usize = 5;
+
+const WHITE_KINGSIDE: u8 = 1;
+const WHITE_QUEENSIDE: u8 = 2;
+const BLACK_KINGSIDE: u8 = 4;
+const BLACK_QUEENSIDE: u8 = 8;
+
+// castling rights lost when a piece moves from or to the square (the king and rook squares)
+fn castling_rights_lost(square: usize) -> u8 {
+    match square {
+        4 => WHITE_KINGSIDE | WHITE_QUEENSIDE,
+        0 => WHITE_QUEENSIDE,
+        7 => WHITE_KINGSIDE,
+        60 => BLACK_KINGSIDE | BLACK_QUEENSIDE,
+        56 => BLACK_QUEENSIDE,
+        63 => BLACK_KINGSIDE,
+        _ => 0,
+    }
+}
+
+// the index of the color in arrays holding something for both sides, white first
+pub fn color_index(color: PieceColor) -> usize {
+    match color {
+        PieceColor::White => 0,
+        PieceColor::Black => 1,
+    }
+}
+
+fn opponent(color: PieceColor) -> PieceColor {
+    match color {
+        PieceColor::White => PieceColor::Black,
+        PieceColor::Black => PieceColor::White,
+    }
+}
+
+// the piece type of the kind index, with the moved flags set
+pub(crate) fn kind_piece_type(kind: usize) -> PieceType {
+    match kind {
+        KING => PieceType::King { moved: true },
+        QUEEN => PieceType::Queen,
+        ROOK => PieceType::Rook { moved: true },
+        BISHOP => PieceType::Bishop,
+        KNIGHT => PieceType::Knight,
+        _ => PieceType::Pawn { moved: true },
+    }
+}
+
+pub(crate) fn kind_index(piece_type: PieceType) -> usize {
+    match piece_type {
+        PieceType::King { .. } => KING,
+        PieceType::Queen => QUEEN,
+        PieceType::Rook { .. } => ROOK,
+        PieceType::Bishop => BISHOP,
+        PieceType::Knight => KNIGHT,
+        PieceType::Pawn { .. } => PAWN,
+    }
+}
+
+// what `make` changed besides the moving piece, so that `unmake` can restore it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Unmake {
+    captured: Option<(usize, usize)>,
+    castling: u8,
+    en_passant: Option<usize>,
+    hash: u64,
+}
+
+// the position as one bitboard per color and kind of piece, used for move generation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+    pieces: [[Bitboard; 6]; 2],
+    colors: [Bitboard; 2],
+    pub side_to_move: PieceColor,
+    castling: u8,
+    en_passant: Option<usize>,
+    // the Zobrist hash, kept up to date by `make` and `unmake`
+    hash: u64,
+}
+
+impl Position {
+    pub fn from_board(board: &Board) -> Position {
+        let mut position = Position {
+            pieces: [[0; 6]; 2],
+            colors: [0; 2],
+            side_to_move: board.side_to_move,
+            castling: board.castling_bits(),
+            en_passant: board.en_passant.map(square_index),
+            hash: 0,
+        };
+        for piece in board
+            .fields
+            .iter()
+            .flatten()
+            .filter_map(|field| field.piece.as_ref())
+        {
+            position.put(
+                color_index(piece.piece_color),
+                kind_index(piece.piece_type),
+                square_index(piece.coordinates),
+            );
+        }
+
+        position.hash ^= castling_key(position.castling) ^ position.en_passant_hash();
+        if position.side_to_move == PieceColor::Black {
+            position.hash ^= side_key();
+        }
+        position
+    }
+
+    // the same position with the other side to move, which can't capture en passant
+    pub fn with_side_to_move(&self, color: PieceColor) -> Position {
+        if color == self.side_to_move {
+            return *self;
+        }
+        Position {
+            side_to_move: color,
+            en_passant: None,
+            hash: self.hash ^ side_key() ^ self.en_passant_hash(),
+            ..*self
+        }
+    }
+
+    pub fn hash(&self) -> u64 {
+        self.hash
+    }
+
+    // the en passant file is only hashed when a pawn of the side to move can capture there,
+    // so that the position is the same as when it's reached without the double push
+    fn en_passant_hash(&self) -> u64 {
+        let us = color_index(self.side_to_move);
+        match self.en_passant {
+            Some(square) if PAWN_ATTACKS[1 - us][square] & self.pieces[us][PAWN] != 0 => {
+                en_passant_key(Some(square))
+            }
+            _ => 0,
+        }
+    }
+
+    fn put(&mut self, color: usize, kind: usize, square: usize) {
+        self.pieces[color][kind] |= square_bit(square);
+        self.colors[color] |= square_bit(square);
+        self.hash ^= piece_key(color, kind, square);
+    }
+
+    fn remove(&mut self, color: usize, kind: usize, square: usize) {
+        self.pieces[color][kind] &= !square_bit(square);
+        self.colors[color] &= !square_bit(square);
+        self.hash ^= piece_key(color, kind, square);
+    }
+
+    pub(crate) fn pieces(&self, color: usize, kind: usize) -> Bitboard {
+        self.pieces[color][kind]
+    }
+
+    fn occupied(&self) -> Bitboard {
+        self.colors[0] | self.colors[1]
+    }
+
+    pub(crate) fn kind_at(&self, color: usize, square: usize) -> Option<usize> {
+        (0..6).find(|kind| self.pieces[color][*kind] & square_bit(square) != 0)
+    }
+
+    pub fn is_attacked(&self, square: usize, by: PieceColor) -> bool {
+        let attacker = color_index(by);
+        let pieces = &self.pieces[attacker];
+        let occupied = self.occupied();
+        // a pawn of the attacker attacks the square if a pawn of the other color standing
+        // there would attack the pawn
+        PAWN_ATTACKS[1 - attacker][square] & pieces[PAWN] != 0
+            || KNIGHT_ATTACKS[square] & pieces[KNIGHT] != 0
+            || KING_ATTACKS[square] & pieces[KING] != 0
+            || bishop_attacks(square, occupied) & (pieces[BISHOP] | pieces[QUEEN]) != 0
+            || rook_attacks(square, occupied) & (pieces[ROOK] | pieces[QUEEN]) != 0
+    }
+
+    pub fn in_check(&self, color: PieceColor) -> bool {
+        let king = self.pieces[color_index(color)][KING];
+        king != 0 && self.is_attacked(king.trailing_zeros() as usize, opponent(color))
+    }
+
+    fn push_moves(&self, from: usize, targets: Bitboard, result: &mut Vec<Move>) {
+        let them = self.colors[1 - color_index(self.side_to_move)];
+        for to in squares(targets) {
+            result.push(Move {
+                from: square_coordinates(from),
+                to: square_coordinates(to),
+                promotion: None,
+                capture: them & square_bit(to) != 0,
+                castle: false,
+                en_passant: false,
+                double_push: false,
+            });
+        }
+    }
+
+    fn push_pawn_moves(&self, from: usize, to: usize, result: &mut Vec<Move>) {
+        let them = self.colors[1 - color_index(self.side_to_move)];
+        let en_passant = self.en_passant == Some(to);
+        let chess_move = Move {
+            from: square_coordinates(from),
+            to: square_coordinates(to),
+            promotion: None,
+            capture: en_passant || them & square_bit(to) != 0,
+            castle: false,
+            en_passant,
+            double_push: from.abs_diff(to) == 16,
+        };
+        if !(8..56).contains(&to) {
+            for promotion in PROMOTION_PIECES {
+                result.push(Move {
+                    promotion: Some(promotion),
+                    ..chess_move
+                });
+            }
+        } else {
+            result.push(chess_move);
+        }
+    }
+
+    fn push_castling(&self, result: &mut Vec<Move>) {
+        let color = self.side_to_move;
+        let (kingside, queenside, king) = match color {
+            PieceColor::White => (WHITE_KINGSIDE, WHITE_QUEENSIDE, 4),
+            PieceColor::Black => (BLACK_KINGSIDE, BLACK_QUEENSIDE, 60),
+        };
+        if self.castling & (kingside | queenside) == 0 || self.in_check(color) {
+            return;
+        }
+        let occupied = self.occupied();
+        // the fields between the king and the rook, and the ones the king passes and lands on
+        for (right, empty, safe, to) in [
+            (
+                kingside,
+                [king + 1, king + 2].as_slice(),
+                [king + 1, king + 2],
+                king + 2,
+            ),
+            (
+                queenside,
+                [king - 1, king - 2, king - 3].as_slice(),
+                [king - 1, king - 2],
+                king - 2,
+            ),
+        ] {
+            if self.castling & right != 0
+                && empty
+                    .iter()
+                    .all(|square| occupied & square_bit(*square) == 0)
+                && safe
+                    .iter()
+                    .all(|square| !self.is_attacked(*square, opponent(color)))
+            {
+                result.push(Move {
+                    from: square_coordinates(king),
+                    to: square_coordinates(to),
+                    promotion: None,
+                    capture: false,
+                    castle: true,
+                    en_passant: false,
+                    double_push: false,
+                });
+            }
+        }
+    }
+
+    // moves that follow the rules of movement but may leave the own king in check
+    pub fn pseudo_legal_moves(&self) -> Vec<Move> {
+        let mut result = Vec::with_capacity(64);
+        let us = color_index(self.side_to_move);
+        let own = self.colors[us];
+        let them = self.colors[1 - us];
+        let occupied = self.occupied();
+        let pieces = &self.pieces[us];
+
+        for from in squares(pieces[KNIGHT]) {
+            self.push_moves(from, KNIGHT_ATTACKS[from] & !own, &mut result);
+        }
+        for from in squares(pieces[BISHOP] | pieces[QUEEN]) {
+            self.push_moves(from, bishop_attacks(from, occupied) & !own, &mut result);
+        }
+        for from in squares(pieces[ROOK] | pieces[QUEEN]) {
+            self.push_moves(from, rook_attacks(from, occupied) & !own, &mut result);
+        }
+        for from in squares(pieces[KING]) {
+            self.push_moves(from, KING_ATTACKS[from] & !own, &mut result);
+        }
+
+        let en_passant = self.en_passant.map_or(0, square_bit);
+        for from in squares(pieces[PAWN]) {
+            let (single, start_rank) = match self.side_to_move {
+                PieceColor::White => (from + 8, 1),
+                PieceColor::Black => (from.wrapping_sub(8), 6),
+            };
+            if single < 64 && occupied & square_bit(single) == 0 {
+                self.push_pawn_moves(from, single, &mut result);
+                let double = if us == 0 {
+                    from + 16
+                } else {
+                    from.wrapping_sub(16)
+                };
+                if from / 8 == start_rank && occupied & square_bit(double) == 0 {
+                    self.push_pawn_moves(from, double, &mut result);
+                }
+            }
+            for to in squares(PAWN_ATTACKS[us][from] & (them | en_passant)) {
+                self.push_pawn_moves(from, to, &mut result);
+            }
+        }
+
+        self.push_castling(&mut result);
+        result
+    }
+
+    pub fn legal_moves(&self) -> Vec<Move> {
+        let color = self.side_to_move;
+        let mut position = *self;
+        self.pseudo_legal_moves()
+            .into_iter()
+            .filter(|chess_move| {
+                let unmake = position.make(chess_move);
+                let legal = !position.in_check(color);
+                position.unmake(chess_move, unmake);
+                legal
+            })
+            .collect()
+    }
+
+    fn rook_castling_squares(chess_move: &Move) -> (usize, usize) {
+        let king = square_index(chess_move.from);
+        if chess_move.to.x > chess_move.from.x {
+            (king + 3, king + 1)
+        } else {
+            (king - 4, king - 1)
+        }
+    }
+
+    // plays the move in place; it has to be one of the generated moves
+    pub fn make(&mut self, chess_move: &Move) -> Unmake {
+        let us = color_index(self.side_to_move);
+        let them = 1 - us;
+        let from = square_index(chess_move.from);
+        let to = square_index(chess_move.to);
+        let kind = self.kind_at(us, from).expect("There is a piece to move");
+        let mut unmake = Unmake {
+            captured: None,
+            castling: self.castling,
+            en_passant: self.en_passant,
+            hash: self.hash,
+        };
+        let previous_keys = castling_key(self.castling) ^ self.en_passant_hash();
+
+        if chess_move.castle {
+            let (rook_from, rook_to) = Position::rook_castling_squares(chess_move);
+            self.remove(us, ROOK, rook_from);
+            self.put(us, ROOK, rook_to);
+        } else if chess_move.en_passant {
+            let captured = if us == 0 { to - 8 } else { to + 8 };
+            self.remove(them, PAWN, captured);
+            unmake.captured = Some((PAWN, captured));
+        } else if let Some(captured) = self.kind_at(them, to) {
+            self.remove(them, captured, to);
+            unmake.captured = Some((captured, to));
+        }
+
+        self.remove(us, kind, from);
+        let new_kind = chess_move.promotion.map_or(kind, kind_index);
+        self.put(us, new_kind, to);
+
+        self.en_passant = chess_move.double_push.then_some((from + to) / 2);
+        self.castling &= !(castling_rights_lost(from) | castling_rights_lost(to));
+        self.side_to_move = opponent(self.side_to_move);
+        self.hash ^=
+            previous_keys ^ castling_key(self.castling) ^ self.en_passant_hash() ^ side_key();
+        unmake
+    }
+
+    pub fn unmake(&mut self, chess_move: &Move, unmake: Unmake) {
+        self.side_to_move = opponent(self.side_to_move);
+        let us = color_index(self.side_to_move);
+        let from = square_index(chess_move.from);
+        let to = square_index(chess_move.to);
+
+        let moved_kind = self.kind_at(us, to).expect("The moved piece is there");
+        self.remove(us, moved_kind, to);
+        let kind = if chess_move.promotion.is_some() {
+            PAWN
+        } else {
+            moved_kind
+        };
+        self.put(us, kind, from);
+
+        if chess_move.castle {
+            let (rook_from, rook_to) = Position::rook_castling_squares(chess_move);
+            self.remove(us, ROOK, rook_to);
+            self.put(us, ROOK, rook_from);
+        }
+        if let Some((captured, square)) = unmake.captured {
+            self.put(1 - us, captured, square);
+        }
+        self.castling = unmake.castling;
+        self.en_passant = unmake.en_passant;
+        self.hash = unmake.hash;
+    }
+
+    pub fn perft(&mut self, depth: u32) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+        let moves = self.legal_moves();
+        if depth == 1 {
+            return moves.len() as u64;
+        }
+        let mut nodes = 0;
+        for chess_move in &moves {
+            let unmake = self.make(chess_move);
+            nodes += self.perft(depth - 1);
+            self.unmake(chess_move, unmake);
+        }
+        nodes
+    }
+}
+
+impl Board {
+    // the castling rights as bits, based on which kings and rooks haven't moved yet
+    pub(crate) fn castling_bits(&self) -> u8 {
+        let mut castling = 0;
+        for (right, king, rook) in [
+            (WHITE_KINGSIDE, (5, 1), (8, 1)),
+            (WHITE_QUEENSIDE, (5, 1), (1, 1)),
+            (BLACK_KINGSIDE, (5, 8), (8, 8)),
+            (BLACK_QUEENSIDE, (5, 8), (1, 8)),
+        ] {
+            let unmoved = |(x, y), piece_type| {
+                self.get_piece(Coordinates { x, y }).is_some_and(|piece| {
+                    piece.piece_type == piece_type
+                        && piece.piece_color
+                            == if y == 1 {
+                                PieceColor::White
+                            } else {
+                                PieceColor::Black
+                            }
+                })
+            };
+            if unmoved(king, PieceType::King { moved: false })
+                && unmoved(rook, PieceType::Rook { moved: false })
+            {
+                castling |= right;
+            }
+        }
+        castling
+    }
+}
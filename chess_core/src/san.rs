@@ -0,0 +1,172 @@
+use crate::board::Board;
+use crate::chess_pieces::*;
+use crate::coordinates::Coordinates;
+use crate::moves::get_possible_moves;
+
+fn piece_letter(piece_type: PieceType) -> &'static str {
+    match piece_type {
+        PieceType::King { .. } => "K",
+        PieceType::Queen => "Q",
+        PieceType::Rook { .. } => "R",
+        PieceType::Bishop => "B",
+        PieceType::Knight => "N",
+        PieceType::Pawn { .. } => "",
+    }
+}
+
+fn same_kind(a: PieceType, b: PieceType) -> bool {
+    std::mem::discriminant(&a) == std::mem::discriminant(&b)
+}
+
+fn is_castling(board: &Board, piece: &Piece, to: Coordinates) -> bool {
+    matches!(piece.piece_type, PieceType::King { .. })
+        && board.get_piece(to).is_some_and(|target| {
+            target.piece_color == piece.piece_color
+                && matches!(target.piece_type, PieceType::Rook { .. })
+        })
+}
+
+// the file, rank or both of the moving piece when another piece of the same kind
+// could move to the same field
+fn disambiguation(board: &Board, piece: &Piece, to: Coordinates) -> String {
+    let mut rivals = Vec::new();
+    for field in board.fields.iter().flatten() {
+        if let Some(other) = &field.piece {
+            if other.coordinates != piece.coordinates
+                && other.piece_color == piece.piece_color
+                && same_kind(other.piece_type, piece.piece_type)
+                && get_possible_moves(other, board, true).contains(&to)
+            {
+                rivals.push(other.coordinates);
+            }
+        }
+    }
+
+    let square = piece.coordinates.to_algebraic();
+    if rivals.is_empty() {
+        String::new()
+    } else if rivals.iter().all(|rival| rival.x != piece.coordinates.x) {
+        square[..1].to_string()
+    } else if rivals.iter().all(|rival| rival.y != piece.coordinates.y) {
+        square[1..].to_string()
+    } else {
+        square
+    }
+}
+
+// "+" if the move gives check, "#" if it gives mate
+fn check_suffix(
+    board: &Board,
+    from: Coordinates,
+    to: Coordinates,
+    promotion: Option<PieceType>,
+) -> &'static str {
+    let mut after = board.clone();
+    if !after.move_piece(from, to, promotion) {
+        return "";
+    }
+    let opponent = after.side_to_move;
+    if !after.king_in_danger(opponent) {
+        ""
+    } else if after.no_possible_moves(opponent) {
+        "#"
+    } else {
+        "+"
+    }
+}
+
+fn san_without_suffix(
+    board: &Board,
+    piece: &Piece,
+    to: Coordinates,
+    promotion: Option<PieceType>,
+) -> String {
+    let from = piece.coordinates;
+    let mut san = String::new();
+    if is_castling(board, piece, to) {
+        san.push_str(if to.x > from.x { "O-O" } else { "O-O-O" });
+        return san;
+    }
+
+    let capture = board
+        .get_piece(to)
+        .is_some_and(|target| target.piece_color != piece.piece_color)
+        || board.en_passant_capture(from, to).is_some();
+
+    if let PieceType::Pawn { .. } = piece.piece_type {
+        if capture {
+            san.push_str(&from.to_algebraic()[..1]);
+        }
+    } else {
+        san.push_str(piece_letter(piece.piece_type));
+        san.push_str(&disambiguation(board, piece, to));
+    }
+    if capture {
+        san.push('x');
+    }
+    san.push_str(&to.to_algebraic());
+    if board.is_promotion(from, to) {
+        san.push('=');
+        san.push_str(piece_letter(promotion.unwrap_or(PieceType::Queen)));
+    }
+    san
+}
+
+// renders a move in standard algebraic notation, the board being the position before the move
+pub fn to_san(
+    board: &Board,
+    from: Coordinates,
+    to: Coordinates,
+    promotion: Option<PieceType>,
+) -> String {
+    match board.get_piece(from) {
+        Some(piece) => {
+            san_without_suffix(board, piece, to, promotion)
+                + check_suffix(board, from, to, promotion)
+        }
+        None => String::new(),
+    }
+}
+
+// strips annotations and accepts common variations like "0-0" or "e8Q"
+fn normalize_san(san: &str) -> String {
+    let mut san = san.trim_end_matches(['+', '#', '!', '?']).replace('0', "O");
+    let bytes = san.as_bytes();
+    if bytes.len() >= 3
+        && b"QRBN".contains(&bytes[bytes.len() - 1])
+        && (bytes[bytes.len() - 2] == b'1' || bytes[bytes.len() - 2] == b'8')
+    {
+        san.insert(san.len() - 1, '=');
+    }
+    san
+}
+
+// finds the legal move of the side to move that the SAN string describes
+pub fn parse_san(
+    board: &Board,
+    san: &str,
+) -> Option<(Coordinates, Coordinates, Option<PieceType>)> {
+    let san = normalize_san(san);
+    for field in board.fields.iter().flatten() {
+        let piece = match &field.piece {
+            Some(piece) if piece.piece_color == board.side_to_move => piece,
+            _ => continue,
+        };
+        for to in get_possible_moves(piece, board, true) {
+            let promotions: Vec<Option<PieceType>> = if board.is_promotion(piece.coordinates, to) {
+                PROMOTION_PIECES
+                    .iter()
+                    .map(|piece_type| Some(*piece_type))
+                    .collect()
+            } else {
+                vec![None]
+            };
+            for promotion in promotions {
+                if san_without_suffix(board, piece, to, promotion) == san {
+                    return Some((piece.coordinates, to, promotion));
+                }
+            }
+        }
+    }
+    None
+}
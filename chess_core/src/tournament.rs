@@ -0,0 +1,384 @@
+use crate::board::Board;
+use crate::chess_move::Move;
+use crate::chess_pieces::PieceColor;
+use crate::engine::SearchLimits;
+use crate::fen::FenError;
+use crate::game_result::{EndReason, GameResult, Outcome};
+use crate::pgn::{parse_pgn, LoggedMove, MoveLog, PgnError};
+use crate::uci::UciEngine;
+use std::fmt::{self, Display, Formatter};
+
+// the z-score of a 95% confidence interval
+const CONFIDENCE_Z: f64 = 1.96;
+
+// lines are numbered from 1
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EpdError {
+    pub line: usize,
+    pub error: FenError,
+}
+
+impl Display for EpdError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.error)
+    }
+}
+
+impl std::error::Error for EpdError {}
+
+// the value of an EPD operation such as `hmvc 0;`
+fn epd_operation<'a>(operations: &'a str, name: &str) -> Option<&'a str> {
+    operations.split(';').find_map(|operation| {
+        let mut words = operation.split_whitespace();
+        if words.next() == Some(name) {
+            words.next()
+        } else {
+            None
+        }
+    })
+}
+
+// one opening position per line: the first four FEN fields followed by operations, of
+// which only the move counters `hmvc` and `fmvn` are used
+pub fn parse_epd(text: &str) -> Result<Vec<MoveLog>, EpdError> {
+    let mut openings = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        // the fields may be separated by any whitespace; the operations are the rest of the
+        // line, as they may hold quoted strings with spaces
+        let fields: Vec<&str> = line.split_whitespace().take(4).collect();
+        let operations = fields
+            .iter()
+            .fold(line, |rest, field| {
+                rest.trim_start().strip_prefix(field).unwrap_or_default()
+            })
+            .trim_start();
+        let fen = format!(
+            "{} {} {}",
+            fields.join(" "),
+            epd_operation(operations, "hmvc").unwrap_or("0"),
+            epd_operation(operations, "fmvn").unwrap_or("1"),
+        );
+        let board = Board::from_fen(&fen).map_err(|error| EpdError {
+            line: index + 1,
+            error,
+        })?;
+        openings.push(MoveLog::new(board.to_fen()));
+    }
+    Ok(openings)
+}
+
+// the mainline of every game in the file is an opening
+pub fn parse_pgn_openings(text: &str) -> Result<Vec<MoveLog>, PgnError> {
+    parse_pgn(text)?
+        .iter()
+        .enumerate()
+        .map(|(index, game)| game.replay(index + 1).map(|(_, log)| log))
+        .collect()
+}
+
+// a game of the tournament: who plays which color, starting from which opening
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pairing {
+    pub round: usize,
+    pub white: usize,
+    pub black: usize,
+    pub opening: usize,
+}
+
+// every engine plays every other one the given number of games; each opening is played
+// twice, with the colors reversed, so that neither engine profits from a lopsided opening
+pub fn round_robin(engines: usize, games: usize, openings: usize) -> Vec<Pairing> {
+    let mut pairings = Vec::new();
+    for first in 0..engines {
+        for second in first + 1..engines {
+            for game in 0..games {
+                let (white, black) = if game % 2 == 0 {
+                    (first, second)
+                } else {
+                    (second, first)
+                };
+                pairings.push(Pairing {
+                    round: pairings.len() + 1,
+                    white,
+                    black,
+                    opening: (game / 2) % openings.max(1),
+                });
+            }
+        }
+    }
+    pairings
+}
+
+// plays the game from the end of the opening until the rules end it; draws are claimed
+// as soon as possible, an engine failing to move or making an illegal move loses
+pub fn play_game(
+    white: &mut UciEngine,
+    black: &mut UciEngine,
+    opening: &MoveLog,
+    limits: &SearchLimits,
+) -> (MoveLog, GameResult) {
+    let mut board = Board::from_fen(&opening.start_fen).expect("Openings are valid positions");
+    let mut log = MoveLog::new(opening.start_fen.clone());
+    let mut uci_moves = Vec::new();
+    for logged_move in &opening.moves {
+        let chess_move = Move::from_board_target(
+            &board,
+            logged_move.from,
+            logged_move.to,
+            logged_move.promotion,
+        )
+        .expect("Openings are legal games");
+        uci_moves.push(chess_move.to_uci());
+        log.push(logged_move.clone());
+        board.apply(chess_move);
+    }
+
+    let forfeit = |color: PieceColor, reason| match color {
+        PieceColor::White => GameResult::win(PieceColor::Black, reason),
+        PieceColor::Black => GameResult::win(PieceColor::White, reason),
+    };
+    for (color, engine) in [
+        (PieceColor::White, &mut *white),
+        (PieceColor::Black, &mut *black),
+    ] {
+        if engine.new_game().is_err() {
+            return (log, forfeit(color, EndReason::EngineFailure));
+        }
+    }
+
+    loop {
+        let to_move = board.side_to_move;
+        if let Some(result) = board.game_result(to_move).or(board.claimable_draw()) {
+            return (log, result);
+        }
+        let engine = match to_move {
+            PieceColor::White => &mut *white,
+            PieceColor::Black => &mut *black,
+        };
+        let best_move = engine
+            .set_position(&log.start_fen, &uci_moves)
+            .and_then(|_| engine.go(limits, |_| {}));
+        let Ok(uci) = best_move else {
+            return (log, forfeit(to_move, EndReason::EngineFailure));
+        };
+        let Some(chess_move) = Move::from_uci(&board, &uci) else {
+            return (log, forfeit(to_move, EndReason::IllegalMove));
+        };
+        log.push(LoggedMove::new(
+            &board,
+            chess_move.from,
+            chess_move.board_target(),
+            chess_move.promotion,
+        ));
+        uci_moves.push(uci);
+        board.apply(chess_move);
+    }
+}
+
+// the Elo difference the score corresponds to, with the margin of its 95% confidence
+// interval; both are infinite if all games were won or lost
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EloEstimate {
+    pub difference: f64,
+    pub error: f64,
+}
+
+fn elo_from_fraction(fraction: f64) -> f64 {
+    -400.0 * (1.0 / fraction - 1.0).log10()
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Score {
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+}
+
+impl Score {
+    pub fn games(&self) -> u32 {
+        self.wins + self.draws + self.losses
+    }
+
+    pub fn points(&self) -> f64 {
+        self.wins as f64 + self.draws as f64 / 2.0
+    }
+
+    pub fn add(&mut self, other: Score) {
+        self.wins += other.wins;
+        self.draws += other.draws;
+        self.losses += other.losses;
+    }
+
+    pub fn elo(&self) -> Option<EloEstimate> {
+        let games = self.games() as f64;
+        if games == 0.0 {
+            return None;
+        }
+        let mean = self.points() / games;
+        let variance = (self.wins as f64 * (1.0 - mean).powi(2)
+            + self.draws as f64 * (0.5 - mean).powi(2)
+            + self.losses as f64 * mean.powi(2))
+            / games;
+        let margin = CONFIDENCE_Z * (variance / games).sqrt();
+        let low = elo_from_fraction((mean - margin).max(0.0));
+        let high = elo_from_fraction((mean + margin).min(1.0));
+        Some(EloEstimate {
+            difference: elo_from_fraction(mean),
+            error: (high - low) / 2.0,
+        })
+    }
+}
+
+// the results of every engine against every other one
+#[derive(Debug, Clone, PartialEq)]
+pub struct Crosstable {
+    pub names: Vec<String>,
+    // the score of the engine of the row against the engine of the column
+    scores: Vec<Vec<Score>>,
+}
+
+impl Crosstable {
+    pub fn new(names: Vec<String>) -> Crosstable {
+        let scores = vec![vec![Score::default(); names.len()]; names.len()];
+        Crosstable { names, scores }
+    }
+
+    pub fn add(&mut self, pairing: &Pairing, outcome: Outcome) {
+        let (white, black) = (pairing.white, pairing.black);
+        match outcome {
+            Outcome::WhiteWins => {
+                self.scores[white][black].wins += 1;
+                self.scores[black][white].losses += 1;
+            }
+            Outcome::BlackWins => {
+                self.scores[white][black].losses += 1;
+                self.scores[black][white].wins += 1;
+            }
+            Outcome::Draw => {
+                self.scores[white][black].draws += 1;
+                self.scores[black][white].draws += 1;
+            }
+        }
+    }
+
+    pub fn score(&self, engine: usize, opponent: usize) -> Score {
+        self.scores[engine][opponent]
+    }
+
+    // the score against all opponents together
+    pub fn total(&self, engine: usize) -> Score {
+        let mut total = Score::default();
+        for score in &self.scores[engine] {
+            total.add(*score);
+        }
+        total
+    }
+
+    // engine indices from the most points to the fewest
+    pub fn ranking(&self) -> Vec<usize> {
+        let mut ranking: Vec<usize> = (0..self.names.len()).collect();
+        ranking.sort_by(|a, b| self.total(*b).points().total_cmp(&self.total(*a).points()));
+        ranking
+    }
+}
+
+fn format_points(points: f64) -> String {
+    if points.fract() == 0.0 {
+        format!("{}", points)
+    } else {
+        format!("{:.1}", points)
+    }
+}
+
+fn format_elo(elo: f64) -> String {
+    if elo.is_finite() {
+        // adding zero turns -0 into 0
+        format!("{:+.0}", elo + 0.0)
+    } else if elo > 0.0 {
+        "+inf".to_string()
+    } else {
+        "-inf".to_string()
+    }
+}
+
+// the standings, with the Elo difference of every engine to the rest of the field,
+// followed by the results of every pairing
+impl Display for Crosstable {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let width = self
+            .names
+            .iter()
+            .map(|name| name.len())
+            .max()
+            .unwrap_or(0)
+            .max(4);
+        writeln!(
+            f,
+            "{:>4}  {:<width$}  {:>6}  {:>6}  {:>5}  {:>6}  {:>6}",
+            "Rank", "Name", "Elo", "+/-", "Games", "Score", "Draws"
+        )?;
+        for (rank, engine) in self.ranking().into_iter().enumerate() {
+            let total = self.total(engine);
+            let (elo, error) = match total.elo() {
+                Some(estimate) if estimate.error.is_finite() => (
+                    format_elo(estimate.difference),
+                    format!("{:.0}", estimate.error),
+                ),
+                Some(estimate) => (format_elo(estimate.difference), "inf".to_string()),
+                None => ("-".to_string(), "-".to_string()),
+            };
+            let games = total.games().max(1) as f64;
+            writeln!(
+                f,
+                "{:>4}  {:<width$}  {:>6}  {:>6}  {:>5}  {:>5.1}%  {:>5.1}%",
+                rank + 1,
+                self.names[engine],
+                elo,
+                error,
+                total.games(),
+                100.0 * total.points() / games,
+                100.0 * total.draws as f64 / games
+            )?;
+        }
+
+        let cells: Vec<Vec<String>> = (0..self.names.len())
+            .map(|engine| {
+                (0..self.names.len())
+                    .map(|opponent| {
+                        let score = self.score(engine, opponent);
+                        if engine == opponent {
+                            "-".to_string()
+                        } else {
+                            format!("{}/{}", format_points(score.points()), score.games())
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        let cell_width = cells
+            .iter()
+            .flatten()
+            .map(|cell| cell.len())
+            .chain(self.names.iter().map(|name| name.len()))
+            .max()
+            .unwrap_or(0);
+        writeln!(f)?;
+        write!(f, "{:<width$}", "")?;
+        for name in &self.names {
+            write!(f, "  {:>cell_width$}", name)?;
+        }
+        writeln!(f)?;
+        for (name, row) in self.names.iter().zip(&cells) {
+            write!(f, "{:<width$}", name)?;
+            for cell in row {
+                write!(f, "  {:>cell_width$}", cell)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,237 @@
+use crate::engine::SearchLimits;
+use crate::fen::STARTING_FEN;
+use std::fmt::{self, Display, Formatter};
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// how long an engine may take to answer `uci` and `isready`
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+// how long an engine may take to exit after `quit` before it is killed
+const QUIT_TIMEOUT: Duration = Duration::from_secs(1);
+// how long an engine searching to a depth or a number of nodes may think before it is
+// given up on
+const SEARCH_TIMEOUT: Duration = Duration::from_secs(120);
+
+#[derive(Debug)]
+pub enum UciError {
+    Spawn {
+        command: String,
+        error: std::io::Error,
+    },
+    Io(std::io::Error),
+    Timeout(&'static str),
+    EngineExited,
+    NoBestMove,
+}
+
+impl Display for UciError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            UciError::Spawn { command, error } => {
+                write!(f, "can't start engine \"{}\": {}", command, error)
+            }
+            UciError::Io(error) => write!(f, "can't talk to the engine: {}", error),
+            UciError::Timeout(expected) => write!(f, "engine didn't answer with {}", expected),
+            UciError::EngineExited => write!(f, "engine exited unexpectedly"),
+            UciError::NoBestMove => write!(f, "engine didn't find any move"),
+        }
+    }
+}
+
+impl std::error::Error for UciError {}
+
+impl From<std::io::Error> for UciError {
+    fn from(error: std::io::Error) -> Self {
+        UciError::Io(error)
+    }
+}
+
+// a running engine speaking the Universal Chess Interface, started once and asked for
+// any number of moves; it is told to quit when dropped
+pub struct UciEngine {
+    process: Child,
+    stdin: ChildStdin,
+    // lines the engine writes, read on a separate thread so that reading can time out
+    lines: Receiver<String>,
+    // from the `id name` line
+    pub name: Option<String>,
+}
+
+impl UciEngine {
+    // starts the engine and waits until it is ready
+    pub fn start(command: &str, args: &[String]) -> Result<UciEngine, UciError> {
+        let mut process = Command::new(command)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|error| UciError::Spawn {
+                command: command.to_string(),
+                error,
+            })?;
+        let stdin = process.stdin.take().expect("stdin is piped");
+        let stdout = process.stdout.take().expect("stdout is piped");
+        let (sender, lines) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let mut engine = UciEngine {
+            process,
+            stdin,
+            lines,
+            name: None,
+        };
+        engine.send("uci")?;
+        loop {
+            let line = engine.read_line(HANDSHAKE_TIMEOUT, "uciok")?;
+            if let Some(name) = line.strip_prefix("id name ") {
+                engine.name = Some(name.to_string());
+            } else if line.trim() == "uciok" {
+                break;
+            }
+        }
+        engine.wait_until_ready()?;
+        Ok(engine)
+    }
+
+    fn send(&mut self, command: &str) -> Result<(), UciError> {
+        writeln!(self.stdin, "{}", command)?;
+        self.stdin.flush()?;
+        Ok(())
+    }
+
+    fn read_line(&mut self, timeout: Duration, expected: &'static str) -> Result<String, UciError> {
+        self.lines
+            .recv_timeout(timeout)
+            .map_err(|error| match error {
+                RecvTimeoutError::Timeout => UciError::Timeout(expected),
+                RecvTimeoutError::Disconnected => UciError::EngineExited,
+            })
+    }
+
+    pub fn wait_until_ready(&mut self) -> Result<(), UciError> {
+        self.send("isready")?;
+        while self.read_line(HANDSHAKE_TIMEOUT, "readyok")?.trim() != "readyok" {}
+        Ok(())
+    }
+
+    pub fn set_option(&mut self, name: &str, value: &str) -> Result<(), UciError> {
+        self.send(&format!("setoption name {} value {}", name, value))
+    }
+
+    // tells the engine that the next positions come from a different game
+    pub fn new_game(&mut self) -> Result<(), UciError> {
+        self.send("ucinewgame")?;
+        self.wait_until_ready()
+    }
+
+    // the position the game started from with the moves played since, in UCI notation
+    pub fn set_position(&mut self, start_fen: &str, moves: &[String]) -> Result<(), UciError> {
+        let mut command = if start_fen == STARTING_FEN {
+            "position startpos".to_string()
+        } else {
+            format!("position fen {}", start_fen)
+        };
+        if !moves.is_empty() {
+            command.push_str(" moves ");
+            command.push_str(&moves.join(" "));
+        }
+        self.send(&command)
+    }
+
+    // searches the position set last and returns the best move in UCI notation as soon as
+    // the engine sends it; `info` lines are passed on while the engine is thinking
+    pub fn go(
+        &mut self,
+        limits: &SearchLimits,
+        mut on_info: impl FnMut(&str),
+    ) -> Result<String, UciError> {
+        self.send(&go_command(limits))?;
+        let deadline = Instant::now() + search_time_limit(limits);
+        loop {
+            let timeout = deadline.saturating_duration_since(Instant::now());
+            let line = match self.read_line(timeout, "bestmove") {
+                // a hanging engine would keep on searching, or never read anything again
+                Err(UciError::Timeout(expected)) => {
+                    let _ = self.process.kill();
+                    return Err(UciError::Timeout(expected));
+                }
+                line => line?,
+            };
+            if line.starts_with("info") {
+                on_info(&line);
+            } else if let Some(rest) = line.strip_prefix("bestmove") {
+                return match rest.split_whitespace().next() {
+                    Some("(none)") | Some("0000") | None => Err(UciError::NoBestMove),
+                    Some(best_move) => Ok(best_move.to_string()),
+                };
+            }
+        }
+    }
+}
+
+// the longest a search may take: the fixed time for the move or the time left on the clocks,
+// otherwise `SEARCH_TIMEOUT`, with some leeway either way
+fn search_time_limit(limits: &SearchLimits) -> Duration {
+    let clocks = [(limits.wtime, limits.winc), (limits.btime, limits.binc)]
+        .into_iter()
+        .filter_map(|(time, increment)| time.map(|time| time + increment.unwrap_or_default()))
+        .max();
+    let search_time = match (limits.movetime, clocks) {
+        (Some(movetime), Some(clocks)) => movetime.min(clocks),
+        (movetime, clocks) => movetime.or(clocks).unwrap_or(SEARCH_TIMEOUT),
+    };
+    search_time + HANDSHAKE_TIMEOUT
+}
+
+// most engines search until they are stopped if there are no limits at all
+fn go_command(limits: &SearchLimits) -> String {
+    let mut command = "go".to_string();
+    let times = [
+        ("wtime", limits.wtime),
+        ("btime", limits.btime),
+        ("winc", limits.winc),
+        ("binc", limits.binc),
+        ("movetime", limits.movetime),
+    ];
+    for (name, time) in times {
+        if let Some(time) = time {
+            command.push_str(&format!(" {} {}", name, time.as_millis()));
+        }
+    }
+    if let Some(depth) = limits.depth {
+        command.push_str(&format!(" depth {}", depth));
+    }
+    if let Some(nodes) = limits.nodes {
+        command.push_str(&format!(" nodes {}", nodes));
+    }
+    command
+}
+
+impl Drop for UciEngine {
+    fn drop(&mut self) {
+        if self.send("quit").is_ok() {
+            let start = Instant::now();
+            while start.elapsed() < QUIT_TIMEOUT {
+                if !matches!(self.process.try_wait(), Ok(None)) {
+                    return;
+                }
+                thread::sleep(Duration::from_millis(10));
+            }
+        }
+        let _ = self.process.kill();
+        let _ = self.process.wait();
+    }
+}
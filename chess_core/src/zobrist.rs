@@ -0,0 +1,48 @@
+// random keys xor-ed together into a 64-bit hash of a position: one for every kind of piece
+// of each color on each square, one for black to move, one for each castling right and one
+// for each file an en passant capture can happen on. They are generated at compile time from
+// a fixed seed, so hashes stay the same between builds and can be stored
+
+const PIECE_KEYS: usize = 2 * 6 * 64;
+const SIDE_KEY: usize = PIECE_KEYS;
+const CASTLING_KEYS: usize = SIDE_KEY + 1;
+const EN_PASSANT_KEYS: usize = CASTLING_KEYS + 4;
+const KEY_COUNT: usize = EN_PASSANT_KEYS + 8;
+
+// the splitmix64 generator
+const fn generate_keys() -> [u64; KEY_COUNT] {
+    let mut keys = [0; KEY_COUNT];
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut i = 0;
+    while i < KEY_COUNT {
+        state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        keys[i] = z ^ (z >> 31);
+        i += 1;
+    }
+    keys
+}
+
+const KEYS: [u64; KEY_COUNT] = generate_keys();
+
+// color and kind are the indices used by `Position`, white and the king first
+pub fn piece_key(color: usize, kind: usize, square: usize) -> u64 {
+    KEYS[(color * 6 + kind) * 64 + square]
+}
+
+pub fn side_key() -> u64 {
+    KEYS[SIDE_KEY]
+}
+
+// the rights are the bits used by `Position`
+pub fn castling_key(rights: u8) -> u64 {
+    (0..4)
+        .filter(|right| rights & (1 << right) != 0)
+        .fold(0, |key, right| key ^ KEYS[CASTLING_KEYS + right])
+}
+
+pub fn en_passant_key(square: Option<usize>) -> u64 {
+    square.map_or(0, |square| KEYS[EN_PASSANT_KEYS + square % 8])
+}
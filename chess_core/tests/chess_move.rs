@@ -0,0 +1,99 @@
+use chess_core::board::Board;
+use chess_core::chess_move::Move;
+use chess_core::chess_pieces::PieceType;
+use chess_core::coordinates::Coordinates;
+
+fn coords(square: &str) -> Coordinates {
+    Coordinates::from_algebraic(square).unwrap()
+}
+
+fn uci_move(board: &Board, uci: &str) -> Move {
+    Move::from_uci(board, uci).unwrap_or_else(|| panic!("{uci} is legal"))
+}
+
+#[test]
+fn uci_castling_moves_the_king_onto_its_rook() {
+    let board = Board::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+    for (uci, rook, san) in [("e1g1", "h1", "O-O"), ("e1c1", "a1", "O-O-O")] {
+        let castling = uci_move(&board, uci);
+        assert!(castling.castle);
+        assert_eq!(castling.from, coords("e1"));
+        assert_eq!(castling.board_target(), coords(rook));
+        assert_eq!(castling.to_uci(), uci);
+        assert_eq!(castling.to_san(&board), san);
+        assert_eq!(Move::from_san(&board, san), Some(castling));
+        assert_eq!(
+            Move::from_board_target(&board, coords("e1"), coords(rook), None),
+            Some(castling)
+        );
+    }
+
+    let mut board = board;
+    board.apply(uci_move(&board, "e1g1")).unwrap();
+    assert_eq!(board.to_fen(), "r3k2r/8/8/8/8/8/8/R4RK1 b kq - 1 1");
+    board.apply(uci_move(&board, "e8c8")).unwrap();
+    assert_eq!(board.to_fen(), "2kr3r/8/8/8/8/8/8/R4RK1 w - - 2 2");
+}
+
+#[test]
+fn underpromotions_keep_their_piece() {
+    let mut board = Board::from_fen("1k6/4P3/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+    let promotion = uci_move(&board, "e7e8n");
+    assert_eq!(promotion.promotion, Some(PieceType::Knight));
+    assert_eq!(promotion.to_uci(), "e7e8n");
+    assert_eq!(promotion.to_san(&board), "e8=N");
+    assert_eq!(Move::from_san(&board, "e8=N"), Some(promotion));
+    // a promotion has to name its piece in UCI
+    assert_eq!(Move::from_uci(&board, "e7e8"), None);
+
+    board.apply(promotion).unwrap();
+    assert_eq!(board.to_fen(), "1k2N3/8/8/8/8/8/8/4K3 b - - 0 1");
+}
+
+#[test]
+fn en_passant_and_double_pushes_are_flagged() {
+    let board = Board::from_fen("4k3/8/8/3pP3/8/8/5P2/4K3 w - d6 0 1").unwrap();
+    let capture = uci_move(&board, "e5d6");
+    assert!(capture.en_passant);
+    assert!(capture.capture);
+    assert!(!capture.double_push);
+    assert_eq!(capture.to_san(&board), "exd6");
+
+    let double_push = uci_move(&board, "f2f4");
+    assert!(double_push.double_push);
+    assert!(!double_push.en_passant && !double_push.capture);
+
+    let push = uci_move(&board, "e5e6");
+    assert!(!push.en_passant && !push.capture && !push.double_push);
+}
+
+#[test]
+fn malformed_and_illegal_uci_is_rejected() {
+    let board = Board::from_fen("4k3/4P3/8/8/8/8/4P3/R3K3 w - - 0 1").unwrap();
+    for uci in [
+        "", "e2", "e2e", "e2e9", "i2i4", "e2-e4", "e2e4x", "e2e5", "e1g1", "e1c1", "e8e7", "e7e8k",
+        "e7e8p",
+    ] {
+        assert_eq!(Move::from_uci(&board, uci), None, "{uci}");
+    }
+}
+
+#[test]
+fn applied_moves_are_undone_back_to_the_same_position() {
+    let mut board = Board::from_fen("r3k2r/1P3p2/8/4P3/8/8/8/R3K2R b KQkq - 0 1").unwrap();
+    let mut history = vec![(board.to_fen(), board.hash())];
+    let mut records = Vec::new();
+    for uci in ["f7f5", "e5f6", "e8g8", "b7a8r", "f8a8", "e1c1"] {
+        let chess_move = uci_move(&board, uci);
+        records.push(board.apply(chess_move).unwrap());
+        assert_eq!(board.hash(), board.position().hash(), "{uci}");
+        history.push((board.to_fen(), board.hash()));
+    }
+    assert_eq!(board.to_fen(), "r5k1/8/5P2/8/8/8/8/2KR3R b - - 1 4");
+
+    for record in records.iter().rev() {
+        history.pop();
+        board.undo_move(record);
+        assert_eq!(Some(&(board.to_fen(), board.hash())), history.last());
+    }
+}
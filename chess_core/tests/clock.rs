@@ -0,0 +1,145 @@
+use chess_core::board::Board;
+use chess_core::chess_pieces::PieceColor;
+use chess_core::clock::{ChessClock, IncrementMode, TimeControl};
+use chess_core::engine::SearchLimits;
+use chess_core::game_result::{EndReason, GameResult};
+use std::time::Duration;
+
+fn seconds(seconds: u64) -> Duration {
+    Duration::from_secs(seconds)
+}
+
+fn clock(mode: IncrementMode) -> ChessClock {
+    let control = TimeControl {
+        mode,
+        ..TimeControl::new(1, 5)
+    };
+    ChessClock::new(control, PieceColor::White)
+}
+
+#[test]
+fn fischer_increment_is_added_after_every_move() {
+    let mut clock = clock(IncrementMode::Fischer);
+    clock.tick(seconds(2));
+    clock.press();
+    assert_eq!(clock.remaining(PieceColor::White), seconds(63));
+    assert_eq!(clock.running(), Some(PieceColor::Black));
+
+    clock.tick(seconds(10));
+    assert_eq!(clock.remaining(PieceColor::Black), seconds(50));
+    assert_eq!(clock.remaining(PieceColor::White), seconds(63));
+}
+
+#[test]
+fn bronstein_delay_gives_back_at_most_the_time_spent() {
+    let mut clock = clock(IncrementMode::Bronstein);
+    clock.tick(seconds(2));
+    clock.press();
+    assert_eq!(clock.remaining(PieceColor::White), seconds(60));
+
+    clock.tick(seconds(8));
+    clock.press();
+    assert_eq!(clock.remaining(PieceColor::Black), seconds(57));
+}
+
+#[test]
+fn simple_delay_passes_before_the_clock_runs() {
+    let mut clock = clock(IncrementMode::SimpleDelay);
+    clock.tick(seconds(3));
+    assert_eq!(clock.remaining(PieceColor::White), seconds(60));
+    clock.tick(seconds(4));
+    assert_eq!(clock.remaining(PieceColor::White), seconds(58));
+    clock.press();
+    assert_eq!(clock.remaining(PieceColor::White), seconds(58));
+
+    clock.tick(seconds(5));
+    assert_eq!(clock.remaining(PieceColor::Black), seconds(60));
+}
+
+#[test]
+fn side_to_move_flags_when_its_time_runs_out() {
+    let mut clock = clock(IncrementMode::Fischer);
+    clock.tick(seconds(59));
+    assert_eq!(clock.flagged(), None);
+    clock.tick(seconds(2));
+    assert_eq!(clock.remaining(PieceColor::White), Duration::ZERO);
+    assert_eq!(clock.flagged(), Some(PieceColor::White));
+
+    // the increment doesn't save a side that already ran out of time
+    clock.press();
+    assert_eq!(clock.remaining(PieceColor::White), Duration::ZERO);
+}
+
+#[test]
+fn engines_are_told_both_clocks() {
+    let mut clock = clock(IncrementMode::Fischer);
+    clock.tick(seconds(10));
+    let limits = clock.search_limits(SearchLimits {
+        depth: Some(4),
+        ..Default::default()
+    });
+    assert_eq!(limits.depth, Some(4));
+    assert_eq!(limits.wtime, Some(seconds(50)));
+    assert_eq!(limits.btime, Some(seconds(60)));
+    assert_eq!(limits.winc, Some(seconds(5)));
+    assert_eq!(limits.binc, Some(seconds(5)));
+}
+
+#[test]
+fn timeout_is_a_draw_if_the_opponent_cannot_checkmate() {
+    let board = Board::from_fen("4k3/8/8/8/8/8/3N4/4K3 b - - 0 1").unwrap();
+    assert_eq!(
+        board.timeout_result(PieceColor::Black),
+        GameResult::draw(EndReason::TimeoutVsInsufficientMaterial)
+    );
+    assert_eq!(
+        board.timeout_result(PieceColor::White),
+        GameResult::draw(EndReason::TimeoutVsInsufficientMaterial)
+    );
+
+    let board = Board::from_fen("4k3/4p3/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+    assert_eq!(
+        board.timeout_result(PieceColor::White),
+        GameResult::win(PieceColor::Black, EndReason::Timeout)
+    );
+
+    // bishops all on the same color can't mate whoever has them
+    let board = Board::from_fen("4k3/8/8/8/5b2/8/8/2B1K3 w - - 0 1").unwrap();
+    assert_eq!(
+        board.timeout_result(PieceColor::White),
+        GameResult::draw(EndReason::TimeoutVsInsufficientMaterial)
+    );
+}
+
+#[test]
+fn timeout_loses_to_a_lone_minor_piece_if_a_mate_is_possible() {
+    // the pawn or the rook can block the king in, so the knight or bishop could mate
+    for fen in [
+        "4k3/4p3/8/8/8/8/3N4/4K3 b - - 0 1",
+        "4k3/r7/8/8/8/8/3B4/4K3 b - - 0 1",
+    ] {
+        let board = Board::from_fen(fen).unwrap();
+        assert_eq!(
+            board.timeout_result(PieceColor::Black),
+            GameResult::win(PieceColor::White, EndReason::Timeout),
+            "{fen}"
+        );
+    }
+    // the side with the pawn still wins on time against the knight too
+    let board = Board::from_fen("4k3/4p3/8/8/8/8/3N4/4K3 w - - 0 1").unwrap();
+    assert_eq!(
+        board.timeout_result(PieceColor::White),
+        GameResult::win(PieceColor::Black, EndReason::Timeout)
+    );
+}
+
+#[test]
+fn time_controls_are_shown_in_minutes_and_seconds() {
+    assert_eq!(TimeControl::new(5, 3).to_string(), "5+3");
+    assert_eq!(TimeControl::new(5, 3).pgn_tag(), "300+3");
+    let delay = TimeControl {
+        mode: IncrementMode::SimpleDelay,
+        ..TimeControl::new(90, 30)
+    };
+    assert_eq!(delay.to_string(), "90+30 simple delay");
+}
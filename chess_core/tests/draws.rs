@@ -0,0 +1,177 @@
+use chess_core::board::Board;
+use chess_core::chess_pieces::PieceColor;
+use chess_core::coordinates::Coordinates;
+use chess_core::game_result::{EndReason, GameResult};
+
+fn board(fen: &str) -> Board {
+    Board::from_fen(fen).unwrap()
+}
+
+fn play(board: &mut Board, from: &str, to: &str) {
+    let from = Coordinates::from_algebraic(from).unwrap();
+    let to = Coordinates::from_algebraic(to).unwrap();
+    assert!(board.is_legal_move(from, to), "{from} {to}");
+    assert!(board.move_piece(from, to, None));
+}
+
+fn result(board: &Board) -> Option<GameResult> {
+    board.game_result(board.side_to_move)
+}
+
+#[test]
+fn stalemate_is_a_draw() {
+    let board = board("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1");
+    assert_eq!(result(&board), Some(GameResult::draw(EndReason::Stalemate)));
+}
+
+#[test]
+fn fifty_moves_can_be_claimed_and_seventy_five_end_the_game() {
+    let mut fifty = board("4k3/8/8/8/8/8/R7/4K3 w - - 98 80");
+    play(&mut fifty, "a2", "a3");
+    assert_eq!(fifty.claimable_draw(), None);
+    play(&mut fifty, "e8", "d8");
+    assert_eq!(
+        fifty.claimable_draw(),
+        Some(GameResult::draw(EndReason::FiftyMoveRule))
+    );
+    assert_eq!(result(&fifty), None);
+
+    let mut seventy_five = board("4k3/8/8/8/8/8/R7/4K3 w - - 149 80");
+    assert_eq!(result(&seventy_five), None);
+    play(&mut seventy_five, "a2", "a3");
+    assert_eq!(
+        result(&seventy_five),
+        Some(GameResult::draw(EndReason::SeventyFiveMoveRule))
+    );
+}
+
+#[test]
+fn checkmate_on_the_last_move_of_the_move_rules_still_wins() {
+    for halfmove_clock in [99, 149] {
+        let mut board = board(&format!(
+            "6k1/5ppp/8/8/8/8/8/R5K1 w - - {halfmove_clock} 80"
+        ));
+        play(&mut board, "a1", "a8");
+        assert_eq!(
+            result(&board),
+            Some(GameResult::win(PieceColor::White, EndReason::Checkmate))
+        );
+    }
+}
+
+// both knights go out and back, repeating the starting position once more
+fn repeat_position(board: &mut Board) {
+    play(board, "a1", "b3");
+    play(board, "a8", "b6");
+    play(board, "b3", "a1");
+    play(board, "b6", "a8");
+}
+
+#[test]
+fn threefold_repetition_can_be_claimed_and_fivefold_ends_the_game() {
+    let mut board = board("n3k3/8/8/8/8/8/8/N3K3 w - - 0 1");
+    repeat_position(&mut board);
+    assert_eq!(board.claimable_draw(), None);
+    repeat_position(&mut board);
+    assert_eq!(
+        board.claimable_draw(),
+        Some(GameResult::draw(EndReason::ThreefoldRepetition))
+    );
+    assert_eq!(result(&board), None);
+
+    repeat_position(&mut board);
+    play(&mut board, "a1", "b3");
+    play(&mut board, "a8", "b6");
+    play(&mut board, "b3", "a1");
+    let from = Coordinates::from_algebraic("b6").unwrap();
+    let to = Coordinates::from_algebraic("a8").unwrap();
+    let record = board.make_move(from, to, None).unwrap();
+    assert_eq!(
+        result(&board),
+        Some(GameResult::draw(EndReason::FivefoldRepetition))
+    );
+
+    // taking the move back takes its position out of the history too
+    board.undo_move(&record);
+    assert_eq!(result(&board), None);
+    board.make_move(from, to, None).unwrap();
+    assert_eq!(
+        result(&board),
+        Some(GameResult::draw(EndReason::FivefoldRepetition))
+    );
+}
+
+#[test]
+fn undo_takes_back_a_threefold_repetition() {
+    let mut board = board("n3k3/8/8/8/8/8/8/N3K3 w - - 0 1");
+    repeat_position(&mut board);
+    play(&mut board, "a1", "b3");
+    play(&mut board, "a8", "b6");
+    play(&mut board, "b3", "a1");
+    let from = Coordinates::from_algebraic("b6").unwrap();
+    let to = Coordinates::from_algebraic("a8").unwrap();
+    let record = board.make_move(from, to, None).unwrap();
+    assert!(board.claimable_draw().is_some());
+    board.undo_move(&record);
+    assert_eq!(board.claimable_draw(), None);
+}
+
+#[test]
+fn kings_with_a_single_minor_piece_are_a_draw() {
+    for fen in [
+        "4k3/8/8/8/8/8/8/4K3 w - - 0 1",
+        "4k3/8/8/8/8/8/8/2B1K3 w - - 0 1",
+        "4k3/8/8/8/8/8/8/1N2K3 w - - 0 1",
+        "4k3/8/8/8/8/8/8/4K1n1 w - - 0 1",
+    ] {
+        assert_eq!(
+            result(&board(fen)),
+            Some(GameResult::draw(EndReason::InsufficientMaterial)),
+            "{fen}"
+        );
+    }
+}
+
+#[test]
+fn bishops_are_a_draw_only_on_the_same_color() {
+    // c1 and f4 are both dark squares
+    assert_eq!(
+        result(&board("4k3/8/8/8/5b2/8/8/2B1K3 w - - 0 1")),
+        Some(GameResult::draw(EndReason::InsufficientMaterial))
+    );
+    // f5 is a light square
+    assert_eq!(result(&board("4k3/8/8/5b2/8/8/8/2B1K3 w - - 0 1")), None);
+    // a knight and a bishop, or a pawn, can still mate
+    assert_eq!(result(&board("4k3/8/8/8/8/8/8/1NB1K3 w - - 0 1")), None);
+    assert_eq!(result(&board("4k3/8/8/8/8/8/4P3/4K3 w - - 0 1")), None);
+}
+
+// the kings step aside and back, with black to move at the start
+fn shuffle_kings(board: &mut Board) {
+    play(board, "e8", "d8");
+    play(board, "e1", "d1");
+    play(board, "d8", "e8");
+    play(board, "d1", "e1");
+}
+
+#[test]
+fn double_push_without_en_passant_capture_counts_for_repetition() {
+    let mut uncapturable = board("4k3/8/8/8/8/8/4P3/4K3 w - - 0 1");
+    play(&mut uncapturable, "e2", "e4");
+    let after_double_push = uncapturable.hash();
+    shuffle_kings(&mut uncapturable);
+    assert_eq!(uncapturable.hash(), after_double_push);
+    shuffle_kings(&mut uncapturable);
+    assert_eq!(
+        uncapturable.claimable_draw(),
+        Some(GameResult::draw(EndReason::ThreefoldRepetition))
+    );
+
+    // a pawn that can capture en passant makes the position after the double push different
+    let mut capturable = board("4k3/8/8/8/3p4/8/4P3/4K3 w - - 0 1");
+    play(&mut capturable, "e2", "e4");
+    let after_double_push = capturable.hash();
+    shuffle_kings(&mut capturable);
+    assert_ne!(capturable.hash(), after_double_push);
+    assert_eq!(capturable.hash(), capturable.position().hash());
+}
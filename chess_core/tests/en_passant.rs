@@ -0,0 +1,53 @@
+use chess_core::board::Board;
+use chess_core::coordinates::Coordinates;
+
+fn coords(square: &str) -> Coordinates {
+    Coordinates::from_algebraic(square).unwrap()
+}
+
+#[test]
+fn double_push_sets_the_target_and_any_other_move_clears_it() {
+    let mut board = Board::from_fen("4k3/3p4/8/4P3/8/8/8/4K3 b - - 0 1").unwrap();
+    assert!(board.move_piece(coords("d7"), coords("d5"), None));
+    assert_eq!(board.en_passant, Some(coords("d6")));
+
+    // the capture isn't taken up, so the chance is gone
+    assert!(board.move_piece(coords("e1"), coords("d1"), None));
+    assert_eq!(board.en_passant, None);
+    assert!(!board.legal_moves_from(coords("e5")).contains(&coords("d6")));
+
+    // a single step doesn't set a target
+    let mut board = Board::from_fen("4k3/8/8/8/8/8/4P3/4K3 w - - 0 1").unwrap();
+    assert!(board.move_piece(coords("e2"), coords("e3"), None));
+    assert_eq!(board.en_passant, None);
+}
+
+#[test]
+fn en_passant_capture_removes_the_bypassed_pawn() {
+    let mut board = Board::from_fen("4k3/3p4/8/4P3/8/8/8/4K3 b - - 0 1").unwrap();
+    assert!(board.move_piece(coords("d7"), coords("d5"), None));
+    assert!(board.legal_moves_from(coords("e5")).contains(&coords("d6")));
+    assert_eq!(
+        board.en_passant_capture(coords("e5"), coords("d6")),
+        Some(coords("d5"))
+    );
+
+    assert!(board.move_piece(coords("e5"), coords("d6"), None));
+    assert!(board.get_piece(coords("d5")).is_none());
+    assert!(board.get_piece(coords("e5")).is_none());
+    assert!(board.get_piece(coords("d6")).is_some());
+    assert_eq!(board.en_passant, None);
+    assert_eq!(board.to_fen(), "4k3/8/3P4/8/8/8/8/4K3 b - - 0 2");
+}
+
+#[test]
+fn en_passant_is_not_offered_when_it_exposes_the_king() {
+    // both pawns leave the fifth rank, opening it for the rook
+    let board = Board::from_fen("4k3/8/8/KPp4r/8/8/8/8 w - c6 0 1").unwrap();
+    assert_eq!(board.legal_moves_from(coords("b5")), [coords("b6")]);
+    assert!(!board.is_legal_move(coords("b5"), coords("c6")));
+
+    // without the rook the capture is fine
+    let board = Board::from_fen("4k3/8/8/KPp5/8/8/8/8 w - c6 0 1").unwrap();
+    assert!(board.is_legal_move(coords("b5"), coords("c6")));
+}
@@ -0,0 +1,51 @@
+use chess_core::board::Board;
+use chess_core::chess_move::Move;
+use chess_core::engine::{Engine, SearchLimits, MATE_SCORE};
+
+fn best_move(fen: &str, depth: u32) -> (Option<String>, i32) {
+    let board = Board::from_fen(fen).unwrap();
+    let limits = SearchLimits {
+        depth: Some(depth),
+        ..Default::default()
+    };
+    let result = Engine::new().search(&board, limits);
+    (result.best_move.map(|best| best.to_uci()), result.score)
+}
+
+#[test]
+fn finds_back_rank_mate() {
+    let (best, score) = best_move("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1", 3);
+    assert_eq!(best.as_deref(), Some("a1a8"));
+    assert_eq!(score, MATE_SCORE - 1);
+}
+
+#[test]
+fn finds_mate_in_two() {
+    // one rook cuts the king off on the last rank, the other one mates
+    let (best, score) = best_move("7k/8/8/8/8/8/R7/1R4K1 w - - 0 1", 4);
+    assert!(best.is_some());
+    assert_eq!(score, MATE_SCORE - 3);
+}
+
+#[test]
+fn takes_hanging_queen() {
+    let (best, _) = best_move("4k3/8/8/3q4/8/8/8/3RK3 w - - 0 1", 3);
+    assert_eq!(best.as_deref(), Some("d1d5"));
+}
+
+#[test]
+fn no_move_when_mated() {
+    let (best, _) = best_move("R5k1/5ppp/8/8/8/8/8/6K1 b - - 0 1", 3);
+    assert_eq!(best, None);
+}
+
+#[test]
+fn best_move_is_legal() {
+    let board = Board::starting();
+    let limits = SearchLimits {
+        nodes: Some(20_000),
+        ..Default::default()
+    };
+    let best = Engine::new().search(&board, limits).best_move.unwrap();
+    assert_eq!(Move::from_uci(&board, &best.to_uci()), Some(best));
+}
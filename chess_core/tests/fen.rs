@@ -0,0 +1,137 @@
+use chess_core::board::Board;
+use chess_core::chess_pieces::PieceColor;
+use chess_core::coordinates::Coordinates;
+use chess_core::fen::{FenError, STARTING_FEN};
+
+#[test]
+fn positions_round_trip() {
+    for fen in [
+        STARTING_FEN,
+        "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1",
+        "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "r3k2r/8/8/8/8/8/8/R3K2R b Kq - 12 40",
+        "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+    ] {
+        assert_eq!(Board::from_fen(fen).unwrap().to_fen(), fen);
+    }
+}
+
+#[test]
+fn move_counters_may_be_omitted() {
+    let board = Board::from_fen("4k3/8/8/8/8/8/8/4K3 w - -").unwrap();
+    assert_eq!(board.to_fen(), "4k3/8/8/8/8/8/8/4K3 w - - 0 1");
+}
+
+fn fen_error(fen: &str) -> FenError {
+    Board::from_fen(fen).unwrap_err()
+}
+
+#[test]
+fn malformed_fields_are_rejected() {
+    assert_eq!(fen_error(""), FenError::MissingField("piece placement"));
+    assert_eq!(
+        fen_error("4k3/8/8/8/8/8/8/4K3 w"),
+        FenError::MissingField("castling")
+    );
+    assert_eq!(
+        fen_error("4k3/8/8/8/8/8/8/4K3 w - - 0 1 extra"),
+        FenError::TooManyFields(7)
+    );
+    assert_eq!(
+        fen_error("4k3/8/8/8/8/8/4K3 w - - 0 1"),
+        FenError::WrongRankCount(7)
+    );
+    assert_eq!(
+        fen_error("4k3/8/8/8/8/8/8/4K2 w - - 0 1"),
+        FenError::WrongRankLength { rank: 1, length: 7 }
+    );
+    assert_eq!(
+        fen_error("4k3/8/8/8/8/8/8/4K2X w - - 0 1"),
+        FenError::InvalidPiece {
+            rank: 1,
+            symbol: 'X'
+        }
+    );
+    assert_eq!(
+        fen_error("4k3/8/8/8/8/8/8/4K3 x - - 0 1"),
+        FenError::InvalidSideToMove("x".to_string())
+    );
+    assert_eq!(
+        fen_error("4k3/8/8/8/8/8/8/4K3 w - - -1 1"),
+        FenError::InvalidHalfmoveClock("-1".to_string())
+    );
+    assert_eq!(
+        fen_error("4k3/8/8/8/8/8/8/4K3 w - - 0 0"),
+        FenError::InvalidFullMoveNumber("0".to_string())
+    );
+}
+
+#[test]
+fn impossible_positions_are_rejected() {
+    assert_eq!(
+        fen_error("4k3/8/8/8/8/8/8/4KK2 w - - 0 1"),
+        FenError::WrongKingCount {
+            color: PieceColor::White,
+            count: 2
+        }
+    );
+    assert_eq!(
+        fen_error("8/8/8/8/8/8/8/4K3 w - - 0 1"),
+        FenError::WrongKingCount {
+            color: PieceColor::Black,
+            count: 0
+        }
+    );
+    assert_eq!(
+        fen_error("3Pk3/8/8/8/8/8/8/4K3 w - - 0 1"),
+        FenError::PawnOnBackRank(Coordinates { x: 4, y: 8 })
+    );
+    assert_eq!(
+        fen_error("4k3/8/8/8/8/8/8/r3K3 b - - 0 1"),
+        FenError::OpponentInCheck
+    );
+}
+
+#[test]
+fn invalid_castling_is_rejected() {
+    for castling in ["KKq", "KQkqk", "x", "Kx"] {
+        assert_eq!(
+            fen_error(&format!("r3k2r/8/8/8/8/8/8/R3K2R w {castling} - 0 1")),
+            FenError::InvalidCastling(castling.to_string())
+        );
+    }
+    // the king or the rook isn't on its starting field
+    assert_eq!(
+        fen_error("r3k2r/8/8/8/8/8/8/R4K1R w K - 0 1"),
+        FenError::InvalidCastling("K".to_string())
+    );
+    assert_eq!(
+        fen_error("r3k2r/8/8/8/8/8/8/R3K1R1 w K - 0 1"),
+        FenError::InvalidCastling("K".to_string())
+    );
+}
+
+#[test]
+fn invalid_en_passant_is_rejected() {
+    for (fen, square) in [
+        // not a square
+        ("4k3/8/8/8/4P3/8/8/4K3 b - e9 0 1", "e9"),
+        // not on the third or sixth rank
+        ("4k3/8/8/8/4P3/8/8/4K3 b - e4 0 1", "e4"),
+        ("4k3/8/8/8/4P3/8/8/4K3 w - e3 0 1", "e3"),
+        // no pawn that could have just moved past it
+        ("4k3/8/8/8/8/8/8/4K3 b - e3 0 1", "e3"),
+        ("4k3/8/8/8/4p3/8/8/4K3 b - e3 0 1", "e3"),
+        // the square itself or the one the pawn came from isn't empty
+        ("4k3/8/8/8/4P3/4N3/8/4K3 b - e3 0 1", "e3"),
+        ("4k3/8/8/8/4P3/8/4N3/4K3 b - e3 0 1", "e3"),
+        ("4k3/4n3/4n3/4p3/8/8/8/4K3 w - e6 0 1", "e6"),
+    ] {
+        assert_eq!(
+            fen_error(fen),
+            FenError::InvalidEnPassant(square.to_string()),
+            "{fen}"
+        );
+    }
+}
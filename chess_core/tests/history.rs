@@ -0,0 +1,148 @@
+use chess_core::board::Board;
+use chess_core::chess_move::Move;
+use chess_core::chess_pieces::PieceColor;
+use chess_core::clock::{ChessClock, TimeControl};
+use chess_core::fen::STARTING_FEN;
+use chess_core::history::MoveHistory;
+use chess_core::pgn::{LoggedMove, MoveLog};
+use std::time::Duration;
+
+// a game as the app keeps it while it is played
+struct Game {
+    board: Board,
+    log: MoveLog,
+    clock: Option<ChessClock>,
+    history: MoveHistory,
+}
+
+impl Game {
+    fn new(clock: Option<ChessClock>) -> Game {
+        Game {
+            board: Board::starting(),
+            log: MoveLog::default(),
+            clock,
+            history: MoveHistory::default(),
+        }
+    }
+
+    // thinks for the given number of seconds, then plays the move and presses the clock
+    fn play(&mut self, uci: &str, seconds: u64) {
+        if let Some(clock) = self.clock.as_mut() {
+            clock.tick(Duration::from_secs(seconds));
+        }
+        let chess_move = Move::from_uci(&self.board, uci).unwrap();
+        let to = chess_move.board_target();
+        let logged_move = LoggedMove::new(&self.board, chess_move.from, to, chess_move.promotion);
+        let record = self.board.apply(chess_move).unwrap();
+        self.log.push(logged_move);
+        self.history.push(record, self.clock);
+        if let Some(clock) = self.clock.as_mut() {
+            clock.press();
+        }
+    }
+
+    fn undo(&mut self, count: usize) -> bool {
+        self.history
+            .undo(count, &mut self.board, &mut self.log, &mut self.clock)
+    }
+
+    fn redo(&mut self, count: usize) -> bool {
+        self.history
+            .redo(count, &mut self.board, &mut self.log, &mut self.clock)
+    }
+
+    fn sans(&self) -> Vec<&str> {
+        self.log
+            .moves
+            .iter()
+            .map(|logged| logged.san.as_str())
+            .collect()
+    }
+}
+
+#[test]
+fn moves_are_taken_back_and_played_again() {
+    let mut game = Game::new(None);
+    game.play("e2e4", 0);
+    game.play("e7e5", 0);
+    game.play("g1f3", 0);
+    let after_three = game.board.to_fen();
+
+    assert!(game.undo(1));
+    assert_eq!(game.sans(), ["e4", "e5"]);
+    assert_eq!(game.history.played().len(), 2);
+    assert!(game.undo(1));
+    assert!(game.undo(1));
+    assert_eq!(game.board.to_fen(), STARTING_FEN);
+    assert!(game.sans().is_empty());
+    assert!(!game.undo(1));
+
+    assert!(game.redo(1));
+    assert_eq!(game.sans(), ["e4"]);
+    assert!(game.redo(1));
+    assert!(game.redo(1));
+    assert_eq!(game.board.to_fen(), after_three);
+    assert_eq!(game.sans(), ["e4", "e5", "Nf3"]);
+    assert!(!game.redo(1));
+}
+
+#[test]
+fn bot_games_take_back_both_moves_at_once() {
+    let mut game = Game::new(None);
+    game.play("e2e4", 0);
+    game.play("c7c5", 0);
+    game.play("g1f3", 0);
+    let after_one =
+        Board::from_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1").unwrap();
+
+    assert!(game.undo(2));
+    assert_eq!(game.board.to_fen(), after_one.to_fen());
+    assert_eq!(game.board.hash(), after_one.hash());
+    assert_eq!(game.sans(), ["e4"]);
+    // a single move left can't be taken back in steps of two
+    assert!(!game.undo(2));
+    assert_eq!(game.sans(), ["e4"]);
+
+    assert!(game.redo(2));
+    assert_eq!(game.sans(), ["e4", "c5", "Nf3"]);
+    assert!(!game.redo(2));
+}
+
+#[test]
+fn a_new_move_drops_the_moves_taken_back() {
+    let mut game = Game::new(None);
+    game.play("e2e4", 0);
+    game.play("e7e5", 0);
+    assert!(game.undo(1));
+    game.play("c7c5", 0);
+    assert!(!game.redo(1));
+    assert_eq!(game.sans(), ["e4", "c5"]);
+}
+
+#[test]
+fn clocks_go_back_with_the_moves() {
+    let control = TimeControl::new(5, 3);
+    let mut game = Game::new(Some(ChessClock::new(control, PieceColor::White)));
+    game.play("e2e4", 10);
+    game.play("e7e5", 20);
+    game.clock.as_mut().unwrap().tick(Duration::from_secs(5));
+    let before_undo = game.clock;
+    let seconds = |game: &Game, color| game.clock.unwrap().remaining(color).as_secs();
+
+    // back to white thinking for 10 seconds about the first move
+    assert!(game.undo(2));
+    assert_eq!(game.clock.unwrap().running(), Some(PieceColor::White));
+    assert_eq!(seconds(&game, PieceColor::White), 290);
+    assert_eq!(seconds(&game, PieceColor::Black), 300);
+
+    // black thinking for 20 seconds after white got the increment
+    assert!(game.redo(1));
+    assert_eq!(game.clock.unwrap().running(), Some(PieceColor::Black));
+    assert_eq!(seconds(&game, PieceColor::White), 293);
+    assert_eq!(seconds(&game, PieceColor::Black), 280);
+
+    assert!(game.redo(1));
+    assert_eq!(game.clock, before_undo);
+    assert_eq!(seconds(&game, PieceColor::White), 288);
+    assert_eq!(seconds(&game, PieceColor::Black), 283);
+}
@@ -0,0 +1,61 @@
+use chess_core::board::Board;
+use chess_core::fen::STARTING_FEN;
+
+// positions and node counts from https://www.chessprogramming.org/Perft_Results
+const KIWIPETE: &str = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+const POSITION_3: &str = "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1";
+const POSITION_4: &str = "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1";
+const POSITION_4_MIRRORED: &str =
+    "r2q1rk1/pP1p2pp/Q4n2/bbp1p3/Np6/1B3NBn/pPPP1PPP/R3K2R b KQ - 0 1";
+const POSITION_5: &str = "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8";
+const POSITION_6: &str = "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10";
+
+fn assert_perft(fen: &str, expected: &[u64]) {
+    let board = Board::from_fen(fen).expect("Test positions are valid");
+    for (depth, nodes) in expected.iter().enumerate() {
+        let depth = depth as u32 + 1;
+        assert_eq!(board.perft(depth), *nodes, "perft({}) of {}", depth, fen);
+    }
+}
+
+#[test]
+fn initial_position() {
+    assert_perft(STARTING_FEN, &[20, 400, 8902, 197281, 4865609]);
+}
+
+#[test]
+fn kiwipete() {
+    assert_perft(KIWIPETE, &[48, 2039, 97862, 4085603]);
+}
+
+#[test]
+fn position_3() {
+    assert_perft(POSITION_3, &[14, 191, 2812, 43238, 674624]);
+}
+
+#[test]
+fn position_4() {
+    assert_perft(POSITION_4, &[6, 264, 9467, 422333]);
+}
+
+#[test]
+fn position_4_mirrored() {
+    assert_perft(POSITION_4_MIRRORED, &[6, 264, 9467, 422333]);
+}
+
+#[test]
+fn position_5() {
+    assert_perft(POSITION_5, &[44, 1486, 62379, 2103487]);
+}
+
+#[test]
+fn position_6() {
+    assert_perft(POSITION_6, &[46, 2079, 89890, 3894594]);
+}
+
+#[test]
+fn divide_adds_up_to_perft() {
+    let board = Board::from_fen(KIWIPETE).expect("Test positions are valid");
+    let divided: u64 = board.divide(3).iter().map(|(_, nodes)| nodes).sum();
+    assert_eq!(divided, board.perft(3));
+}
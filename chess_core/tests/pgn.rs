@@ -0,0 +1,41 @@
+use chess_core::pgn::parse_pgn;
+
+#[test]
+fn castling_can_be_written_with_zeros() {
+    let games =
+        parse_pgn("1. e4 e5 2. Nf3 Nc6 3. Bc4 Bc5 4. 0-0 Nf6 5. d3 d6 6. Be3 0-0 *").unwrap();
+    assert_eq!(games.len(), 1);
+    assert_eq!(games[0].moves[6], "0-0");
+    assert_eq!(games[0].moves[11], "0-0");
+    let (positions, _) = games[0].replay(1).unwrap();
+    assert_eq!(
+        positions.last().unwrap().to_fen(),
+        "r1bq1rk1/ppp2ppp/2np1n2/2b1p3/2B1P3/3PBN2/PPP2PPP/RN1Q1RK1 w - - 2 7"
+    );
+
+    let games =
+        parse_pgn("[FEN \"r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1\"]\n\n1. 0-0-0 0-0 *").unwrap();
+    let (positions, _) = games[0].replay(1).unwrap();
+    assert_eq!(
+        positions.last().unwrap().to_fen(),
+        "r4rk1/8/8/8/8/8/8/2KR3R w - - 2 2"
+    );
+}
+
+#[test]
+fn move_numbers_comments_and_nags_are_skipped() {
+    let games = parse_pgn(
+        "1.e4 {best by test} 1...e5 $1 2. Nf3 ; a comment\n2... Nc6 (2... d6 3. d4) 3.Bb5!? a6 1-0",
+    )
+    .unwrap();
+    assert_eq!(games.len(), 1);
+    assert_eq!(games[0].moves, ["e4", "e5", "Nf3", "Nc6", "Bb5!?", "a6"]);
+    assert_eq!(games[0].result, "1-0");
+    assert!(games[0].replay(1).is_ok());
+}
+
+#[test]
+fn black_move_after_a_comment_keeps_its_number() {
+    let games = parse_pgn("1. d4 {queen's pawn} 1... d5 2. c4 *").unwrap();
+    assert_eq!(games[0].moves, ["d4", "d5", "c4"]);
+}
@@ -0,0 +1,83 @@
+use chess_core::board::Board;
+use chess_core::chess_pieces::PieceType;
+use chess_core::coordinates::Coordinates;
+use chess_core::san::{parse_san, to_san};
+
+// the SAN of the move, after checking that parsing it gives back the same move
+fn san(fen: &str, from: &str, to: &str, promotion: Option<PieceType>) -> String {
+    let board = Board::from_fen(fen).unwrap();
+    let from = Coordinates::from_algebraic(from).unwrap();
+    let to = Coordinates::from_algebraic(to).unwrap();
+    let san = to_san(&board, from, to, promotion);
+    assert_eq!(
+        parse_san(&board, &san),
+        Some((from, to, promotion)),
+        "{san}"
+    );
+    san
+}
+
+#[test]
+fn moves_are_disambiguated_by_file_rank_or_both() {
+    // two knights can reach d2
+    let fen = "4k3/8/8/8/8/5N2/8/1N2K3 w - - 0 1";
+    assert_eq!(san(fen, "b1", "d2", None), "Nbd2");
+    assert_eq!(san(fen, "f3", "d2", None), "Nfd2");
+    // two rooks on the same file can reach a3
+    let fen = "4k3/8/8/R7/8/8/8/R3K3 w - - 0 1";
+    assert_eq!(san(fen, "a1", "a3", None), "R1a3");
+    assert_eq!(san(fen, "a5", "a3", None), "R5a3");
+    // three queens can reach h1, sharing a file and a rank with the one on e4
+    let fen = "2k5/8/8/8/4Q2Q/8/8/K3Q3 w - - 0 1";
+    assert_eq!(san(fen, "e4", "h1", None), "Qe4h1");
+    assert_eq!(san(fen, "h4", "h1", None), "Qhh1");
+    assert_eq!(san(fen, "e1", "h1", None), "Q1h1");
+    // only one knight can reach d4
+    assert_eq!(
+        san("4k3/8/8/8/8/5N2/8/1N2K3 w - - 0 1", "f3", "d4", None),
+        "Nd4"
+    );
+}
+
+#[test]
+fn promotions_name_the_new_piece() {
+    let fen = "r3k3/1P6/8/8/8/8/8/4K3 w - - 0 1";
+    assert_eq!(san(fen, "b7", "b8", Some(PieceType::Knight)), "b8=N");
+    assert_eq!(
+        san(fen, "b7", "b8", Some(PieceType::Rook { moved: true })),
+        "b8=R+"
+    );
+    assert_eq!(san(fen, "b7", "a8", Some(PieceType::Bishop)), "bxa8=B");
+    assert_eq!(san(fen, "b7", "a8", Some(PieceType::Queen)), "bxa8=Q+");
+}
+
+#[test]
+fn checks_and_mates_are_marked() {
+    assert_eq!(
+        san("4k3/8/8/8/8/8/8/R3K3 w - - 0 1", "a1", "a8", None),
+        "Ra8+"
+    );
+    assert_eq!(
+        san("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1", "a1", "a8", None),
+        "Ra8#"
+    );
+    assert_eq!(
+        san("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1", "a1", "a7", None),
+        "Ra7"
+    );
+    // castling can give check too
+    assert_eq!(
+        san("5k2/8/8/8/8/8/8/4K2R w K - 0 1", "e1", "h1", None),
+        "O-O+"
+    );
+}
+
+#[test]
+fn annotations_and_zero_castling_are_parsed() {
+    let board = Board::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+    let king = Coordinates::from_algebraic("e1").unwrap();
+    let rook = Coordinates::from_algebraic("a1").unwrap();
+    assert_eq!(parse_san(&board, "0-0-0"), Some((king, rook, None)));
+    assert_eq!(parse_san(&board, "O-O-O!?"), Some((king, rook, None)));
+    assert_eq!(parse_san(&board, "Kd3"), None);
+}
@@ -0,0 +1,116 @@
+use chess_core::fen::STARTING_FEN;
+use chess_core::game_result::Outcome;
+use chess_core::tournament::{
+    parse_epd, parse_pgn_openings, round_robin, Crosstable, Pairing, Score,
+};
+
+#[test]
+fn epd_positions_get_move_counters() {
+    let openings = parse_epd(
+        "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 hmvc 0; fmvn 1;\n\
+         # comment\n\
+         \n\
+         4k3/8/8/8/8/8/4P3/4K3 w - - id \"pawn ending\";\n",
+    )
+    .unwrap();
+    assert_eq!(openings.len(), 2);
+    assert_eq!(
+        openings[0].start_fen,
+        "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1"
+    );
+    assert_eq!(openings[1].start_fen, "4k3/8/8/8/8/8/4P3/4K3 w - - 0 1");
+    assert!(openings.iter().all(|opening| opening.moves.is_empty()));
+}
+
+#[test]
+fn epd_fields_may_be_separated_by_tabs_and_runs_of_spaces() {
+    let openings =
+        parse_epd("4k3/8/8/8/8/8/4P3/4K3\tb  -\t-   hmvc 7;\tfmvn 31; c0 \"two  spaces\";\n")
+            .unwrap();
+    assert_eq!(openings.len(), 1);
+    assert_eq!(openings[0].start_fen, "4k3/8/8/8/8/8/4P3/4K3 b - - 7 31");
+}
+
+#[test]
+fn invalid_epd_line_is_reported() {
+    let error = parse_epd("4k3/8/8/8/8/8/4P3/4K3 w - -\n4k3/8/8 w - -\n").unwrap_err();
+    assert_eq!(error.line, 2);
+}
+
+#[test]
+fn pgn_openings_keep_their_moves() {
+    let openings =
+        parse_pgn_openings("1. e4 e5 2. Nf3 Nc6 *\n\n1. d4 {main} d5 (1... Nf6) *\n").unwrap();
+    assert_eq!(openings.len(), 2);
+    assert_eq!(openings[0].start_fen, STARTING_FEN);
+    assert_eq!(openings[0].uci_moves(), ["e2e4", "e7e5", "g1f3", "b8c6"]);
+    assert_eq!(openings[1].uci_moves(), ["d2d4", "d7d5"]);
+}
+
+#[test]
+fn round_robin_alternates_colors_for_each_opening() {
+    let pairings = round_robin(3, 4, 3);
+    assert_eq!(pairings.len(), 12);
+    let first_pair: Vec<(usize, usize, usize)> = pairings[..4]
+        .iter()
+        .map(|pairing| (pairing.white, pairing.black, pairing.opening))
+        .collect();
+    assert_eq!(first_pair, [(0, 1, 0), (1, 0, 0), (0, 1, 1), (1, 0, 1)]);
+    assert!(pairings
+        .iter()
+        .enumerate()
+        .all(|(index, pairing)| pairing.round == index + 1));
+}
+
+#[test]
+fn elo_follows_the_score() {
+    let even = Score {
+        wins: 3,
+        draws: 4,
+        losses: 3,
+    };
+    let estimate = even.elo().unwrap();
+    assert_eq!(estimate.difference, 0.0);
+    assert!(estimate.error > 0.0);
+
+    let three_quarters = Score {
+        wins: 30,
+        draws: 0,
+        losses: 10,
+    };
+    let estimate = three_quarters.elo().unwrap();
+    assert!((estimate.difference - 190.8).abs() < 0.1);
+
+    let all_won = Score {
+        wins: 5,
+        draws: 0,
+        losses: 0,
+    };
+    assert_eq!(all_won.elo().unwrap().difference, f64::INFINITY);
+    assert_eq!(Score::default().elo(), None);
+}
+
+#[test]
+fn crosstable_counts_both_sides() {
+    let mut crosstable = Crosstable::new(vec!["a".to_string(), "b".to_string()]);
+    let pairing = |white, black| Pairing {
+        round: 1,
+        white,
+        black,
+        opening: 0,
+    };
+    crosstable.add(&pairing(0, 1), Outcome::WhiteWins);
+    crosstable.add(&pairing(1, 0), Outcome::WhiteWins);
+    crosstable.add(&pairing(1, 0), Outcome::Draw);
+
+    assert_eq!(
+        crosstable.score(1, 0),
+        Score {
+            wins: 1,
+            draws: 1,
+            losses: 1,
+        }
+    );
+    assert_eq!(crosstable.total(0).points(), 1.5);
+    assert_eq!(crosstable.ranking(), [0, 1]);
+}
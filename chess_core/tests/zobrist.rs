@@ -0,0 +1,45 @@
+use chess_core::board::Board;
+use chess_core::chess_pieces::PieceType;
+use chess_core::coordinates::Coordinates;
+
+fn coords(x: i32, y: i32) -> Coordinates {
+    Coordinates { x, y }
+}
+
+// the hash kept up to date move by move has to match the one made from the whole position
+fn assert_hash_matches(board: &Board) {
+    assert_eq!(board.hash(), board.position().hash(), "{}", board.to_fen());
+}
+
+#[test]
+fn incremental_hash_matches_hash_from_scratch() {
+    let mut board = Board::from_fen("r3k2r/1P6/8/8/5p2/8/4P3/R3K2R w KQkq - 0 1").unwrap();
+    assert_hash_matches(&board);
+
+    // a double push, en passant, castling on both sides of the board and a promotion with
+    // a capture that also takes away a castling right
+    let moves = [
+        (coords(5, 2), coords(5, 4), None),
+        (coords(6, 4), coords(5, 3), None),
+        (coords(5, 1), coords(1, 1), None),
+        (coords(5, 8), coords(8, 8), None),
+        (coords(2, 7), coords(1, 8), Some(PieceType::Queen)),
+    ];
+    let mut records = Vec::new();
+    let mut hashes = vec![board.hash()];
+    for (from, to, promotion) in moves {
+        assert!(board.is_legal_move(from, to), "{from:?} {to:?}");
+        records.push(board.make_move(from, to, promotion).unwrap());
+        assert_hash_matches(&board);
+        hashes.push(board.hash());
+    }
+    assert_eq!(board.to_fen(), "Q4rk1/8/8/8/8/4p3/8/2KR3R b - - 0 3");
+
+    for record in records.iter().rev() {
+        hashes.pop();
+        board.undo_move(record);
+        assert_hash_matches(&board);
+        assert_eq!(Some(&board.hash()), hashes.last());
+    }
+    assert_eq!(board.to_fen(), "r3k2r/1P6/8/8/5p2/8/4P3/R3K2R w KQkq - 0 1");
+}
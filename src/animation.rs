@@ -0,0 +1,180 @@
+use crate::*;
+use std::fmt::{self, Display, Formatter};
+use std::time::Duration;
+
+// the move animation lengths the settings cycle through; zero turns animations off
+pub const ANIMATION_DURATIONS: [Duration; 5] = [
+    Duration::ZERO,
+    Duration::from_millis(100),
+    Duration::from_millis(200),
+    Duration::from_millis(300),
+    Duration::from_millis(500),
+];
+// moving pieces are drawn above the standing ones, the piece held with the mouse above all
+const MOVING_PIECE_Z: f32 = 20.0;
+pub const DRAGGED_PIECE_Z: f32 = 30.0;
+// captured pieces shrink to this part of their size while fading out
+const CAPTURED_PIECE_END_SCALE: f32 = 0.5;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Easing {
+    Linear,
+    #[default]
+    EaseOut,
+    EaseInOut,
+}
+
+impl Easing {
+    pub fn next(&self) -> Easing {
+        match self {
+            Easing::Linear => Easing::EaseOut,
+            Easing::EaseOut => Easing::EaseInOut,
+            Easing::EaseInOut => Easing::Linear,
+        }
+    }
+
+    // the part of the way covered after the given part of the animation, both from 0 to 1
+    pub fn apply(&self, t: f32) -> f32 {
+        match self {
+            Easing::Linear => t,
+            Easing::EaseOut => 1.0 - (1.0 - t).powi(3),
+            Easing::EaseInOut if t < 0.5 => 4.0 * t.powi(3),
+            Easing::EaseInOut => 1.0 - (2.0 - 2.0 * t).powi(3) / 2.0,
+        }
+    }
+}
+
+impl Display for Easing {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Easing::Linear => write!(f, "linear"),
+            Easing::EaseOut => write!(f, "ease out"),
+            Easing::EaseInOut => write!(f, "ease in and out"),
+        }
+    }
+}
+
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AnimationSettings {
+    pub duration: Duration,
+    pub easing: Easing,
+}
+
+impl Default for AnimationSettings {
+    fn default() -> Self {
+        AnimationSettings {
+            duration: ANIMATION_DURATIONS[2],
+            easing: Easing::default(),
+        }
+    }
+}
+
+impl AnimationSettings {
+    pub fn next_duration(&mut self) {
+        self.duration = *ANIMATION_DURATIONS
+            .iter()
+            .find(|duration| **duration > self.duration)
+            .unwrap_or(&ANIMATION_DURATIONS[0]);
+    }
+
+    // e.g. "200 ms"
+    pub fn duration_name(&self) -> String {
+        if self.duration.is_zero() {
+            "off".to_string()
+        } else {
+            format!("{} ms", self.duration.as_millis())
+        }
+    }
+
+    fn progress(&self, elapsed: Duration) -> f32 {
+        if self.duration.is_zero() {
+            1.0
+        } else {
+            (elapsed.as_secs_f32() / self.duration.as_secs_f32()).min(1.0)
+        }
+    }
+}
+
+// a piece sprite sliding to its new field; the piece stands there on the board already
+#[derive(Component)]
+pub struct PieceAnimation {
+    from: Vec2,
+    to: Vec2,
+    elapsed: Duration,
+}
+
+// the sprite of a captured piece, fading out before it is despawned
+#[derive(Component, Default)]
+struct CapturedPiece {
+    elapsed: Duration,
+}
+
+// moves the sprite from wherever it is now, e.g. from under the cursor, to `to`
+pub fn animate_piece(commands: &mut Commands, entity: Entity, transform: &Transform, to: Vec2) {
+    commands.entity(entity).insert(PieceAnimation {
+        from: transform.translation.truncate(),
+        to,
+        elapsed: Duration::ZERO,
+    });
+}
+
+// the sprite stops being a piece at once, so that nothing else moves or despawns it
+pub fn remove_captured_piece(commands: &mut Commands, entity: Entity) {
+    commands
+        .entity(entity)
+        .remove::<PieceSprite>()
+        .remove::<PieceAnimation>()
+        .insert(CapturedPiece::default());
+}
+
+fn animate_pieces(
+    mut commands: Commands,
+    time: Res<Time>,
+    settings: Res<AnimationSettings>,
+    mut animations: Query<(Entity, &mut Transform, &mut PieceAnimation)>,
+) {
+    for (entity, mut transform, mut animation) in &mut animations {
+        animation.elapsed += time.delta();
+        let progress = settings.progress(animation.elapsed);
+        let position = animation
+            .from
+            .lerp(animation.to, settings.easing.apply(progress));
+        if progress < 1.0 {
+            transform.translation = position.extend(MOVING_PIECE_Z);
+        } else {
+            transform.translation = position.extend(PIECE_Z);
+            commands.entity(entity).remove::<PieceAnimation>();
+        }
+    }
+}
+
+fn fade_captured_pieces(
+    mut commands: Commands,
+    time: Res<Time>,
+    settings: Res<AnimationSettings>,
+    mut captured: Query<(Entity, &mut Sprite, &mut Transform, &mut CapturedPiece)>,
+) {
+    for (entity, mut sprite, mut transform, mut captured) in &mut captured {
+        captured.elapsed += time.delta();
+        let progress = settings.progress(captured.elapsed);
+        if progress < 1.0 {
+            let faded = settings.easing.apply(progress);
+            sprite.color.set_a(1.0 - faded);
+            let scale = PIECE_SCALE * (1.0 - faded * (1.0 - CAPTURED_PIECE_END_SCALE));
+            transform.scale = Vec3::new(scale, scale, 1.0);
+        } else {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+pub struct AnimationPlugin;
+
+impl Plugin for AnimationPlugin {
+    // animations also finish after the game ended, e.g. with a capture
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AnimationSettings>()
+            .add_system(animate_pieces)
+            .add_system(fade_captured_pieces);
+    }
+}
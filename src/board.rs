@@ -1,410 +1,509 @@
+pub use chess_core::board::*;
+
+use crate::animation::PieceAnimation;
 use crate::field::*;
-use crate::moves::get_possible_moves;
+use crate::history::MoveHistory;
+use crate::pgn::MoveLog;
+use crate::time_control::{new_clock, TimeControlSettings};
 use crate::*;
-
-#[derive(Debug, Component, Clone, PartialEq, Eq)]
-pub struct Board {
-    pub fields: Vec<Vec<Field>>,
-    pub white_king_pos: Coordinates,
-    pub black_king_pos: Coordinates,
-    full_move_number: u32,
+use bevy::window::WindowResized;
+
+// sprite of a piece; the rules only know about the piece standing on the board
+#[derive(Debug, Clone, PartialEq, Eq, Component)]
+pub struct PieceSprite {
+    pub piece_type: PieceType,
+    pub piece_color: PieceColor,
+    pub coordinates: Coordinates,
+    pub border: bool,
 }
 
-fn starting_piece_from_coordinates(coordinates: Coordinates) -> Option<Piece> {
-    let piece_color = if coordinates.y < 3 {
-        PieceColor::White
-    } else if coordinates.y > 6 {
-        PieceColor::Black
-    } else {
-        return None;
-    };
+impl PieceSprite {
+    pub fn new(piece: &Piece) -> Self {
+        Self {
+            piece_type: piece.piece_type,
+            piece_color: piece.piece_color,
+            coordinates: piece.coordinates,
+            border: false,
+        }
+    }
+}
 
-    let piece_type = if coordinates.y == 2 || coordinates.y == 7 {
-        PieceType::Pawn { moved: false }
-    } else if coordinates.x == 1 || coordinates.x == 8 {
-        PieceType::Rook { moved: false }
-    } else if coordinates.x == 2 || coordinates.x == 7 {
-        PieceType::Knight
-    } else if coordinates.x == 3 || coordinates.x == 6 {
-        PieceType::Bishop
-    } else if coordinates.x == 4 {
-        PieceType::Queen
-    } else {
-        PieceType::King { moved: false }
-    };
-    Some(Piece::new(piece_type, piece_color, coordinates))
+#[derive(Debug, Clone, PartialEq, Eq, Component)]
+pub struct FieldSprite {
+    pub coordinates: Coordinates,
+    pub color: FieldColor,
 }
 
-impl Board {
-    pub fn empty() -> Board {
-        let fields: Vec<Vec<Field>> = Vec::new();
-        Board {
-            fields,
-            white_king_pos: Coordinates { x: 5, y: 1 },
-            black_king_pos: Coordinates { x: 5, y: 8 },
-            full_move_number: 1,
-        }
-    }
+// piece sprites by the field they stand on
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PieceEntities(HashMap<Coordinates, Entity>);
 
-    pub fn print_board(&self) {
-        for i in 0..BOARD_SIZE {
-            for j in 0..BOARD_SIZE {
-                match &self.fields[i][j].piece {
-                    Some(piece) => print!("{}", piece),
-                    None => print!(" "),
-                }
-            }
-            println!();
-        }
+impl PieceEntities {
+    pub fn get(&self, coordinates: Coordinates) -> Option<Entity> {
+        self.0.get(&coordinates).copied()
     }
 
-    pub fn to_fen(&self) -> String {
-        let mut fen = String::new();
-        for i in (0..BOARD_SIZE).rev() {
-            let mut empty_fields = 0;
-            for j in 0..BOARD_SIZE {
-                match &self.fields[i][j].piece {
-                    Some(piece) => {
-                        if empty_fields > 0 {
-                            fen.push_str(&empty_fields.to_string());
-                            empty_fields = 0;
-                        }
-                        fen.push_str(&piece.to_fen());
-                    }
-                    None => empty_fields += 1,
-                }
-            }
-            if empty_fields > 0 {
-                fen.push_str(&empty_fields.to_string());
-            }
-            if i > 0 {
-                fen.push('/');
-            }
-        }
-        fen + " b - - 0 " + &self.full_move_number.to_string()
+    pub fn remove(&mut self, coordinates: Coordinates) -> Option<Entity> {
+        self.0.remove(&coordinates)
     }
 
-    pub fn get_field(&self, coordinates: Coordinates) -> Option<&Field> {
-        if coordinates.x < 1 || coordinates.x > BOARD_SIZE as i32 {
-            return None;
-        }
-        if coordinates.y < 1 || coordinates.y > BOARD_SIZE as i32 {
-            return None;
+    // whatever stood on the destination field has to be despawned by the caller
+    pub fn move_entity(&mut self, from: Coordinates, to: Coordinates) {
+        if let Some(entity) = self.0.remove(&from) {
+            self.0.insert(to, entity);
         }
-        Some(&self.fields[(coordinates.y - 1) as usize][(coordinates.x - 1) as usize])
     }
+}
 
-    pub fn get_field_mut(&mut self, coordinates: Coordinates) -> Option<&mut Field> {
-        if coordinates.x < 1 || coordinates.x > BOARD_SIZE as i32 {
-            return None;
-        }
-        if coordinates.y < 1 || coordinates.y > BOARD_SIZE as i32 {
-            return None;
-        }
-        Some(&mut self.fields[(coordinates.y - 1) as usize][(coordinates.x - 1) as usize])
-    }
+// piece sprites are drawn above the fields, at half the size of their images
+pub const PIECE_Z: f32 = 10.0;
+pub const PIECE_SCALE: f32 = 0.5;
 
-    pub fn get_piece(&self, coordinates: Coordinates) -> Option<&Piece> {
-        match self.get_field(coordinates) {
-            Some(field) => match &field.piece {
-                Some(piece) => Some(piece),
-                None => None,
-            },
-            None => None,
-        }
+pub fn piece_sprite_bundle(image: Handle<Image>, on_window_coordinates: Vec2) -> SpriteBundle {
+    SpriteBundle {
+        texture: image,
+        transform: Transform {
+            translation: on_window_coordinates.extend(PIECE_Z),
+            scale: Vec3::new(PIECE_SCALE, PIECE_SCALE, 1.0),
+            ..default()
+        },
+        ..default()
     }
+}
 
-    pub fn field_in_danger(&self, my_color: PieceColor, coords: Coordinates) -> bool {
-        for row in &self.fields {
-            for field in row {
-                if let Some(some_piece) = &field.piece {
-                    if some_piece.piece_color != my_color {
-                        let possible_moves = get_possible_moves(some_piece, self, false);
-                        if possible_moves.contains(&coords) {
-                            return true;
-                        }
-                    }
-                }
-            }
-        }
-        false
-    }
+fn spawn_piece(
+    commands: &mut Commands,
+    piece: &Piece,
+    image: Handle<Image>,
+    on_window_coordinates: Vec2,
+) -> Entity {
+    commands
+        .spawn(piece_sprite_bundle(image, on_window_coordinates))
+        .insert(PieceSprite::new(piece))
+        .id()
+}
 
-    // iterates through all fields. If there is an enemy piece, check if
-    // it could take the king
-    pub fn king_in_danger(&self, my_color: PieceColor) -> bool {
-        let king_position = &self.get_king_position(my_color);
-        self.field_in_danger(my_color, *king_position)
+// a file letter below the board or a rank number left of it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Component)]
+pub enum CoordinateLabel {
+    File(i32),
+    Rank(i32),
+}
+
+// from the edge of the board to the centre of a label
+const LABEL_MARGIN: f32 = 25.0;
+// the board is shown with room for its labels, in world units, and for the panels and texts
+// around it, in pixels
+const BOARD_VIEW_MARGIN: f32 = 60.0;
+const PANEL_SPACE: f32 = 320.0;
+const TEXT_SPACE: f32 = 45.0;
+
+// world units per pixel; the camera zooms so that the board fits the window
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct BoardScale(pub f32);
+
+impl Default for BoardScale {
+    fn default() -> Self {
+        BoardScale(1.0)
     }
+}
 
-    pub fn no_possible_moves(&self, my_color: PieceColor) -> bool {
-        let mut all_moves = Vec::new();
-        for row in &self.fields {
-            for field in row {
-                if let Some(some_piece) = &field.piece {
-                    if some_piece.piece_color == my_color {
-                        let mut possible_moves = get_possible_moves(some_piece, self, true);
-                        all_moves.append(&mut possible_moves);
-                    }
-                }
-            }
-        }
-        all_moves.is_empty()
+impl BoardScale {
+    pub fn fitting(width: f32, height: f32) -> BoardScale {
+        let half_view = FIELD_SIZE * BOARD_SIZE as f32 / 2.0 + BOARD_VIEW_MARGIN;
+        // in a narrow window the board covers the panels rather than getting tiny
+        let half_width = (width / 2.0 - PANEL_SPACE).max(width / 4.0);
+        let half_height = (height / 2.0 - TEXT_SPACE).max(height / 4.0);
+        BoardScale((half_view / half_width).max(half_view / half_height))
     }
 
-    fn get_king_position(&self, my_color: PieceColor) -> Coordinates {
-        let mut king_position = self.white_king_pos;
-        if my_color == PieceColor::Black {
-            king_position = self.black_king_pos;
-        }
-        king_position
+    // the size in pixels of something as large as a field
+    pub fn field_pixels(&self) -> f32 {
+        FIELD_SIZE / self.0
     }
+}
 
-    pub fn is_check_after_move(
-        &self,
-        from: &Coordinates,
-        to: &Coordinates,
-        my_color: PieceColor,
-    ) -> bool {
-        let mut dummy_board: Board = self.clone();
-        if !dummy_board.move_piece(*from, *to) {
-            panic!("Something went wrong! Can't make a dummy move");
-        }
+// the position new games start from, the standard one unless given on the command line
+#[derive(Resource)]
+pub struct StartingPosition(pub Board);
 
-        match my_color {
-            PieceColor::White => dummy_board.king_in_danger(PieceColor::White),
-            PieceColor::Black => dummy_board.king_in_danger(PieceColor::Black),
-        }
+impl Default for StartingPosition {
+    fn default() -> Self {
+        StartingPosition(Board::starting())
     }
+}
 
-    pub fn remove_piece(&mut self, coordinates: Coordinates) -> Option<Piece> {
-        match self.get_field_mut(coordinates) {
-            Some(field) => {
-                let piece = field.piece.clone();
-                field.piece = None;
-                piece
-            }
-            None => None,
+// a flipped board is turned around, so the field seen in the bottom left corner is h8
+fn orient(coordinates: Coordinates, flipped: bool) -> Coordinates {
+    if flipped {
+        Coordinates {
+            x: BOARD_SIZE as i32 + 1 - coordinates.x,
+            y: BOARD_SIZE as i32 + 1 - coordinates.y,
         }
+    } else {
+        coordinates
     }
+}
 
-    fn castling(&mut self, from: Coordinates, to: Coordinates) -> bool {
-        if to == (Coordinates { x: 1, y: 1 }) {
-            self.move_piece(from, Coordinates { x: 3, y: 1 })
-                && self.move_piece(to, Coordinates { x: 4, y: 1 })
-        } else if to == (Coordinates { x: 1, y: 8 }) {
-            self.move_piece(from, Coordinates { x: 7, y: 8 })
-                && self.move_piece(to, Coordinates { x: 6, y: 8 })
-        } else if to == (Coordinates { x: 8, y: 8 }) {
-            self.move_piece(from, Coordinates { x: 3, y: 8 })
-                && self.move_piece(to, Coordinates { x: 4, y: 8 })
-        } else if to == (Coordinates { x: 8, y: 1 }) {
-            self.move_piece(from, Coordinates { x: 7, y: 1 })
-                && self.move_piece(to, Coordinates { x: 6, y: 1 })
-        } else {
-            false
-        }
+// center of the field on the window, with the board centred at the origin
+pub fn field_translation(coordinates: Coordinates, flipped: bool) -> Vec2 {
+    let start = -((FIELD_SIZE * BOARD_SIZE as f32) / 2.0 - (FIELD_SIZE / 2.0));
+    let coordinates = orient(coordinates, flipped);
+    Vec2 {
+        x: start + (coordinates.x - 1) as f32 * FIELD_SIZE,
+        y: start + (coordinates.y - 1) as f32 * FIELD_SIZE,
     }
+}
 
-    pub fn move_piece(&mut self, from: Coordinates, to: Coordinates) -> bool {
-        if let Some(piece) = self.get_piece(to) {
-            let piece_from = self
-                .get_piece(from)
-                .expect("This should retrun Some(piece) for sure");
-
-            if piece_from.piece_color == piece.piece_color
-                && piece.piece_type == (PieceType::Rook { moved: false })
-                && piece_from.piece_type == (PieceType::King { moved: false })
-            {
-                return self.castling(from, to);
-            }
-        }
+// field the cursor is over, the inverse of `field_translation`
+pub fn mouse_pos_to_coordinates(
+    x: f32,
+    y: f32,
+    width: f32,
+    height: f32,
+    scale: BoardScale,
+    flipped: bool,
+) -> Coordinates {
+    let translation = cursor_translation(x, y, width, height, scale);
+    let left_down = -(BOARD_SIZE as f32 * FIELD_SIZE) / 2.0;
+    let on_screen = Coordinates {
+        x: ((translation.x - left_down) / FIELD_SIZE).floor() as i32 + 1,
+        y: ((translation.y - left_down) / FIELD_SIZE).floor() as i32 + 1,
+    };
+    orient(on_screen, flipped)
+}
 
-        let mut white_king_moved = false;
-        let mut black_king_moved = false;
-        let mut ok = true;
-        let piece = self.remove_piece(from);
-        match piece {
-            Some(piece) => {
-                if piece.piece_color == PieceColor::Black {
-                    self.full_move_number += 1;
-                }
-                let field = self.get_field_mut(to);
-                match field {
-                    Some(field) => {
-                        let mut piece = piece;
-                        piece.coordinates = to;
-
-                        if piece.piece_type == (PieceType::Pawn { moved: false }) {
-                            piece.piece_type = PieceType::Pawn { moved: true };
-                        }
-                        if piece.piece_type == (PieceType::Rook { moved: false }) {
-                            piece.piece_type = PieceType::Rook { moved: true };
-                        }
-
-                        if piece.piece_type == (PieceType::King { moved: false })
-                            || piece.piece_type == (PieceType::King { moved: true })
-                        {
-                            piece.piece_type = PieceType::King { moved: true };
-                            if piece.piece_color == PieceColor::White {
-                                white_king_moved = true;
-                            } else {
-                                black_king_moved = true;
-                            }
-                        }
-                        if piece.piece_type == (PieceType::Pawn { moved: true })
-                            && (piece.coordinates.y == 1 || piece.coordinates.y == 8)
-                        {
-                            piece.piece_type = PieceType::Queen;
-                        }
-
-                        field.piece = Some(piece);
-                    }
-                    None => ok = false,
-                }
-            }
-            None => ok = false,
-        }
-        if white_king_moved {
-            self.white_king_pos = to;
-        }
-        if black_king_moved {
-            self.black_king_pos = to;
-        }
-        ok
-    }
+// the point under the cursor, in the coordinates sprites are placed in
+pub fn cursor_translation(x: f32, y: f32, width: f32, height: f32, scale: BoardScale) -> Vec2 {
+    Vec2::new(x - width / 2.0, y - height / 2.0) * scale.0
+}
 
-    pub fn set_field_entity(&mut self, coordinates: Coordinates, entity: Entity) {
-        if let Some(field) = self.get_field_mut(coordinates) {
-            field.entity = entity;
+// labels follow the fields of their file or rank when the board is turned around
+fn label_translation(label: CoordinateLabel, flipped: bool) -> Vec2 {
+    let edge = -(FIELD_SIZE * BOARD_SIZE as f32) / 2.0 - LABEL_MARGIN;
+    match label {
+        CoordinateLabel::File(x) => {
+            Vec2::new(field_translation(Coordinates { x, y: 1 }, flipped).x, edge)
         }
-    }
-
-    pub fn get_field_entity(&self, coordinates: Coordinates) -> Option<Entity> {
-        self.get_field(coordinates).map(|field| field.entity)
-    }
-
-    pub fn get_piece_entity(&self, coordinates: Coordinates) -> Option<Entity> {
-        match self.get_field(coordinates) {
-            Some(field) => match &field.piece {
-                Some(piece) => piece.entity,
-                None => None,
-            },
-            None => None,
+        CoordinateLabel::Rank(y) => {
+            Vec2::new(edge, field_translation(Coordinates { x: 1, y }, flipped).y)
         }
     }
+}
 
-    pub fn set_piece_entity(&mut self, coordinates: Coordinates, entity: Entity) {
-        if let Some(field) = self.get_field_mut(coordinates) {
-            if let Some(piece) = &mut field.piece {
-                piece.entity = Some(entity);
-            }
+pub fn spawn_pieces(
+    commands: &mut Commands,
+    game_textures: &Res<GameTextures>,
+    board: &Board,
+    flipped: bool,
+) -> PieceEntities {
+    let mut entities = PieceEntities::default();
+    for field in board.fields.iter().flatten() {
+        if let Some(piece) = &field.piece {
+            let image = get_image(&PieceSprite::new(piece), game_textures);
+            let entity = spawn_piece(
+                commands,
+                piece,
+                image,
+                field_translation(field.coordinates, flipped),
+            );
+            entities.0.insert(field.coordinates, entity);
         }
     }
+    entities
 }
 
-fn spawn_piece(
+// spawns the sprites of all fields and pieces
+pub fn spawn_board(
     commands: &mut Commands,
-    mut piece: &mut Piece,
-    image: Handle<Image>,
-    on_window_coordinates: Vec2,
-) -> Entity {
-    let id = commands
-        .spawn(SpriteBundle {
-            texture: image,
-            transform: Transform {
-                translation: Vec3::new(on_window_coordinates.x, on_window_coordinates.y, 10.0),
-                scale: Vec3::new(0.5, 0.5, 1.0),
+    game_textures: &Res<GameTextures>,
+    board: &Board,
+    flipped: bool,
+) -> PieceEntities {
+    for field in board.fields.iter().flatten() {
+        let translation = field_translation(field.coordinates, flipped);
+        let sprite_color = match field.color {
+            FieldColor::Black => BLACK_FIELD,
+            FieldColor::White => WHITE_FIELD,
+        };
+
+        commands
+            .spawn(SpriteBundle {
+                transform: Transform {
+                    translation: translation.extend(0.0),
+                    ..default()
+                },
+                sprite: Sprite {
+                    custom_size: Some(Vec2::new(FIELD_SIZE, FIELD_SIZE)),
+                    color: sprite_color,
+                    ..default()
+                },
                 ..default()
-            },
-            ..default()
-        })
-        .id();
-    piece.entity = Some(id);
-    commands.entity(id).insert(piece.clone());
-    id
+            })
+            .insert(FieldSprite {
+                coordinates: field.coordinates,
+                color: field.color,
+            });
+    }
+    spawn_pieces(commands, game_textures, board, flipped)
 }
 
 pub fn board_spawn_system(
     mut commands: Commands,
     game_textures: Res<GameTextures>,
     mut game_state: ResMut<GameState>,
+    starting_position: Res<StartingPosition>,
+    time_control: Res<TimeControlSettings>,
+    mut whose_turn: ResMut<State<WhoseTurn>>,
 ) {
-    let start_x = (-1.0) * ((FIELD_SIZE * BOARD_SIZE as f32) / 2.0 - (FIELD_SIZE / 2.0));
-    let mut x = start_x;
-    let mut y = (-1.0) * ((FIELD_SIZE * BOARD_SIZE as f32) / 2.0 - (FIELD_SIZE / 2.0));
-
-    let mut fields: Vec<Vec<Field>> = Vec::new();
-
-    for i in 0..BOARD_SIZE {
-        let mut row: Vec<Field> = Vec::new();
-        for j in 0..BOARD_SIZE {
-            let (field_color, sprite_color) = if (i + j) % 2 == 0 {
-                (FieldColor::Black, BLACK_FIELD)
-            } else {
-                (FieldColor::White, WHITE_FIELD)
-            };
-            let coordinates = Coordinates {
-                x: (j + 1) as i32,
-                y: (i + 1) as i32,
-            };
-
-            let piece = starting_piece_from_coordinates(coordinates);
-
-            let field_id = commands
-                .spawn(SpriteBundle {
-                    transform: Transform {
-                        translation: Vec3::new(x as f32, y as f32, 0.0),
-                        ..default()
-                    },
-                    sprite: Sprite {
-                        custom_size: Some(Vec2::new(FIELD_SIZE, FIELD_SIZE)),
-                        color: sprite_color,
-                        ..default()
-                    },
-                    ..default()
-                })
-                .id();
-
-            let mut field = Field {
-                entity: field_id,
-                coordinates,
-                color: field_color,
-                piece: piece.clone(),
-            };
-
-            if let Some(mut piece) = piece {
-                let image = get_image(&piece, &game_textures);
-                spawn_piece(&mut commands, &mut piece, image, Vec2 { x, y });
-                field.piece = Some(piece);
-            }
+    let board = starting_position.0.clone();
+    game_state.piece_entities =
+        spawn_board(&mut commands, &game_textures, &board, game_state.flipped);
+
+    game_state.move_log = MoveLog::new(board.to_fen());
+    game_state.history = MoveHistory::default();
+    game_state.bot_error = None;
+    game_state.viewed_move = None;
+    game_state.clock = new_clock(&time_control, board.side_to_move);
+    game_state.white = board.side_to_move == PieceColor::White;
+    // the bot opens if the player has the other side, and makes all moves of an engine match
+    if game_state.vs_bot
+        && (game_state.engine_match.is_some() || board.side_to_move != game_state.player_color)
+    {
+        game_state.bot_turn = true;
+        whose_turn
+            .set(WhoseTurn::Bot)
+            .expect("Unexpected error while setting state");
+    }
+    game_state.board = board;
+}
 
-            commands.entity(field_id).insert({
-                Field {
-                    entity: field_id,
-                    coordinates,
-                    color: field_color,
-                    piece: None,
-                }
-            });
-            row.push(field);
-            x += FIELD_SIZE;
+fn spawn_coordinate_labels(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    game_state: Res<GameState>,
+) {
+    let style = TextStyle {
+        font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+        font_size: 30.0,
+        color: Color::WHITE,
+    };
+    for index in 1..=BOARD_SIZE as i32 {
+        // e.g. "c3", split into the file and the rank
+        let square = Coordinates { x: index, y: index }.to_algebraic();
+        let (file, rank) = square.split_at(1);
+        for (label, text) in [
+            (CoordinateLabel::File(index), file),
+            (CoordinateLabel::Rank(index), rank),
+        ] {
+            let translation = label_translation(label, game_state.flipped);
+            commands.spawn((
+                Text2dBundle {
+                    text: Text::from_section(text, style.clone())
+                        .with_alignment(TextAlignment::CENTER),
+                    transform: Transform::from_translation(translation.extend(0.0)),
+                    ..default()
+                },
+                label,
+            ));
         }
-        fields.push(row);
-        x = start_x;
-        y += FIELD_SIZE;
     }
-    game_state.board.fields = fields;
-    game_state.board.white_king_pos = Coordinates { x: 5, y: 1 };
-    game_state.board.black_king_pos = Coordinates { x: 5, y: 8 };
+}
+
+// puts the fields, pieces and labels where they belong once the board was turned around
+#[allow(clippy::type_complexity)]
+fn orient_board(
+    mut commands: Commands,
+    game_state: Res<GameState>,
+    mut drawn_flipped: Local<bool>,
+    mut fields: Query<(&FieldSprite, &mut Transform)>,
+    mut pieces: Query<(Entity, &PieceSprite, &mut Transform), Without<FieldSprite>>,
+    mut labels: Query<
+        (&CoordinateLabel, &mut Transform),
+        (Without<FieldSprite>, Without<PieceSprite>),
+    >,
+) {
+    let flipped = game_state.flipped;
+    if *drawn_flipped == flipped {
+        return;
+    }
+    *drawn_flipped = flipped;
+
+    for (field, mut transform) in &mut fields {
+        let translation = field_translation(field.coordinates, flipped);
+        transform.translation = translation.extend(transform.translation.z);
+    }
+    for (entity, piece, mut transform) in &mut pieces {
+        // a pawn waiting for its promotion stands on the last rank already
+        let coordinates = match game_state.pending_promotion {
+            Some(pending) if pending.entity == entity => pending.to,
+            _ => piece.coordinates,
+        };
+        transform.translation = field_translation(coordinates, flipped).extend(PIECE_Z);
+        commands.entity(entity).remove::<PieceAnimation>();
+    }
+    for (label, mut transform) in &mut labels {
+        let translation = label_translation(*label, flipped);
+        transform.translation = translation.extend(transform.translation.z);
+    }
+}
+
+// zooms the camera when the window is resized, e.g. when leaving fullscreen
+fn fit_board_to_window(
+    mut resized: EventReader<WindowResized>,
+    windows: Res<Windows>,
+    mut board_scale: ResMut<BoardScale>,
+    mut projections: Query<&mut OrthographicProjection>,
+) {
+    // the window is measured in the first frame as well
+    let resized = resized.iter().count() > 0;
+    if !resized && !board_scale.is_added() {
+        return;
+    }
+    let Some(window) = windows.get_primary() else {
+        return;
+    };
+    *board_scale = BoardScale::fitting(window.width(), window.height());
+    for mut projection in &mut projections {
+        projection.scale = board_scale.0;
+    }
+}
+
+fn despawn_coordinate_labels(mut commands: Commands, labels: Query<Entity, With<CoordinateLabel>>) {
+    for entity in labels.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
 }
 
 pub struct BoardPlugin;
 
 impl Plugin for BoardPlugin {
     fn build(&self, app: &mut App) {
-        app.add_system_set(
-            SystemSet::on_enter(GlobalState::InGame).with_system(board_spawn_system),
-        );
+        app.init_resource::<StartingPosition>()
+            .init_resource::<BoardScale>()
+            .add_system(fit_board_to_window)
+            .add_system_set(
+                SystemSet::on_enter(GlobalState::InGame)
+                    .with_system(board_spawn_system)
+                    .with_system(spawn_coordinate_labels),
+            )
+            .add_system_set(SystemSet::on_update(GlobalState::InGame).with_system(orient_board))
+            .add_system_set(
+                SystemSet::on_enter(GlobalState::MainMenu).with_system(despawn_coordinate_labels),
+            );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WINDOW_SIZES: [(f32, f32); 4] = [
+        (1920.0, 1080.0),
+        (1280.0, 800.0),
+        (800.0, 1200.0),
+        (3840.0, 900.0),
+    ];
+
+    fn coords(square: &str) -> Coordinates {
+        Coordinates::from_algebraic(square).unwrap()
+    }
+
+    // the window pixel a point of the board is drawn at
+    fn pixel(translation: Vec2, width: f32, height: f32, scale: BoardScale) -> Vec2 {
+        translation / scale.0 + Vec2::new(width, height) / 2.0
+    }
+
+    #[test]
+    fn corner_fields_are_placed_by_orientation() {
+        let corner = FIELD_SIZE * (BOARD_SIZE as f32 - 1.0) / 2.0;
+        for (square, flipped, x, y) in [
+            ("a1", false, -corner, -corner),
+            ("h8", false, corner, corner),
+            ("h1", false, corner, -corner),
+            ("a8", false, -corner, corner),
+            ("a1", true, corner, corner),
+            ("h8", true, -corner, -corner),
+            ("h1", true, -corner, corner),
+            ("a8", true, corner, -corner),
+        ] {
+            assert_eq!(
+                field_translation(coords(square), flipped),
+                Vec2::new(x, y),
+                "{square} flipped: {flipped}"
+            );
+        }
+    }
+
+    #[test]
+    fn board_fits_any_window() {
+        let half_view = FIELD_SIZE * BOARD_SIZE as f32 / 2.0 + BOARD_VIEW_MARGIN;
+        for (width, height) in WINDOW_SIZES {
+            let scale = BoardScale::fitting(width, height);
+            let half_pixels = half_view / scale.0;
+            let half_width = (width / 2.0 - PANEL_SPACE).max(width / 4.0);
+            let half_height = (height / 2.0 - TEXT_SPACE).max(height / 4.0);
+            assert!(half_pixels <= half_width + 0.01, "{width}x{height}");
+            assert!(half_pixels <= half_height + 0.01, "{width}x{height}");
+            // as large as the window allows in one direction
+            assert!(
+                (half_pixels - half_width.min(half_height)).abs() < 0.01,
+                "{width}x{height}"
+            );
+            assert_eq!(scale.field_pixels(), FIELD_SIZE / scale.0);
+        }
+    }
+
+    #[test]
+    fn cursor_finds_the_field_under_it() {
+        for (width, height) in WINDOW_SIZES {
+            let scale = BoardScale::fitting(width, height);
+            for flipped in [false, true] {
+                for x in 1..=BOARD_SIZE as i32 {
+                    for y in 1..=BOARD_SIZE as i32 {
+                        let field = Coordinates { x, y };
+                        let center = pixel(field_translation(field, flipped), width, height, scale);
+                        assert_eq!(
+                            mouse_pos_to_coordinates(
+                                center.x, center.y, width, height, scale, flipped
+                            ),
+                            field,
+                            "{width}x{height} flipped: {flipped}"
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn cursor_in_the_board_corners() {
+        let half_board = FIELD_SIZE * BOARD_SIZE as f32 / 2.0;
+        // a little inside each corner of the board as it is drawn
+        let inside = half_board - 1.0;
+        for (width, height) in WINDOW_SIZES {
+            let scale = BoardScale::fitting(width, height);
+            for (x, y, square, flipped_square) in [
+                (-inside, -inside, "a1", "h8"),
+                (inside, inside, "h8", "a1"),
+                (inside, -inside, "h1", "a8"),
+                (-inside, inside, "a8", "h1"),
+            ] {
+                let corner = pixel(Vec2::new(x, y), width, height, scale);
+                for (flipped, square) in [(false, square), (true, flipped_square)] {
+                    assert_eq!(
+                        mouse_pos_to_coordinates(corner.x, corner.y, width, height, scale, flipped),
+                        coords(square),
+                        "{width}x{height} flipped: {flipped}"
+                    );
+                }
+            }
+
+            // just outside the board there is no field
+            let outside = pixel(Vec2::splat(-half_board - 1.0), width, height, scale);
+            let off_board =
+                mouse_pos_to_coordinates(outside.x, outside.y, width, height, scale, false);
+            assert_eq!(off_board, Coordinates { x: 0, y: 0 });
+        }
     }
 }
@@ -1,94 +1,283 @@
-use crate::coordinates::*;
-use crate::user_input::handle_piece_move;
+use crate::bot_config::{BotConfig, BotPreset};
+use crate::chess_move::Move;
+use crate::engine::Engine;
+use crate::position::color_index;
+use crate::uci::{UciEngine, UciError};
+use crate::user_input::{handle_castling, handle_piece_move};
 use bevy::{
     prelude::*,
     tasks::{AsyncComputeTaskPool, Task},
 };
 use futures_lite::future;
-use std::io::Read;
-use std::{io::Write, process::Command};
+use std::fmt::{self, Display, Formatter};
+use std::io::ErrorKind;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use crate::*;
 
+// the engine the bot asks for its moves
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BotEngine {
+    // the one in `chess_core`, which works without installing anything
+    #[default]
+    Native,
+    // an installed UCI engine, Stockfish unless the bot config says otherwise
+    External,
+}
+
+impl BotEngine {
+    pub fn next(&self) -> BotEngine {
+        match self {
+            BotEngine::Native => BotEngine::External,
+            BotEngine::External => BotEngine::Native,
+        }
+    }
+}
+
+// the side the player takes against the bot
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ColorChoice {
+    #[default]
+    White,
+    Black,
+    Random,
+}
+
+impl ColorChoice {
+    pub fn next(&self) -> ColorChoice {
+        match self {
+            ColorChoice::White => ColorChoice::Black,
+            ColorChoice::Black => ColorChoice::Random,
+            ColorChoice::Random => ColorChoice::White,
+        }
+    }
+
+    // a random choice is made anew for every game
+    pub fn pick(&self) -> PieceColor {
+        match self {
+            ColorChoice::White => PieceColor::White,
+            ColorChoice::Black => PieceColor::Black,
+            ColorChoice::Random => {
+                let nanos = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map_or(0, |time| time.subsec_nanos());
+                if nanos.is_multiple_of(2) {
+                    PieceColor::White
+                } else {
+                    PieceColor::Black
+                }
+            }
+        }
+    }
+}
+
+// an engine playing at the strength of one of the presets
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BotPlayer {
+    pub engine: BotEngine,
+    pub engine_path: String,
+    pub preset: BotPreset,
+}
+
+impl BotPlayer {
+    pub fn new(engine: BotEngine, config: &BotConfig, preset: usize) -> BotPlayer {
+        let preset = config.presets[preset].clone();
+        BotPlayer {
+            engine,
+            engine_path: config.preset_engine_path(&preset).to_string(),
+            preset,
+        }
+    }
+
+    // shown during the game and written to the PGN
+    pub fn name(&self) -> String {
+        let engine = match self.engine {
+            BotEngine::Native => "Built-in engine",
+            BotEngine::External => Path::new(&self.engine_path)
+                .file_stem()
+                .and_then(|name| name.to_str())
+                .unwrap_or(&self.engine_path),
+        };
+        format!("{} ({})", engine, self.preset.name)
+    }
+}
+
+// the bot that has to move: one of the sides of an engine match, or the one chosen
+// in the bot settings
+pub fn bot_to_move(game_state: &GameState, config: &BotConfig) -> BotPlayer {
+    match &game_state.engine_match {
+        Some(engine_match) => engine_match.player(game_state.board.side_to_move).clone(),
+        None => BotPlayer::new(game_state.bot_engine, config, config.selected),
+    }
+}
+
+// the engines are kept between moves: the built-in one to reuse what it found while thinking
+// about the previous moves, the external one so that it isn't started again for every move;
+// each side has its own, as in an engine match both sides are played by engines
+#[derive(Resource, Default)]
+pub struct BotEngines {
+    native: [Arc<Mutex<Engine>>; 2],
+    external: [Arc<Mutex<Option<UciEngine>>>; 2],
+}
+
+// why the bot couldn't make a move
+#[derive(Debug)]
+pub enum BotError {
+    EngineNotFound(String),
+    Engine(UciError),
+    IllegalMove(String),
+    NoMove,
+}
+
+impl Display for BotError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            BotError::EngineNotFound(path) => write!(f, "Engine not found at {}", path),
+            BotError::Engine(error) => write!(f, "Engine failed: {}", error),
+            BotError::IllegalMove(uci) => write!(f, "Engine returned illegal move {}", uci),
+            BotError::NoMove => write!(f, "Engine didn't return any move"),
+        }
+    }
+}
+
+impl From<UciError> for BotError {
+    fn from(error: UciError) -> Self {
+        match error {
+            UciError::Spawn { command, error } if error.kind() == ErrorKind::NotFound => {
+                BotError::EngineNotFound(command)
+            }
+            UciError::NoBestMove => BotError::NoMove,
+            error => BotError::Engine(error),
+        }
+    }
+}
+
+// the move the bot is thinking about
 #[derive(Component)]
-struct BotMoveTask(Task<String>);
-
-fn spawn_task(mut commands: Commands, game_state: ResMut<GameState>) {
-    if game_state.bot_turn {
-        let thread_pool = AsyncComputeTaskPool::get();
-        let position = game_state.board.to_fen();
-        let task = thread_pool.spawn(async move { get_best_move_from_stockfish(&position) });
-        commands.spawn(BotMoveTask(task));
-    }
-}
-
-fn extract_coordinates_from_move(string: String) -> (Coordinates, Coordinates) {
-    if string.len() != 4 {
-        println!("Invalid move string: {}", string);
-        panic!("Invalid move string received from Stockfish");
-    }
-    let from = string.chars().next().expect("Invalid move string");
-    let from_number = string.chars().nth(1).expect("Invalid move string");
-    let to = string.chars().nth(2).expect("Invalid move string");
-    let to_number = string.chars().nth(3).expect("Invalid move string");
-
-    let from_first = ((from as u8 - 48) as char)
-        .to_digit(10)
-        .expect("Invalid move string") as i32;
-    let from_second = from_number.to_digit(10).expect("Invalid move string") as i32;
-    let to_first = ((to as u8 - 48) as char)
-        .to_digit(10)
-        .expect("Invalid move string") as i32;
-    let to_second = to_number.to_digit(10).expect("Invalid move string") as i32;
-
-    let from = Coordinates {
-        x: from_first,
-        y: from_second,
-    };
-    let to = Coordinates {
-        x: to_first,
-        y: to_second,
+pub struct BotMoveTask(Task<Result<String, BotError>>);
+
+// asks the engine of the bot for a move in the background
+pub fn spawn_bot_task(
+    commands: &mut Commands,
+    game_state: &GameState,
+    engines: &BotEngines,
+    bot: BotPlayer,
+) {
+    let thread_pool = AsyncComputeTaskPool::get();
+    let side = color_index(game_state.board.side_to_move);
+    let BotPlayer {
+        engine_path,
+        mut preset,
+        ..
+    } = bot;
+    // in timed games the engine manages its time itself, the think time still limits it
+    if let Some(clock) = &game_state.clock {
+        preset.limits = clock.search_limits(preset.limits);
+    }
+    let task = match bot.engine {
+        BotEngine::Native => {
+            let engine = engines.native[side].clone();
+            let board = game_state.board.clone();
+            thread_pool
+                .spawn(async move { get_best_move_from_native_engine(&engine, &board, &preset) })
+        }
+        BotEngine::External => {
+            let engine = engines.external[side].clone();
+            let start_fen = game_state.move_log.start_fen.clone();
+            let moves = game_state.move_log.uci_moves();
+            thread_pool.spawn(async move {
+                get_best_move_from_external_engine(
+                    &engine,
+                    &engine_path,
+                    &preset,
+                    &start_fen,
+                    &moves,
+                )
+            })
+        }
     };
+    commands.spawn(BotMoveTask(task));
+}
 
-    (from, to)
+fn spawn_task(
+    mut commands: Commands,
+    game_state: Res<GameState>,
+    engines: Res<BotEngines>,
+    config: Res<BotConfig>,
+) {
+    // the moves of an engine match are asked for by `engine_match`
+    if game_state.bot_turn && game_state.engine_match.is_none() {
+        let bot = bot_to_move(&game_state, &config);
+        spawn_bot_task(&mut commands, &game_state, &engines, bot);
+    }
+}
+
+// every game starts with fresh engines; an engine still thinking about a move of the previous
+// game is left to its task, which drops it when done, instead of waiting for it here
+fn reset_engines(mut engines: ResMut<BotEngines>) {
+    for engine in &mut engines.native {
+        let reset = engine
+            .try_lock()
+            .map(|mut native| native.new_game())
+            .is_ok();
+        if !reset {
+            *engine = Arc::default();
+        }
+    }
+    for engine in &mut engines.external {
+        *engine = Arc::default();
+    }
 }
 
 fn move_piece(
     commands: &mut Commands,
-    piece_query: &mut Query<(&mut Handle<Image>, &mut Transform, &mut Piece)>,
-    from_to: (Coordinates, Coordinates),
+    piece_query: &mut Query<(&mut Handle<Image>, &mut Transform, &mut PieceSprite)>,
+    bot_move: Move,
     game_state: &mut ResMut<GameState>,
     game_textures: &Res<GameTextures>,
     state: &mut ResMut<State<GlobalState>>,
     whose_turn: &mut ResMut<State<WhoseTurn>>,
 ) {
-    let from = from_to.0;
-    let old_field = game_state
-        .board
-        .get_field(from)
-        .expect("Stockfish returned invalid move");
-    let piece = old_field
-        .piece
-        .as_ref()
-        .expect("Stockfish returned invalid move");
-    let piece_entity = piece.entity.expect("Stockfish returned invalid move");
-
-    handle_piece_move(
-        commands,
-        game_state,
-        piece_query,
-        piece_entity,
-        from_to.1,
-        state,
-        game_textures,
-        whose_turn,
-    );
+    let piece_entity = game_state
+        .piece_entities
+        .get(bot_move.from)
+        .expect("The bot returned invalid move");
+
+    if bot_move.castle {
+        let rook_entity = game_state
+            .piece_entities
+            .get(bot_move.board_target())
+            .expect("The bot returned invalid move");
+        handle_castling(
+            commands,
+            game_state,
+            piece_query,
+            piece_entity,
+            rook_entity,
+            state,
+            whose_turn,
+        );
+    } else {
+        handle_piece_move(
+            commands,
+            game_state,
+            piece_query,
+            piece_entity,
+            bot_move.to,
+            state,
+            game_textures,
+            whose_turn,
+            bot_move.promotion,
+        );
+    }
 }
 
 fn manage_task(
     mut commands: Commands,
     mut tasks: Query<(Entity, &mut BotMoveTask)>,
-    mut piece_query: Query<(&mut Handle<Image>, &mut Transform, &mut Piece)>,
+    mut piece_query: Query<(&mut Handle<Image>, &mut Transform, &mut PieceSprite)>,
     mut game_state: ResMut<GameState>,
     game_textures: Res<GameTextures>,
     mut global_state: ResMut<State<GlobalState>>,
@@ -96,7 +285,14 @@ fn manage_task(
 ) {
     for (entity, mut task) in &mut tasks {
         if let Some(result) = future::block_on(future::poll_once(&mut task.0)) {
-            let best_move = extract_coordinates_from_move(result);
+            commands.entity(entity).despawn();
+            let best_move = match result.and_then(|uci| validate_move(&game_state.board, &uci)) {
+                Ok(best_move) => best_move,
+                Err(error) => {
+                    game_state.bot_error = Some(error);
+                    continue;
+                }
+            };
             move_piece(
                 &mut commands,
                 &mut piece_query,
@@ -106,53 +302,63 @@ fn manage_task(
                 &mut global_state,
                 &mut whose_turn,
             );
-
-            commands.entity(entity).despawn();
         }
     }
 }
 
-fn get_best_move_from_stockfish(position: &str) -> String {
-    let mut process = Command::new("stockfish")
-        .stdin(std::process::Stdio::piped())
-        .stdout(std::process::Stdio::piped())
-        .spawn()
-        .expect("failed to execute stockfish");
-
-    let difficulty_input = "setoption name Skill Level value 0\n";
-    let stockfish_stdin = process.stdin.as_mut().expect("failed to open stdin");
-    stockfish_stdin
-        .write_all(difficulty_input.as_bytes())
-        .expect("failed to write to stdin");
-
-    let input = format!("position fen {}\ngo movetime 500\n", position);
-
-    stockfish_stdin
-        .write_all(input.as_bytes())
-        .expect("failed to write to stdin");
-
-    std::thread::sleep(std::time::Duration::from_secs(1));
-
-    stockfish_stdin
-        .write_all(b"quit\n")
-        .expect("failed to write to stdin");
-
-    let stockfish_stdout = process.stdout.as_mut().expect("failed to open stdout");
+// engines can't be trusted to only return legal moves
+fn validate_move(board: &Board, uci: &str) -> Result<Move, BotError> {
+    Move::from_uci(board, uci)
+        .filter(|chess_move| board.is_legal_move(chess_move.from, chess_move.board_target()))
+        .ok_or_else(|| BotError::IllegalMove(uci.to_string()))
+}
 
-    let mut output = String::new();
-    stockfish_stdout
-        .read_to_string(&mut output)
-        .expect("failed to read stdout");
+fn get_best_move_from_native_engine(
+    engine: &Mutex<Engine>,
+    board: &Board,
+    preset: &BotPreset,
+) -> Result<String, BotError> {
+    let result = engine
+        .lock()
+        .expect("The engine is never left in a panicked state")
+        .search(board, preset.limits);
+    result
+        .best_move
+        .map(|best_move| best_move.to_uci())
+        .ok_or(BotError::NoMove)
+}
 
-    let mut best_move = "";
-    for line in output.lines() {
-        if line.starts_with("bestmove") {
-            best_move = line.split_whitespace().nth(1).expect("Parse error");
-            break;
-        }
+fn start_external_engine(engine_path: &str, preset: &BotPreset) -> Result<UciEngine, UciError> {
+    let mut engine = UciEngine::start(engine_path, &[])?;
+    for (name, value) in preset.uci_options() {
+        engine.set_option(name, &value)?;
     }
+    engine.new_game()?;
+    Ok(engine)
+}
 
-    best_move.to_string()
+// a failed engine is dropped, so that it is started again when it is asked for a move again
+fn get_best_move_from_external_engine(
+    engine: &Mutex<Option<UciEngine>>,
+    engine_path: &str,
+    preset: &BotPreset,
+    start_fen: &str,
+    moves: &[String],
+) -> Result<String, BotError> {
+    let mut engine = engine
+        .lock()
+        .expect("The engine is never left in a panicked state");
+    if engine.is_none() {
+        *engine = Some(start_external_engine(engine_path, preset)?);
+    }
+    let running = engine.as_mut().expect("Started above");
+    let best_move = running
+        .set_position(start_fen, moves)
+        .and_then(|_| running.go(&preset.limits, |_| {}));
+    if best_move.is_err() {
+        *engine = None;
+    }
+    Ok(best_move?)
 }
 
 fn clear_tasks(mut commands: Commands, tasks: Query<Entity, With<BotMoveTask>>) {
@@ -165,8 +371,93 @@ pub struct BotPlugin;
 
 impl Plugin for BotPlugin {
     fn build(&self, app: &mut App) {
+        app.init_resource::<BotEngines>();
+        app.add_system_set(SystemSet::on_enter(GlobalState::InGame).with_system(reset_engines));
         app.add_system_set(SystemSet::on_enter(WhoseTurn::Bot).with_system(spawn_task));
         app.add_system_set(SystemSet::on_update(WhoseTurn::Bot).with_system(manage_task));
         app.add_system_set(SystemSet::on_exit(WhoseTurn::Bot).with_system(clear_tasks));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+
+    // a shell script standing in for an engine, answering every search with the given line
+    fn fake_engine(name: &str, reply: &str) -> String {
+        let path = std::env::temp_dir().join(format!("{}-{}.sh", name, std::process::id()));
+        let script = format!(
+            "#!/bin/sh\n\
+             while read line; do\n\
+             case \"$line\" in\n\
+             uci) echo uciok ;;\n\
+             isready) echo readyok ;;\n\
+             go*) echo \"{}\" ;;\n\
+             quit) exit 0 ;;\n\
+             esac\n\
+             done\n",
+            reply
+        );
+        fs::write(&path, script).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+        path.to_string_lossy().into_owned()
+    }
+
+    // the move as the bot task gets it from the engine and checks it afterwards
+    fn engine_move(name: &str, reply: &str) -> (Result<Move, BotError>, bool) {
+        let engine_path = fake_engine(name, reply);
+        let engine = Mutex::new(None);
+        let board = Board::starting();
+        let preset = BotConfig::default().preset().clone();
+        let result = get_best_move_from_external_engine(
+            &engine,
+            &engine_path,
+            &preset,
+            &board.to_fen(),
+            &[],
+        )
+        .and_then(|uci| validate_move(&board, &uci));
+        let kept = engine.lock().unwrap().is_some();
+        drop(engine);
+        fs::remove_file(engine_path).unwrap();
+        (result, kept)
+    }
+
+    #[test]
+    fn engine_move_is_played() {
+        let (result, kept) = engine_move("good-engine", "bestmove e2e4 ponder e7e5");
+        assert_eq!(result.unwrap().to_uci(), "e2e4");
+        assert!(kept);
+    }
+
+    #[test]
+    fn missing_best_move_is_an_error() {
+        for (name, reply) in [
+            ("none-engine", "bestmove (none)"),
+            ("null-engine", "bestmove 0000"),
+            ("empty-engine", "bestmove"),
+        ] {
+            let (result, kept) = engine_move(name, reply);
+            assert!(matches!(result, Err(BotError::NoMove)), "{reply}");
+            // the engine is started again for the next move
+            assert!(!kept);
+        }
+    }
+
+    #[test]
+    fn malformed_or_illegal_best_move_is_an_error() {
+        for (name, reply, uci) in [
+            ("garbage-engine", "bestmove garbage", "garbage"),
+            ("offboard-engine", "bestmove e2e9", "e2e9"),
+            ("illegal-engine", "bestmove e2e5", "e2e5"),
+        ] {
+            let (result, _) = engine_move(name, reply);
+            assert!(
+                matches!(&result, Err(BotError::IllegalMove(illegal)) if illegal == uci),
+                "{reply}"
+            );
+        }
+    }
+}
@@ -0,0 +1,388 @@
+use crate::engine::SearchLimits;
+use bevy::prelude::*;
+use std::fmt::{self, Display, Formatter};
+use std::time::Duration;
+
+// read when the game starts if no other file is given with `--bot-config <file>`
+pub const BOT_CONFIG_FILE: &str = "bot.cfg";
+const DEFAULT_ENGINE_PATH: &str = "stockfish";
+const DEFAULT_PRESET: &str = "Medium";
+
+// how strong the bot plays: the UCI options sent to an external engine and how long
+// any engine may search
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BotPreset {
+    pub name: String,
+    // another engine than the one given for the whole file
+    pub engine_path: Option<String>,
+    pub skill_level: Option<u32>,
+    // limits the strength with UCI_LimitStrength and UCI_Elo
+    pub elo: Option<u32>,
+    pub threads: Option<u32>,
+    // in megabytes
+    pub hash: Option<u32>,
+    pub limits: SearchLimits,
+}
+
+impl BotPreset {
+    fn new(name: &str) -> BotPreset {
+        BotPreset {
+            name: name.to_string(),
+            engine_path: None,
+            skill_level: None,
+            elo: None,
+            threads: None,
+            hash: None,
+            limits: SearchLimits::default(),
+        }
+    }
+
+    fn with_strength(name: &str, skill_level: u32, movetime: u64, depth: Option<u32>) -> BotPreset {
+        BotPreset {
+            skill_level: Some(skill_level),
+            limits: SearchLimits {
+                movetime: Some(Duration::from_millis(movetime)),
+                depth,
+                ..default()
+            },
+            ..BotPreset::new(name)
+        }
+    }
+
+    // the options as UCI option names and values
+    pub fn uci_options(&self) -> Vec<(&'static str, String)> {
+        let mut options = Vec::new();
+        if let Some(skill_level) = self.skill_level {
+            options.push(("Skill Level", skill_level.to_string()));
+        }
+        if let Some(elo) = self.elo {
+            options.push(("UCI_LimitStrength", "true".to_string()));
+            options.push(("UCI_Elo", elo.to_string()));
+        }
+        if let Some(threads) = self.threads {
+            options.push(("Threads", threads.to_string()));
+        }
+        if let Some(hash) = self.hash {
+            options.push(("Hash", hash.to_string()));
+        }
+        options
+    }
+}
+
+// the external engine and the difficulty presets to choose from before a game against the bot
+#[derive(Resource, Debug, Clone, PartialEq, Eq)]
+pub struct BotConfig {
+    pub engine_path: String,
+    pub presets: Vec<BotPreset>,
+    pub selected: usize,
+}
+
+impl Default for BotConfig {
+    // the easier presets also limit the depth, as the built-in engine has no skill level
+    fn default() -> Self {
+        let presets = vec![
+            BotPreset::with_strength("Beginner", 0, 100, Some(1)),
+            BotPreset::with_strength("Easy", 5, 250, Some(2)),
+            BotPreset::with_strength("Medium", 10, 500, Some(4)),
+            BotPreset::with_strength("Hard", 15, 1000, None),
+            BotPreset::with_strength("Maximum", 20, 2000, None),
+        ];
+        BotConfig {
+            engine_path: DEFAULT_ENGINE_PATH.to_string(),
+            selected: preset_index(&presets, DEFAULT_PRESET).unwrap_or(0),
+            presets,
+        }
+    }
+}
+
+fn preset_index(presets: &[BotPreset], name: &str) -> Option<usize> {
+    presets
+        .iter()
+        .position(|preset| preset.name.eq_ignore_ascii_case(name))
+}
+
+impl BotConfig {
+    pub fn preset(&self) -> &BotPreset {
+        &self.presets[self.selected]
+    }
+
+    pub fn preset_mut(&mut self) -> &mut BotPreset {
+        &mut self.presets[self.selected]
+    }
+
+    pub fn next_preset(&mut self) {
+        self.selected = (self.selected + 1) % self.presets.len();
+    }
+
+    pub fn preset_engine_path<'a>(&'a self, preset: &'a BotPreset) -> &'a str {
+        preset.engine_path.as_deref().unwrap_or(&self.engine_path)
+    }
+}
+
+// lines are numbered from 1
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BotConfigError {
+    MissingEquals {
+        line: usize,
+    },
+    UnknownKey {
+        line: usize,
+        key: String,
+    },
+    InvalidValue {
+        line: usize,
+        key: String,
+        value: String,
+    },
+    UnterminatedSection {
+        line: usize,
+    },
+    UnknownPreset(String),
+}
+
+impl Display for BotConfigError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            BotConfigError::MissingEquals { line } => {
+                write!(f, "line {}: expected \"key = value\"", line)
+            }
+            BotConfigError::UnknownKey { line, key } => {
+                write!(f, "line {}: unknown setting \"{}\"", line, key)
+            }
+            BotConfigError::InvalidValue { line, key, value } => {
+                write!(f, "line {}: invalid value \"{}\" for {}", line, value, key)
+            }
+            BotConfigError::UnterminatedSection { line } => {
+                write!(f, "line {}: expected \"[preset name]\"", line)
+            }
+            BotConfigError::UnknownPreset(name) => write!(f, "no preset named \"{}\"", name),
+        }
+    }
+}
+
+// a "#" starts a comment at the start of a line or after whitespace, so that paths may
+// contain one
+fn strip_comment(line: &str) -> &str {
+    let mut previous = None;
+    for (index, symbol) in line.char_indices() {
+        if symbol == '#' && previous.is_none_or(char::is_whitespace) {
+            return &line[..index];
+        }
+        previous = Some(symbol);
+    }
+    line
+}
+
+// settings before the first section apply to the whole file, every "[name]" section
+// describes a preset, e.g.
+//
+// engine_path = /usr/games/stockfish
+// default_preset = Club player
+//
+// [Club player]
+// elo = 1600
+// threads = 2
+// hash = 64
+// movetime = 1000
+//
+// presets given in the file replace the built-in ones; a preset can use another engine
+// with its own `engine_path`, so that two engines can be matched against each other
+pub fn parse_bot_config(text: &str) -> Result<BotConfig, BotConfigError> {
+    let mut config = BotConfig::default();
+    let mut presets: Vec<BotPreset> = Vec::new();
+    let mut default_preset = None;
+
+    for (index, line) in text.lines().enumerate() {
+        let line_number = index + 1;
+        let line = strip_comment(line).trim();
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(section) = line.strip_prefix('[') {
+            let name = section
+                .strip_suffix(']')
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .ok_or(BotConfigError::UnterminatedSection { line: line_number })?;
+            presets.push(BotPreset::new(name));
+            continue;
+        }
+
+        let (key, value) = line
+            .split_once('=')
+            .ok_or(BotConfigError::MissingEquals { line: line_number })?;
+        let (key, value) = (key.trim(), value.trim());
+        let invalid_value = || BotConfigError::InvalidValue {
+            line: line_number,
+            key: key.to_string(),
+            value: value.to_string(),
+        };
+        let number = || value.parse::<u64>().map_err(|_| invalid_value());
+        let small_number = || value.parse::<u32>().map_err(|_| invalid_value());
+
+        match (presets.last_mut(), key) {
+            (None, "engine_path") => config.engine_path = value.to_string(),
+            (None, "default_preset") => default_preset = Some(value.to_string()),
+            (Some(preset), "engine_path") => preset.engine_path = Some(value.to_string()),
+            (Some(preset), "skill_level") => preset.skill_level = Some(small_number()?),
+            (Some(preset), "elo") => preset.elo = Some(small_number()?),
+            (Some(preset), "threads") => preset.threads = Some(small_number()?),
+            (Some(preset), "hash") => preset.hash = Some(small_number()?),
+            (Some(preset), "movetime") => {
+                preset.limits.movetime = Some(Duration::from_millis(number()?))
+            }
+            (Some(preset), "depth") => preset.limits.depth = Some(small_number()?),
+            (Some(preset), "nodes") => preset.limits.nodes = Some(number()?),
+            _ => {
+                return Err(BotConfigError::UnknownKey {
+                    line: line_number,
+                    key: key.to_string(),
+                })
+            }
+        }
+    }
+
+    if !presets.is_empty() {
+        config.presets = presets;
+        config.selected = 0;
+    }
+    if let Some(name) = default_preset {
+        config.selected =
+            preset_index(&config.presets, &name).ok_or(BotConfigError::UnknownPreset(name))?;
+    }
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn documented_example_is_read() {
+        let config = parse_bot_config(
+            "engine_path = /usr/games/stockfish\n\
+             default_preset = Club player\n\
+             \n\
+             [Club player]\n\
+             elo = 1600\n\
+             threads = 2\n\
+             hash = 64\n\
+             movetime = 1000\n",
+        )
+        .unwrap();
+        assert_eq!(config.engine_path, "/usr/games/stockfish");
+        assert_eq!(config.presets.len(), 1);
+        let preset = config.preset();
+        assert_eq!(preset.name, "Club player");
+        assert_eq!(preset.elo, Some(1600));
+        assert_eq!(preset.threads, Some(2));
+        assert_eq!(preset.hash, Some(64));
+        assert_eq!(preset.limits.movetime, Some(Duration::from_millis(1000)));
+        assert_eq!(preset.skill_level, None);
+        assert_eq!(config.preset_engine_path(preset), "/usr/games/stockfish");
+    }
+
+    #[test]
+    fn empty_file_keeps_the_built_in_presets() {
+        assert_eq!(parse_bot_config("").unwrap(), BotConfig::default());
+        assert_eq!(BotConfig::default().preset().name, DEFAULT_PRESET);
+    }
+
+    #[test]
+    fn comments_are_skipped_but_paths_may_contain_hashes() {
+        let config = parse_bot_config(
+            "# engines\n\
+             engine_path = /opt/engines#2/stockfish # the second build\n\
+             \t# indented comment\n\
+             [Fast] # quick games\n\
+             depth = 3 #plies\n\
+             nodes = 5000\n",
+        )
+        .unwrap();
+        assert_eq!(config.engine_path, "/opt/engines#2/stockfish");
+        assert_eq!(config.preset().name, "Fast");
+        assert_eq!(config.preset().limits.depth, Some(3));
+        assert_eq!(config.preset().limits.nodes, Some(5000));
+    }
+
+    #[test]
+    fn presets_can_use_their_own_engine() {
+        let config = parse_bot_config(
+            "engine_path = stockfish\n\
+             [Stockfish]\n\
+             skill_level = 20\n\
+             [Other]\n\
+             engine_path = ./engines/other\n",
+        )
+        .unwrap();
+        let paths: Vec<&str> = config
+            .presets
+            .iter()
+            .map(|preset| config.preset_engine_path(preset))
+            .collect();
+        assert_eq!(paths, ["stockfish", "./engines/other"]);
+        assert_eq!(config.presets[0].skill_level, Some(20));
+    }
+
+    #[test]
+    fn default_preset_ignores_case() {
+        let config =
+            parse_bot_config("default_preset = HARD\n[Easy]\ndepth = 1\n[Hard]\ndepth = 8\n")
+                .unwrap();
+        assert_eq!(config.selected, 1);
+        // without presets in the file it picks one of the built-in ones
+        let config = parse_bot_config("default_preset = maximum\n").unwrap();
+        assert_eq!(config.preset().name, "Maximum");
+    }
+
+    #[test]
+    fn errors_name_their_line() {
+        assert_eq!(
+            parse_bot_config("# comment\nengine_path stockfish\n"),
+            Err(BotConfigError::MissingEquals { line: 2 })
+        );
+        assert_eq!(
+            parse_bot_config("[Fast]\n\nspeed = 10\n"),
+            Err(BotConfigError::UnknownKey {
+                line: 3,
+                key: "speed".to_string()
+            })
+        );
+        // preset settings can't be given for the whole file
+        assert_eq!(
+            parse_bot_config("depth = 3\n"),
+            Err(BotConfigError::UnknownKey {
+                line: 1,
+                key: "depth".to_string()
+            })
+        );
+        assert_eq!(
+            parse_bot_config("[Fast]\nelo = strong\n"),
+            Err(BotConfigError::InvalidValue {
+                line: 2,
+                key: "elo".to_string(),
+                value: "strong".to_string()
+            })
+        );
+        assert_eq!(
+            parse_bot_config("[Fast]\nmovetime = -5\n"),
+            Err(BotConfigError::InvalidValue {
+                line: 2,
+                key: "movetime".to_string(),
+                value: "-5".to_string()
+            })
+        );
+        assert_eq!(
+            parse_bot_config("[Fast\n"),
+            Err(BotConfigError::UnterminatedSection { line: 1 })
+        );
+        assert_eq!(
+            parse_bot_config("[]\n"),
+            Err(BotConfigError::UnterminatedSection { line: 1 })
+        );
+        assert_eq!(
+            parse_bot_config("default_preset = Slow\n[Fast]\n"),
+            Err(BotConfigError::UnknownPreset("Slow".to_string()))
+        );
+    }
+}
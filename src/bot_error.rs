@@ -0,0 +1,187 @@
+use crate::bot::{bot_to_move, spawn_bot_task, BotEngine, BotEngines};
+use crate::bot_config::BotConfig;
+use crate::game_over::despawn_board;
+use crate::main_menu::spawn_menu_button;
+use crate::ui::*;
+use crate::*;
+
+#[derive(Component)]
+struct BotErrorRoot;
+
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+enum BotErrorButton {
+    Retry,
+    UseNativeEngine,
+    ExitToMenu,
+}
+
+// shown over the board when the engine couldn't make a move
+fn spawn_bot_error(
+    mut commands: Commands,
+    game_state: Res<GameState>,
+    config: Res<BotConfig>,
+    asset_server: Res<AssetServer>,
+    error_root: Query<Entity, With<BotErrorRoot>>,
+) {
+    let Some(error) = &game_state.bot_error else {
+        return;
+    };
+    if !error_root.is_empty() {
+        return;
+    }
+
+    let mut buttons = vec![(BotErrorButton::Retry, RETRY_TEXT)];
+    if bot_to_move(&game_state, &config).engine != BotEngine::Native {
+        buttons.push((BotErrorButton::UseNativeEngine, USE_NATIVE_ENGINE_TEXT));
+    }
+    buttons.push((BotErrorButton::ExitToMenu, EXIT_TO_MENU_TEXT));
+    let buttons: Vec<Entity> = buttons
+        .into_iter()
+        .map(|(button, text)| {
+            let entity = spawn_menu_button(&mut commands, &asset_server, text);
+            commands
+                .entity(entity)
+                .insert(button)
+                .insert(BackgroundColor(BURGUNDY_DARK));
+            entity
+        })
+        .collect();
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                justify_content: JustifyContent::Center,
+                flex_direction: FlexDirection::Column,
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+            background_color: TRANSPARENT_GRAY.into(),
+            ..default()
+        })
+        .insert(BotErrorRoot)
+        .with_children(|commands| {
+            commands.spawn(TextBundle {
+                style: Style {
+                    align_self: AlignSelf::Center,
+                    margin: UiRect::all(Val::Percent(3.0)),
+                    ..default()
+                },
+                text: Text::from_section(
+                    error.to_string(),
+                    TextStyle {
+                        font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+                        font_size: 50.0,
+                        color: Color::WHITE,
+                    },
+                ),
+                ..default()
+            });
+        })
+        .push_children(&buttons);
+}
+
+// an engine match asks for its moves itself, it only has to be told to go on when paused
+fn ask_again(
+    commands: &mut Commands,
+    game_state: &mut GameState,
+    engines: &BotEngines,
+    config: &BotConfig,
+) {
+    match game_state.engine_match.as_mut() {
+        Some(engine_match) => engine_match.step = engine_match.paused,
+        None => {
+            let bot = bot_to_move(game_state, config);
+            spawn_bot_task(commands, game_state, engines, bot);
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn handle_bot_error_button(
+    mut commands: Commands,
+    mut interactions: Query<
+        (&Interaction, &BotErrorButton, &mut BackgroundColor),
+        Changed<Interaction>,
+    >,
+    error_root: Query<Entity, With<BotErrorRoot>>,
+    mut game_state: ResMut<GameState>,
+    engines: Res<BotEngines>,
+    config: Res<BotConfig>,
+    mut global_state: ResMut<State<GlobalState>>,
+    mut whose_turn: ResMut<State<WhoseTurn>>,
+    piece_query: Query<Entity, With<PieceSprite>>,
+    field_query: Query<Entity, With<FieldSprite>>,
+    color_text_query: Query<Entity, With<ColorText>>,
+    fps_text_query: Query<Entity, With<FpsText>>,
+) {
+    for (interaction, button, mut color) in &mut interactions {
+        match *interaction {
+            Interaction::Clicked => {
+                for entity in error_root.iter() {
+                    commands.entity(entity).despawn_recursive();
+                }
+                game_state.bot_error = None;
+                match button {
+                    BotErrorButton::Retry => {
+                        ask_again(&mut commands, &mut game_state, &engines, &config);
+                    }
+                    BotErrorButton::UseNativeEngine => {
+                        let side_to_move = game_state.board.side_to_move;
+                        match game_state.engine_match.as_mut() {
+                            Some(engine_match) => {
+                                engine_match.player_mut(side_to_move).engine = BotEngine::Native
+                            }
+                            None => game_state.bot_engine = BotEngine::Native,
+                        }
+                        ask_again(&mut commands, &mut game_state, &engines, &config);
+                    }
+                    BotErrorButton::ExitToMenu => {
+                        if whose_turn.current() == &WhoseTurn::Bot {
+                            whose_turn
+                                .set(WhoseTurn::Player)
+                                .expect("Error in setting state");
+                        }
+                        despawn_board(
+                            &mut commands,
+                            &piece_query,
+                            &field_query,
+                            &color_text_query,
+                            &fps_text_query,
+                        );
+                        global_state
+                            .set(GlobalState::MainMenu)
+                            .expect("Error in setting state");
+                    }
+                }
+                return;
+            }
+            Interaction::Hovered => {
+                *color = BURGUNDY_LIGHT.into();
+            }
+            Interaction::None => {
+                *color = BURGUNDY_DARK.into();
+            }
+        }
+    }
+}
+
+// the game may be left from the pause menu while the message is shown
+fn despawn_bot_error(mut commands: Commands, error_root: Query<Entity, With<BotErrorRoot>>) {
+    for entity in error_root.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+pub struct BotErrorPlugin;
+
+impl Plugin for BotErrorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(
+            SystemSet::on_update(GlobalState::InGame)
+                .with_system(spawn_bot_error)
+                .with_system(handle_bot_error_button),
+        )
+        .add_system_set(SystemSet::on_enter(GlobalState::MainMenu).with_system(despawn_bot_error));
+    }
+}
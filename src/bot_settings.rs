@@ -0,0 +1,201 @@
+use crate::bot::BotEngine;
+use crate::bot_config::BotConfig;
+use crate::chess_pieces::PieceColor;
+use crate::main_menu::spawn_menu_button;
+use crate::*;
+use bevy_kira_audio::prelude::*;
+use std::time::Duration;
+
+// the think times the button cycles through, in milliseconds
+const MOVE_TIMES: [u64; 7] = [100, 250, 500, 1000, 2000, 5000, 10000];
+
+#[derive(Component)]
+struct BotSettingsRoot;
+
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+enum SettingsButton {
+    Engine,
+    Color,
+    Difficulty,
+    ThinkTime,
+    Start,
+    Back,
+}
+
+impl SettingsButton {
+    fn text(&self, game_state: &GameState, config: &BotConfig) -> String {
+        match self {
+            SettingsButton::Engine => match game_state.bot_engine {
+                BotEngine::Native => NATIVE_ENGINE_TEXT.to_string(),
+                BotEngine::External => {
+                    format!("Engine: {}", config.preset_engine_path(config.preset()))
+                }
+            },
+            SettingsButton::Color => format!("Play as: {:?}", game_state.color_choice),
+            SettingsButton::Difficulty => format!("Difficulty: {}", config.preset().name),
+            SettingsButton::ThinkTime => match config.preset().limits.movetime {
+                Some(movetime) => format!("Think time: {} ms", movetime.as_millis()),
+                None => "Think time: no limit".to_string(),
+            },
+            SettingsButton::Start => START_BOT_GAME_TEXT.to_string(),
+            SettingsButton::Back => BACK_TO_MENU_TEXT.to_string(),
+        }
+    }
+}
+
+fn next_move_time(movetime: Option<Duration>) -> Duration {
+    let current = movetime.map_or(0, |movetime| movetime.as_millis() as u64);
+    let next = MOVE_TIMES
+        .iter()
+        .find(|time| **time > current)
+        .unwrap_or(&MOVE_TIMES[0]);
+    Duration::from_millis(*next)
+}
+
+fn spawn_bot_settings(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    game_state: Res<GameState>,
+    config: Res<BotConfig>,
+) {
+    let buttons: Vec<Entity> = [
+        SettingsButton::Engine,
+        SettingsButton::Color,
+        SettingsButton::Difficulty,
+        SettingsButton::ThinkTime,
+        SettingsButton::Start,
+        SettingsButton::Back,
+    ]
+    .into_iter()
+    .map(|button| {
+        let text = button.text(&game_state, &config);
+        let entity = spawn_menu_button(&mut commands, &asset_server, &text);
+        commands.entity(entity).insert(button);
+        entity
+    })
+    .collect();
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                justify_content: JustifyContent::Center,
+                flex_direction: FlexDirection::Column,
+                ..default()
+            },
+            ..default()
+        })
+        .insert(BotSettingsRoot)
+        .with_children(|commands| {
+            commands.spawn(TextBundle {
+                style: Style {
+                    align_self: AlignSelf::Center,
+                    margin: UiRect::all(Val::Percent(3.0)),
+                    ..default()
+                },
+                text: Text::from_section(
+                    "Bot settings",
+                    TextStyle {
+                        font: asset_server.load("fonts/Aboreto-Regular.ttf"),
+                        font_size: 85.0,
+                        color: Color::BLACK,
+                    },
+                ),
+                ..default()
+            });
+        })
+        .push_children(&buttons);
+}
+
+fn handle_settings_buttons(
+    mut interactions: Query<
+        (&Interaction, &SettingsButton, &mut BackgroundColor),
+        Changed<Interaction>,
+    >,
+    mut game_state: ResMut<GameState>,
+    mut config: ResMut<BotConfig>,
+    mut global_state: ResMut<State<GlobalState>>,
+    audio: Res<bevy_kira_audio::prelude::Audio>,
+) {
+    for (interaction, button, mut color) in &mut interactions {
+        match *interaction {
+            Interaction::Clicked => match button {
+                SettingsButton::Engine => game_state.bot_engine = game_state.bot_engine.next(),
+                SettingsButton::Color => game_state.color_choice = game_state.color_choice.next(),
+                SettingsButton::Difficulty => config.next_preset(),
+                SettingsButton::ThinkTime => {
+                    let limits = &mut config.preset_mut().limits;
+                    limits.movetime = Some(next_move_time(limits.movetime));
+                }
+                SettingsButton::Start => {
+                    game_state.vs_bot = true;
+                    game_state.engine_match = None;
+                    game_state.player_color = game_state.color_choice.pick();
+                    // the player's pieces are at the bottom
+                    game_state.flipped = game_state.player_color == PieceColor::Black;
+                    game_state.white = true;
+                    game_state.bot_turn = false;
+                    game_state.result = None;
+                    global_state
+                        .set(GlobalState::InGame)
+                        .expect("Error in setting state");
+                    audio.pause().fade_out(AudioTween::default());
+                }
+                SettingsButton::Back => {
+                    global_state
+                        .set(GlobalState::MainMenu)
+                        .expect("Error in setting state");
+                }
+            },
+            Interaction::Hovered => {
+                *color = LIGHT_GRAY.into();
+            }
+            Interaction::None => {
+                *color = DARK_GRAY.into();
+            }
+        }
+    }
+}
+
+// the texts of the buttons show the current settings
+fn update_settings_texts(
+    buttons: Query<(&SettingsButton, &Children)>,
+    mut text_query: Query<&mut Text>,
+    game_state: Res<GameState>,
+    config: Res<BotConfig>,
+) {
+    if !game_state.is_changed() && !config.is_changed() {
+        return;
+    }
+    for (button, children) in &buttons {
+        for child in children.iter() {
+            if let Ok(mut text) = text_query.get_mut(*child) {
+                text.sections[0].value = button.text(&game_state, &config);
+            }
+        }
+    }
+}
+
+fn despawn_bot_settings(mut commands: Commands, root: Query<Entity, With<BotSettingsRoot>>) {
+    for entity in root.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+pub struct BotSettingsPlugin;
+
+impl Plugin for BotSettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(
+            SystemSet::on_enter(GlobalState::BotSettings).with_system(spawn_bot_settings),
+        )
+        .add_system_set(
+            SystemSet::on_update(GlobalState::BotSettings)
+                .with_system(handle_settings_buttons)
+                .with_system(update_settings_texts),
+        )
+        .add_system_set(
+            SystemSet::on_exit(GlobalState::BotSettings).with_system(despawn_bot_settings),
+        );
+    }
+}
@@ -0,0 +1,407 @@
+use crate::bot::{spawn_bot_task, BotEngine, BotEngines, BotMoveTask, BotPlayer};
+use crate::bot_config::BotConfig;
+use crate::clock::ChessClock;
+use crate::main_menu::spawn_menu_button;
+use crate::time_control::format_clock_time;
+use crate::*;
+use bevy::time::Stopwatch;
+use bevy_kira_audio::prelude::*;
+use std::time::Duration;
+
+// the delays the button cycles through, in milliseconds
+const MOVE_DELAYS: [u64; 6] = [0, 250, 500, 1000, 2000, 5000];
+const DEFAULT_MOVE_DELAY: Duration = Duration::from_millis(500);
+
+// a game in which both sides are played by engines
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EngineMatch {
+    pub white: BotPlayer,
+    pub black: BotPlayer,
+    // waited after every move, so that the game can be followed
+    pub delay: Duration,
+    pub paused: bool,
+    // a single move is played while the match is paused
+    pub step: bool,
+}
+
+impl EngineMatch {
+    pub fn player(&self, color: PieceColor) -> &BotPlayer {
+        match color {
+            PieceColor::White => &self.white,
+            PieceColor::Black => &self.black,
+        }
+    }
+
+    pub fn player_mut(&mut self, color: PieceColor) -> &mut BotPlayer {
+        match color {
+            PieceColor::White => &mut self.white,
+            PieceColor::Black => &mut self.black,
+        }
+    }
+}
+
+// an engine and a preset, chosen before the match
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct MatchSide {
+    engine: BotEngine,
+    preset: usize,
+}
+
+impl MatchSide {
+    // every preset of the built-in engine, then every preset of the external one
+    fn next(&self, presets: usize) -> MatchSide {
+        if self.preset + 1 < presets {
+            MatchSide {
+                preset: self.preset + 1,
+                ..*self
+            }
+        } else {
+            MatchSide {
+                engine: self.engine.next(),
+                preset: 0,
+            }
+        }
+    }
+
+    fn player(&self, config: &BotConfig) -> BotPlayer {
+        BotPlayer::new(
+            self.engine,
+            config,
+            self.preset.min(config.presets.len() - 1),
+        )
+    }
+}
+
+#[derive(Resource, Debug, Clone, PartialEq, Eq)]
+struct MatchSettings {
+    white: MatchSide,
+    black: MatchSide,
+    delay: Duration,
+}
+
+impl Default for MatchSettings {
+    fn default() -> Self {
+        MatchSettings {
+            white: MatchSide::default(),
+            black: MatchSide::default(),
+            delay: DEFAULT_MOVE_DELAY,
+        }
+    }
+}
+
+fn next_move_delay(delay: Duration) -> Duration {
+    let current = delay.as_millis() as u64;
+    let next = MOVE_DELAYS
+        .iter()
+        .find(|delay| **delay > current)
+        .unwrap_or(&MOVE_DELAYS[0]);
+    Duration::from_millis(*next)
+}
+
+#[derive(Component)]
+struct MatchSettingsRoot;
+
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+enum MatchButton {
+    White,
+    Black,
+    Delay,
+    Start,
+    Back,
+}
+
+impl MatchButton {
+    fn text(&self, settings: &MatchSettings, config: &BotConfig) -> String {
+        match self {
+            MatchButton::White => format!("White: {}", settings.white.player(config).name()),
+            MatchButton::Black => format!("Black: {}", settings.black.player(config).name()),
+            MatchButton::Delay => format!("Delay: {} ms", settings.delay.as_millis()),
+            MatchButton::Start => START_MATCH_TEXT.to_string(),
+            MatchButton::Back => BACK_TO_MENU_TEXT.to_string(),
+        }
+    }
+}
+
+fn spawn_match_settings(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    settings: Res<MatchSettings>,
+    config: Res<BotConfig>,
+) {
+    let buttons: Vec<Entity> = [
+        MatchButton::White,
+        MatchButton::Black,
+        MatchButton::Delay,
+        MatchButton::Start,
+        MatchButton::Back,
+    ]
+    .into_iter()
+    .map(|button| {
+        let text = button.text(&settings, &config);
+        let entity = spawn_menu_button(&mut commands, &asset_server, &text);
+        commands.entity(entity).insert(button);
+        entity
+    })
+    .collect();
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                justify_content: JustifyContent::Center,
+                flex_direction: FlexDirection::Column,
+                ..default()
+            },
+            ..default()
+        })
+        .insert(MatchSettingsRoot)
+        .with_children(|commands| {
+            commands.spawn(TextBundle {
+                style: Style {
+                    align_self: AlignSelf::Center,
+                    margin: UiRect::all(Val::Percent(3.0)),
+                    ..default()
+                },
+                text: Text::from_section(
+                    "Engine match",
+                    TextStyle {
+                        font: asset_server.load("fonts/Aboreto-Regular.ttf"),
+                        font_size: 85.0,
+                        color: Color::BLACK,
+                    },
+                ),
+                ..default()
+            });
+        })
+        .push_children(&buttons);
+}
+
+fn handle_match_buttons(
+    mut interactions: Query<
+        (&Interaction, &MatchButton, &mut BackgroundColor),
+        Changed<Interaction>,
+    >,
+    mut settings: ResMut<MatchSettings>,
+    mut game_state: ResMut<GameState>,
+    config: Res<BotConfig>,
+    mut global_state: ResMut<State<GlobalState>>,
+    audio: Res<bevy_kira_audio::prelude::Audio>,
+) {
+    for (interaction, button, mut color) in &mut interactions {
+        match *interaction {
+            Interaction::Clicked => match button {
+                MatchButton::White => settings.white = settings.white.next(config.presets.len()),
+                MatchButton::Black => settings.black = settings.black.next(config.presets.len()),
+                MatchButton::Delay => settings.delay = next_move_delay(settings.delay),
+                MatchButton::Start => {
+                    game_state.engine_match = Some(EngineMatch {
+                        white: settings.white.player(&config),
+                        black: settings.black.player(&config),
+                        delay: settings.delay,
+                        paused: false,
+                        step: false,
+                    });
+                    // the player only watches, so the bot is always to move
+                    game_state.vs_bot = true;
+                    game_state.bot_turn = true;
+                    game_state.flipped = false;
+                    game_state.white = true;
+                    game_state.result = None;
+                    global_state
+                        .set(GlobalState::InGame)
+                        .expect("Error in setting state");
+                    audio.pause().fade_out(AudioTween::default());
+                }
+                MatchButton::Back => {
+                    global_state
+                        .set(GlobalState::MainMenu)
+                        .expect("Error in setting state");
+                }
+            },
+            Interaction::Hovered => {
+                *color = LIGHT_GRAY.into();
+            }
+            Interaction::None => {
+                *color = DARK_GRAY.into();
+            }
+        }
+    }
+}
+
+fn update_match_settings_texts(
+    buttons: Query<(&MatchButton, &Children)>,
+    mut text_query: Query<&mut Text>,
+    settings: Res<MatchSettings>,
+    config: Res<BotConfig>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+    for (button, children) in &buttons {
+        for child in children.iter() {
+            if let Ok(mut text) = text_query.get_mut(*child) {
+                text.sections[0].value = button.text(&settings, &config);
+            }
+        }
+    }
+}
+
+fn despawn_match_settings(mut commands: Commands, root: Query<Entity, With<MatchSettingsRoot>>) {
+    for entity in root.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+// the engine to move is asked once the previous move was shown for the chosen delay
+fn request_engine_move(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut since_last_move: Local<Stopwatch>,
+    mut game_state: ResMut<GameState>,
+    engines: Res<BotEngines>,
+    tasks: Query<(), With<BotMoveTask>>,
+) {
+    if game_state.engine_match.is_none() || game_state.bot_error.is_some() || !tasks.is_empty() {
+        return;
+    }
+    since_last_move.tick(time.delta());
+
+    let side_to_move = game_state.board.side_to_move;
+    let engine_match = game_state.engine_match.as_mut().expect("Checked above");
+    if engine_match.paused {
+        if !engine_match.step {
+            return;
+        }
+        engine_match.step = false;
+    } else if since_last_move.elapsed() < engine_match.delay {
+        return;
+    }
+    since_last_move.reset();
+    let bot = engine_match.player(side_to_move).clone();
+    spawn_bot_task(&mut commands, &game_state, &engines, bot);
+}
+
+// space pauses and resumes the match, N plays a single move while it is paused
+fn control_engine_match(keys: Res<Input<KeyCode>>, mut game_state: ResMut<GameState>) {
+    let Some(engine_match) = game_state.engine_match.as_mut() else {
+        return;
+    };
+    if keys.just_pressed(KeyCode::Space) {
+        engine_match.paused = !engine_match.paused;
+    }
+    if keys.just_pressed(KeyCode::N) && engine_match.paused {
+        engine_match.step = true;
+    }
+}
+
+#[derive(Component)]
+struct MatchPanel;
+
+fn spawn_match_panel(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    game_state: Res<GameState>,
+    panel: Query<Entity, With<MatchPanel>>,
+) {
+    // left over from the previous match
+    for entity in panel.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    if game_state.engine_match.is_none() {
+        return;
+    }
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+                font_size: 25.0,
+                color: Color::WHITE,
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                top: Val::Px(100.0),
+                right: Val::Px(50.0),
+                ..default()
+            },
+            ..default()
+        }),
+        MatchPanel,
+    ));
+}
+
+// the players with their clocks and whether the match is paused; the moves are in the move
+// history panel
+fn match_panel_text(engine_match: &EngineMatch, clock: Option<&ChessClock>) -> String {
+    let status = if engine_match.paused {
+        "Paused: Space to resume, N for the next move"
+    } else {
+        "Space to pause"
+    };
+    let player = |color| {
+        let name = engine_match.player(color).name();
+        match clock {
+            Some(clock) => format!("{} {}", name, format_clock_time(clock.remaining(color))),
+            None => name,
+        }
+    };
+    format!(
+        "White: {}\nBlack: {}\n{}",
+        player(PieceColor::White),
+        player(PieceColor::Black),
+        status
+    )
+}
+
+fn update_match_panel(
+    game_state: Res<GameState>,
+    mut panel: Query<&mut Text, With<MatchPanel>>,
+    spawned: Query<(), Added<MatchPanel>>,
+) {
+    let Some(engine_match) = &game_state.engine_match else {
+        return;
+    };
+    if !game_state.is_changed() && spawned.is_empty() {
+        return;
+    }
+    for mut text in &mut panel {
+        text.sections[0].value = match_panel_text(engine_match, game_state.clock.as_ref());
+    }
+}
+
+fn despawn_match_panel(mut commands: Commands, panel: Query<Entity, With<MatchPanel>>) {
+    for entity in panel.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+pub struct EngineMatchPlugin;
+
+impl Plugin for EngineMatchPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MatchSettings>()
+            .add_system_set(
+                SystemSet::on_enter(GlobalState::MatchSettings).with_system(spawn_match_settings),
+            )
+            .add_system_set(
+                SystemSet::on_update(GlobalState::MatchSettings)
+                    .with_system(handle_match_buttons)
+                    .with_system(update_match_settings_texts),
+            )
+            .add_system_set(
+                SystemSet::on_exit(GlobalState::MatchSettings).with_system(despawn_match_settings),
+            )
+            .add_system_set(SystemSet::on_enter(GlobalState::InGame).with_system(spawn_match_panel))
+            .add_system_set(
+                SystemSet::on_update(GlobalState::InGame)
+                    .with_system(request_engine_move)
+                    .with_system(control_engine_match)
+                    .with_system(update_match_panel),
+            )
+            .add_system_set(
+                SystemSet::on_enter(GlobalState::MainMenu).with_system(despawn_match_panel),
+            );
+    }
+}
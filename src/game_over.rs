@@ -1,4 +1,8 @@
-use crate::{field::Field, ui::*, *};
+use crate::bot::bot_to_move;
+use crate::bot_config::BotConfig;
+use crate::game_result::EndReason;
+use crate::pgn::{save_pgn, Pgn};
+use crate::{ui::*, *};
 use bevy_kira_audio::AudioControl;
 
 #[derive(Component)]
@@ -7,14 +11,20 @@ struct PlayAgainButton;
 #[derive(Component)]
 struct GameOverText;
 
+// saves the current game as PGN, shared by the game over and pause screens
+#[derive(Component)]
+pub struct SavePgnButton {
+    pub idle_color: Color,
+}
+
 #[derive(Component)]
 
 struct GameOverRoot;
 
 pub fn despawn_board(
     commands: &mut Commands,
-    piece_query: &Query<Entity, With<Piece>>,
-    field_query: &Query<Entity, With<Field>>,
+    piece_query: &Query<Entity, With<PieceSprite>>,
+    field_query: &Query<Entity, With<FieldSprite>>,
     color_text_qury: &Query<Entity, With<ColorText>>,
     fps_text_qury: &Query<Entity, With<FpsText>>,
 ) {
@@ -38,8 +48,8 @@ fn play_again_button_clicked(
     mut commands: Commands,
     mut interactions: Query<(&Interaction, &mut BackgroundColor), With<PlayAgainButton>>,
     mut global_state: ResMut<State<GlobalState>>,
-    piece_query: Query<Entity, With<Piece>>,
-    field_query: Query<Entity, With<Field>>,
+    piece_query: Query<Entity, With<PieceSprite>>,
+    field_query: Query<Entity, With<FieldSprite>>,
     color_text_query: Query<Entity, With<ColorText>>,
     fps_text_query: Query<Entity, With<FpsText>>,
     game_over_root: Query<Entity, With<GameOverRoot>>,
@@ -72,20 +82,80 @@ fn play_again_button_clicked(
     }
 }
 
+pub fn game_pgn(game_state: &GameState, config: &BotConfig) -> Pgn {
+    let mut pgn = if let Some(engine_match) = &game_state.engine_match {
+        let (white, black) = (engine_match.white.name(), engine_match.black.name());
+        Pgn::new(&game_state.move_log, &white, &black, game_state.result)
+    } else {
+        let (white, black) = if game_state.vs_bot && game_state.player_color == PieceColor::Black {
+            (bot_to_move(game_state, config).name(), "Player".to_string())
+        } else if game_state.vs_bot {
+            ("Player".to_string(), bot_to_move(game_state, config).name())
+        } else {
+            ("Player 1".to_string(), "Player 2".to_string())
+        };
+        Pgn::new(&game_state.move_log, &white, &black, game_state.result)
+    };
+    if let Some(clock) = &game_state.clock {
+        pgn.set_tag("TimeControl", &clock.control.pgn_tag());
+    }
+    if game_state.result.is_some_and(|result| {
+        matches!(
+            result.reason,
+            EndReason::Timeout | EndReason::TimeoutVsInsufficientMaterial
+        )
+    }) {
+        pgn.set_tag("Termination", "time forfeit");
+    }
+    pgn
+}
+
+pub fn handle_save_pgn_button(
+    mut interactions: Query<
+        (
+            &Interaction,
+            &SavePgnButton,
+            &Children,
+            &mut BackgroundColor,
+        ),
+        Changed<Interaction>,
+    >,
+    mut text_query: Query<&mut Text>,
+    game_state: Res<GameState>,
+    config: Res<BotConfig>,
+) {
+    for (interaction, button, children, mut color) in &mut interactions {
+        match *interaction {
+            Interaction::Clicked => {
+                let message = match save_pgn(&game_pgn(&game_state, &config)) {
+                    Ok(path) => format!("Saved to {}", path.display()),
+                    Err(error) => format!("Saving failed: {}", error),
+                };
+                for child in children.iter() {
+                    if let Ok(mut text) = text_query.get_mut(*child) {
+                        text.sections[0].value = message.clone();
+                    }
+                }
+            }
+            Interaction::Hovered => {
+                *color = BURGUNDY_LIGHT.into();
+            }
+            Interaction::None => {
+                *color = button.idle_color.into();
+            }
+        }
+    }
+}
+
 pub fn spawn_text(
     commands: &mut Commands,
     asset_server: &Res<AssetServer>,
     game_state: ResMut<GameState>,
 ) -> Entity {
-    let mut winner = " DRAW";
-    if let Some(color) = game_state.winner {
-        match color {
-            PieceColor::White => winner = " WHITE WINS!",
-            PieceColor::Black => winner = " BLACK WINS!",
-        }
-    }
-
-    let text = format!("{}{}", "GAME OVER:", winner);
+    let text = match game_state.result {
+        Some(result) => format!("GAME OVER: {} ({})", result.outcome, result.reason),
+        None => "GAME OVER".to_string(),
+    };
 
     commands
         .spawn(TextBundle {
@@ -107,7 +177,11 @@ pub fn spawn_text(
         .id()
 }
 
-pub fn spawn_button(commands: &mut Commands, asset_server: &Res<AssetServer>) -> Entity {
+pub fn spawn_button(
+    commands: &mut Commands,
+    asset_server: &Res<AssetServer>,
+    text: &str,
+) -> Entity {
     commands
         .spawn(ButtonBundle {
             style: Style {
@@ -128,7 +202,7 @@ pub fn spawn_button(commands: &mut Commands, asset_server: &Res<AssetServer>) ->
                     ..default()
                 },
                 text: Text::from_section(
-                    "PLAY AGAIN",
+                    text,
                     TextStyle {
                         font: asset_server.load("fonts/Aboreto-Regular.ttf"),
                         font_size: 25.0,
@@ -147,8 +221,12 @@ fn spawn_game_over(
     game_state: ResMut<GameState>,
     audio: Res<bevy_kira_audio::prelude::Audio>,
 ) {
-    let play_again_button = spawn_button(&mut commands, &asset_server);
+    let play_again_button = spawn_button(&mut commands, &asset_server, "PLAY AGAIN");
     commands.entity(play_again_button).insert(PlayAgainButton);
+    let save_pgn_button = spawn_button(&mut commands, &asset_server, SAVE_PGN_TEXT);
+    commands.entity(save_pgn_button).insert(SavePgnButton {
+        idle_color: TRANSPARENT_BURGUNDY,
+    });
     let game_over_text = spawn_text(&mut commands, &asset_server, game_state);
     commands.entity(game_over_text).insert(GameOverText);
 
@@ -165,7 +243,8 @@ fn spawn_game_over(
         })
         .insert(GameOverRoot)
         .add_child(game_over_text)
-        .add_child(play_again_button);
+        .add_child(play_again_button)
+        .add_child(save_pgn_button);
 
     audio.resume();
 }
@@ -176,7 +255,9 @@ impl Plugin for GameOverPlugin {
     fn build(&self, app: &mut App) {
         app.add_system_set(SystemSet::on_enter(GlobalState::GameOver).with_system(spawn_game_over))
             .add_system_set(
-                SystemSet::on_update(GlobalState::GameOver).with_system(play_again_button_clicked),
+                SystemSet::on_update(GlobalState::GameOver)
+                    .with_system(play_again_button_clicked)
+                    .with_system(handle_save_pgn_button),
             );
     }
 }
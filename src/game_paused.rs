@@ -1,9 +1,10 @@
 use bevy_kira_audio::AudioControl;
 
-use crate::game_over::despawn_board;
+use crate::game_over::{despawn_board, handle_save_pgn_button, SavePgnButton};
 use crate::main_menu::spawn_menu_button;
+use crate::ui::*;
+use crate::undo::{restore_position, undo_moves};
 use crate::*;
-use crate::{field::Field, ui::*};
 
 #[derive(Component)]
 struct BackButton;
@@ -11,6 +12,9 @@ struct BackButton;
 #[derive(Component)]
 struct ExitButton;
 
+#[derive(Component)]
+struct UndoButton;
+
 #[derive(Component)]
 struct PauseMenuRoot;
 
@@ -20,8 +24,8 @@ fn handle_exit_button(
     mut interactions: Query<(&Interaction, &mut BackgroundColor), With<ExitButton>>,
     pause_root: Query<Entity, With<PauseMenuRoot>>,
     mut global_state: ResMut<State<GlobalState>>,
-    piece_query: Query<Entity, With<Piece>>,
-    field_query: Query<Entity, With<Field>>,
+    piece_query: Query<Entity, With<PieceSprite>>,
+    field_query: Query<Entity, With<FieldSprite>>,
     color_text_query: Query<Entity, With<ColorText>>,
     fps_text_query: Query<Entity, With<FpsText>>,
     mut whose_turn: ResMut<State<WhoseTurn>>,
@@ -83,9 +87,55 @@ fn handle_back_to_game_button(
     }
 }
 
+// takes back the last move (and the bot reply in games against the bot) and goes back to the game
+#[allow(clippy::too_many_arguments)]
+fn handle_undo_button(
+    mut commands: Commands,
+    mut interactions: Query<(&Interaction, &mut BackgroundColor), With<UndoButton>>,
+    pause_root: Query<Entity, With<PauseMenuRoot>>,
+    mut global_state: ResMut<State<GlobalState>>,
+    mut game_state: ResMut<GameState>,
+    game_textures: Res<GameTextures>,
+    piece_query: Query<Entity, With<PieceSprite>>,
+    mut field_query: Query<(&mut Sprite, &FieldSprite)>,
+    audio: Res<bevy_kira_audio::prelude::Audio>,
+) {
+    for (interaction, mut color) in &mut interactions {
+        match *interaction {
+            Interaction::Clicked => {
+                if undo_moves(&mut game_state) {
+                    restore_position(
+                        &mut commands,
+                        &game_textures,
+                        &mut game_state,
+                        &piece_query,
+                        &mut field_query,
+                    );
+                }
+                let root_entity = pause_root.single();
+                commands.entity(root_entity).despawn_recursive();
+                audio.pause();
+                global_state.pop().expect("Error in setting state");
+            }
+            Interaction::Hovered => {
+                *color = BURGUNDY_LIGHT.into();
+            }
+            Interaction::None => {
+                *color = BURGUNDY_DARK.into();
+            }
+        }
+    }
+}
+
 fn spawn_game_paused(mut commands: Commands, asset_server: Res<AssetServer>) {
     let back_to_game_button = spawn_menu_button(&mut commands, &asset_server, KEEP_PLAYING);
     commands.entity(back_to_game_button).insert(BackButton);
+    let undo_button = spawn_menu_button(&mut commands, &asset_server, UNDO_TEXT);
+    commands.entity(undo_button).insert(UndoButton);
+    let save_pgn_button = spawn_menu_button(&mut commands, &asset_server, SAVE_PGN_TEXT);
+    commands.entity(save_pgn_button).insert(SavePgnButton {
+        idle_color: BURGUNDY_DARK,
+    });
     let exit_button = spawn_menu_button(&mut commands, &asset_server, EXIT_TO_MENU_TEXT);
     commands.entity(exit_button).insert(ExitButton);
 
@@ -120,6 +170,8 @@ fn spawn_game_paused(mut commands: Commands, asset_server: Res<AssetServer>) {
             });
         })
         .add_child(back_to_game_button)
+        .add_child(undo_button)
+        .add_child(save_pgn_button)
         .add_child(exit_button);
 }
 
@@ -131,6 +183,8 @@ impl Plugin for GamePausedPlugin {
         app.add_system_set(
             SystemSet::on_update(GlobalState::Paused)
                 .with_system(handle_back_to_game_button)
+                .with_system(handle_undo_button)
+                .with_system(handle_save_pgn_button)
                 .with_system(handle_exit_button),
         );
     }
@@ -1,25 +1,44 @@
 use bevy::prelude::*;
 use board::*;
+use bot::{BotEngine, BotError, ColorChoice};
 use chess_pieces::*;
+use clock::ChessClock;
 use coordinates::Coordinates;
+use engine_match::EngineMatch;
+use game_result::GameResult;
+use history::MoveHistory;
+use pgn::MoveLog;
+use promotion::PendingPromotion;
 use std::collections::HashMap;
 use ui::GameTextures;
 
+pub use chess_core::{
+    chess_move, chess_pieces, clock, coordinates, engine, fen, field, game_result, history, moves,
+    pgn, position, san, uci, BOARD_SIZE,
+};
+
+pub mod animation;
 pub mod audio;
 pub mod board;
 pub mod bot;
-pub mod chess_pieces;
-pub mod coordinates;
-pub mod field;
+pub mod bot_config;
+pub mod bot_error;
+pub mod bot_settings;
+pub mod engine_match;
 pub mod game_over;
 pub mod game_paused;
 pub mod main_menu;
-pub mod moves;
+pub mod move_history;
+pub mod promotion;
+pub mod replay;
+pub mod settings;
+pub mod time_control;
 pub mod ui;
+pub mod undo;
 pub mod user_input;
 
+// in world units, which the camera scales to fit the board into the window
 pub const FIELD_SIZE: f32 = 100.0;
-pub const BOARD_SIZE: usize = 8;
 pub const PLAY_AGAIN_BUTTON_WIDTH: f32 = 150.0;
 pub const PLAY_AGAIN_BUTTON_HEIGHT: f32 = 50.0;
 
@@ -40,9 +59,23 @@ pub const TRANSPARENT_BURGUNDY: Color = Color::rgba(118.0 / 255.0, 9.0 / 255.0,
 
 pub const FRIEND_TEXT: &str = "Play with your friend";
 pub const BOT_TEXT: &str = "Play with bot";
+pub const ENGINE_MATCH_TEXT: &str = "Engine vs engine";
 pub const EXIT_TO_MENU_TEXT: &str = "Exit to main menu";
 pub const QUIT_TEXT: &str = "Quit game";
+pub const REPLAY_TEXT: &str = "Replay loaded games";
 pub const KEEP_PLAYING: &str = "Back to game";
+pub const SAVE_PGN_TEXT: &str = "Save game (PGN)";
+pub const UNDO_TEXT: &str = "Undo move";
+pub const NATIVE_ENGINE_TEXT: &str = "Engine: built-in";
+pub const START_BOT_GAME_TEXT: &str = "Start game";
+pub const BACK_TO_MENU_TEXT: &str = "Back to menu";
+pub const RETRY_TEXT: &str = "Retry";
+pub const USE_NATIVE_ENGINE_TEXT: &str = "Use built-in engine";
+pub const START_MATCH_TEXT: &str = "Start match";
+pub const CURRENT_POSITION_TEXT: &str = "Back to current position";
+pub const FLIP_BOARD_TEXT: &str = "Flip board (F)";
+pub const SETTINGS_TEXT: &str = "Settings";
+pub const BACK_TO_SETTINGS_TEXT: &str = "Back to settings";
 
 pub const WHITE_PAWN_SPRITE: &str = "128px/w_pawn_png_shadow_128px.png";
 pub const BORDERED_WHITE_PAWN_SPRITE: &str = "128px/w_pawn_bordered.png";
@@ -75,11 +108,29 @@ pub const BACKGROUNG: &str = "background.png";
 pub struct GameState {
     pub board: Board,
     pub white: bool,
+    pub piece_entities: PieceEntities,
     pub selected_entity: Option<Entity>,
-    pub winner: Option<PieceColor>,
+    pub result: Option<GameResult>,
     pub bot_turn: bool,
     pub vs_bot: bool,
-    pub castling: bool,
+    pub bot_engine: BotEngine,
+    pub color_choice: ColorChoice,
+    // the side the player takes in games against the bot
+    pub player_color: PieceColor,
+    // the board is drawn with black at the bottom
+    pub flipped: bool,
+    // set when both sides are played by engines
+    pub engine_match: Option<EngineMatch>,
+    // set when the engine couldn't make a move, until the player decides what to do
+    pub bot_error: Option<BotError>,
+    // set in timed games
+    pub clock: Option<ChessClock>,
+    // the number of moves played in the position shown instead of the current one,
+    // while an earlier move is looked at
+    pub viewed_move: Option<usize>,
+    pub pending_promotion: Option<PendingPromotion>,
+    pub move_log: MoveLog,
+    pub history: MoveHistory,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
@@ -88,6 +139,11 @@ pub enum GlobalState {
     InGame,
     GameOver,
     Paused,
+    Replay,
+    BotSettings,
+    MatchSettings,
+    TimeControlSettings,
+    Settings,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
@@ -96,7 +152,7 @@ pub enum WhoseTurn {
     Player,
 }
 
-pub fn get_image(piece: &Piece, game_textures: &Res<GameTextures>) -> Handle<Image> {
+pub fn get_image(piece: &PieceSprite, game_textures: &Res<GameTextures>) -> Handle<Image> {
     let maybe_image = if piece.piece_color == PieceColor::White {
         game_textures.white_images_map.get(&piece.piece_type)
     } else {
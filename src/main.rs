@@ -1,12 +1,25 @@
 use bevy::prelude::*;
-use bevy::window::WindowMode::BorderlessFullscreen;
+use chess_masters::animation::AnimationPlugin;
 use chess_masters::audio::ChessAudioPlugin;
-use chess_masters::board::{Board, BoardPlugin};
-use chess_masters::bot::BotPlugin;
+use chess_masters::board::{Board, BoardPlugin, PieceEntities, StartingPosition};
+use chess_masters::bot::{BotEngine, BotPlugin, ColorChoice};
+use chess_masters::bot_config::{parse_bot_config, BotConfig, BOT_CONFIG_FILE};
+use chess_masters::bot_error::BotErrorPlugin;
+use chess_masters::bot_settings::BotSettingsPlugin;
+use chess_masters::chess_pieces::PieceColor;
+use chess_masters::engine_match::EngineMatchPlugin;
 use chess_masters::game_over::GameOverPlugin;
 use chess_masters::game_paused::GamePausedPlugin;
+use chess_masters::history::MoveHistory;
 use chess_masters::main_menu::MainMenuPlugin;
+use chess_masters::move_history::MoveHistoryPlugin;
+use chess_masters::pgn::{parse_pgn, MoveLog};
+use chess_masters::promotion::PromotionPlugin;
+use chess_masters::replay::{ReplayGames, ReplayPlugin};
+use chess_masters::settings::{SettingsPlugin, WindowSettings};
+use chess_masters::time_control::TimeControlPlugin;
 use chess_masters::ui::{GameTextures, UserInterfacePlugin};
+use chess_masters::undo::UndoPlugin;
 use chess_masters::user_input::UserInputPlugin;
 use chess_masters::*;
 
@@ -18,25 +31,113 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
     commands.insert_resource(GameState {
         white: true,
         board: Board::empty(),
+        piece_entities: PieceEntities::default(),
         selected_entity: None,
-        winner: None,
+        result: None,
         bot_turn: false,
         vs_bot: true,
-        castling: false,
+        bot_engine: BotEngine::default(),
+        color_choice: ColorChoice::default(),
+        player_color: PieceColor::White,
+        flipped: false,
+        engine_match: None,
+        bot_error: None,
+        clock: None,
+        viewed_move: None,
+        pending_promotion: None,
+        move_log: MoveLog::default(),
+        history: MoveHistory::default(),
     });
 }
 
 fn create_window_descriptor() -> WindowDescriptor {
     WindowDescriptor {
         title: "Chess!".to_string(),
-        mode: BorderlessFullscreen,
+        mode: WindowSettings::default().mode(),
         ..default()
     }
 }
 
+// games start from the position given with `--fen "<FEN>"`, if any
+fn starting_position_from_args() -> StartingPosition {
+    let args: Vec<String> = std::env::args().collect();
+    let Some(index) = args.iter().position(|arg| arg == "--fen") else {
+        return StartingPosition::default();
+    };
+    let Some(fen) = args.get(index + 1) else {
+        eprintln!("Missing position after --fen");
+        std::process::exit(1);
+    };
+    match Board::from_fen(fen) {
+        Ok(board) => StartingPosition(board),
+        Err(error) => {
+            eprintln!("Invalid FEN \"{}\": {}", fen, error);
+            std::process::exit(1);
+        }
+    }
+}
+
+// games to replay can be loaded with `--pgn <file>`
+fn replay_games_from_args() -> Option<ReplayGames> {
+    let args: Vec<String> = std::env::args().collect();
+    let index = args.iter().position(|arg| arg == "--pgn")?;
+    let Some(path) = args.get(index + 1) else {
+        eprintln!("Missing file after --pgn");
+        std::process::exit(1);
+    };
+    let text = std::fs::read_to_string(path).unwrap_or_else(|error| {
+        eprintln!("Can't read {}: {}", path, error);
+        std::process::exit(1);
+    });
+    match parse_pgn(&text) {
+        Ok(games) if !games.is_empty() => Some(ReplayGames { games }),
+        Ok(_) => {
+            eprintln!("No games found in {}", path);
+            std::process::exit(1);
+        }
+        Err(error) => {
+            eprintln!("Invalid PGN in {}: {}", path, error);
+            std::process::exit(1);
+        }
+    }
+}
+
+// the bot settings are read from `--bot-config <file>`, or from bot.cfg if there is one
+fn bot_config_from_args() -> BotConfig {
+    let args: Vec<String> = std::env::args().collect();
+    let path = match args.iter().position(|arg| arg == "--bot-config") {
+        Some(index) => match args.get(index + 1) {
+            Some(path) => path.clone(),
+            None => {
+                eprintln!("Missing file after --bot-config");
+                std::process::exit(1);
+            }
+        },
+        None if std::path::Path::new(BOT_CONFIG_FILE).exists() => BOT_CONFIG_FILE.to_string(),
+        None => return BotConfig::default(),
+    };
+    let text = std::fs::read_to_string(&path).unwrap_or_else(|error| {
+        eprintln!("Can't read {}: {}", path, error);
+        std::process::exit(1);
+    });
+    parse_bot_config(&text).unwrap_or_else(|error| {
+        eprintln!("Invalid bot config in {}: {}", path, error);
+        std::process::exit(1);
+    })
+}
+
 fn main() {
-    App::new()
-        .insert_resource(ClearColor(SADDLE_BROWN))
+    let starting_position = starting_position_from_args();
+    let replay_games = replay_games_from_args();
+    let bot_config = bot_config_from_args();
+
+    let mut app = App::new();
+    if let Some(replay_games) = replay_games {
+        app.insert_resource(replay_games);
+    }
+    app.insert_resource(ClearColor(SADDLE_BROWN))
+        .insert_resource(starting_position)
+        .insert_resource(bot_config)
         .add_plugins(DefaultPlugins.set(WindowPlugin {
             window: create_window_descriptor(),
             ..default()
@@ -51,6 +152,16 @@ fn main() {
         .add_plugin(GamePausedPlugin)
         .add_plugin(UserInterfacePlugin)
         .add_plugin(BotPlugin)
+        .add_plugin(PromotionPlugin)
+        .add_plugin(ReplayPlugin)
+        .add_plugin(UndoPlugin)
+        .add_plugin(BotSettingsPlugin)
+        .add_plugin(BotErrorPlugin)
+        .add_plugin(EngineMatchPlugin)
+        .add_plugin(TimeControlPlugin)
+        .add_plugin(MoveHistoryPlugin)
+        .add_plugin(AnimationPlugin)
+        .add_plugin(SettingsPlugin)
         .add_startup_system(setup)
         .run();
 }
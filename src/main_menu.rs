@@ -1,3 +1,5 @@
+use crate::board::BoardScale;
+use crate::replay::ReplayGames;
 use crate::*;
 use bevy::app::AppExit;
 use bevy_kira_audio::prelude::*;
@@ -11,9 +13,18 @@ struct StartButton;
 #[derive(Component)]
 struct BotButton;
 
+#[derive(Component)]
+struct EngineMatchButton;
+
+#[derive(Component)]
+struct SettingsButton;
+
 #[derive(Component)]
 struct QuitButton;
 
+#[derive(Component)]
+struct ReplayButton;
+
 #[derive(Component)]
 struct MenuBackground;
 
@@ -42,9 +53,11 @@ fn handle_start_button(
         match *interaction {
             Interaction::Clicked => {
                 game_state.vs_bot = false;
+                game_state.engine_match = None;
+                game_state.flipped = false;
                 game_state.white = true;
                 game_state.bot_turn = false;
-                game_state.winner = None;
+                game_state.result = None;
                 despawn_menu(&mut commands, &menu_root, &menu_background);
                 global_state
                     .set(GlobalState::InGame)
@@ -61,27 +74,97 @@ fn handle_start_button(
     }
 }
 
+// the game against the bot starts from the bot settings
 fn handle_bot_button(
     mut commands: Commands,
     mut interactions: Query<(&Interaction, &mut BackgroundColor), With<BotButton>>,
     menu_root: Query<Entity, With<MainMenuRoot>>,
     menu_background: Query<Entity, With<MenuBackground>>,
     mut global_state: ResMut<State<GlobalState>>,
-    audio: Res<bevy_kira_audio::prelude::Audio>,
-    mut game_state: ResMut<GameState>,
 ) {
     for (interaction, mut color) in &mut interactions {
         match *interaction {
             Interaction::Clicked => {
-                game_state.vs_bot = true;
-                game_state.white = true;
-                game_state.bot_turn = false;
-                game_state.winner = None;
                 despawn_menu(&mut commands, &menu_root, &menu_background);
                 global_state
-                    .set(GlobalState::InGame)
+                    .set(GlobalState::BotSettings)
+                    .expect("Error in setting state");
+            }
+            Interaction::Hovered => {
+                *color = LIGHT_GRAY.into();
+            }
+            Interaction::None => {
+                *color = DARK_GRAY.into();
+            }
+        }
+    }
+}
+
+fn handle_engine_match_button(
+    mut commands: Commands,
+    mut interactions: Query<(&Interaction, &mut BackgroundColor), With<EngineMatchButton>>,
+    menu_root: Query<Entity, With<MainMenuRoot>>,
+    menu_background: Query<Entity, With<MenuBackground>>,
+    mut global_state: ResMut<State<GlobalState>>,
+) {
+    for (interaction, mut color) in &mut interactions {
+        match *interaction {
+            Interaction::Clicked => {
+                despawn_menu(&mut commands, &menu_root, &menu_background);
+                global_state
+                    .set(GlobalState::MatchSettings)
+                    .expect("Error in setting state");
+            }
+            Interaction::Hovered => {
+                *color = LIGHT_GRAY.into();
+            }
+            Interaction::None => {
+                *color = DARK_GRAY.into();
+            }
+        }
+    }
+}
+
+// the time control and the animations are chosen in the settings
+fn handle_settings_button(
+    mut commands: Commands,
+    mut interactions: Query<(&Interaction, &mut BackgroundColor), With<SettingsButton>>,
+    menu_root: Query<Entity, With<MainMenuRoot>>,
+    menu_background: Query<Entity, With<MenuBackground>>,
+    mut global_state: ResMut<State<GlobalState>>,
+) {
+    for (interaction, mut color) in &mut interactions {
+        match *interaction {
+            Interaction::Clicked => {
+                despawn_menu(&mut commands, &menu_root, &menu_background);
+                global_state
+                    .set(GlobalState::Settings)
+                    .expect("Error in setting state");
+            }
+            Interaction::Hovered => {
+                *color = LIGHT_GRAY.into();
+            }
+            Interaction::None => {
+                *color = DARK_GRAY.into();
+            }
+        }
+    }
+}
+
+fn handle_replay_button(
+    mut commands: Commands,
+    mut interactions: Query<(&Interaction, &mut BackgroundColor), With<ReplayButton>>,
+    menu_root: Query<Entity, With<MainMenuRoot>>,
+    menu_background: Query<Entity, With<MenuBackground>>,
+    mut global_state: ResMut<State<GlobalState>>,
+) {
+    for (interaction, mut color) in &mut interactions {
+        match *interaction {
+            Interaction::Clicked => {
+                despawn_menu(&mut commands, &menu_root, &menu_background);
+                global_state
+                    .set(GlobalState::Replay)
                     .expect("Error in setting state");
-                audio.pause().fade_out(AudioTween::default());
             }
             Interaction::Hovered => {
                 *color = LIGHT_GRAY.into();
@@ -154,13 +237,37 @@ pub fn spawn_menu_button(
         .id()
 }
 
-fn spawn_main_menu(mut commands: Commands, asset_server: Res<AssetServer>, window: Res<Windows>) {
+fn spawn_main_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    window: Res<Windows>,
+    board_scale: Res<BoardScale>,
+    replay_games: Option<Res<ReplayGames>>,
+) {
     let start_game_button = spawn_menu_button(&mut commands, &asset_server, FRIEND_TEXT);
     commands.entity(start_game_button).insert(StartButton);
     let bot_button = spawn_menu_button(&mut commands, &asset_server, BOT_TEXT);
     commands.entity(bot_button).insert(BotButton);
+    let engine_match_button = spawn_menu_button(&mut commands, &asset_server, ENGINE_MATCH_TEXT);
+    commands
+        .entity(engine_match_button)
+        .insert(EngineMatchButton);
+    let settings_button = spawn_menu_button(&mut commands, &asset_server, SETTINGS_TEXT);
+    commands.entity(settings_button).insert(SettingsButton);
+    let mut buttons = vec![
+        start_game_button,
+        bot_button,
+        engine_match_button,
+        settings_button,
+    ];
+    if replay_games.is_some() {
+        let replay_button = spawn_menu_button(&mut commands, &asset_server, REPLAY_TEXT);
+        commands.entity(replay_button).insert(ReplayButton);
+        buttons.push(replay_button);
+    }
     let quit_button = spawn_menu_button(&mut commands, &asset_server, QUIT_TEXT);
     commands.entity(quit_button).insert(QuitButton);
+    buttons.push(quit_button);
 
     let window = window.get_primary().unwrap();
     let mut scale_x = window.width() / 2560.0;
@@ -176,7 +283,8 @@ fn spawn_main_menu(mut commands: Commands, asset_server: Res<AssetServer>, windo
             texture: background_image,
             transform: Transform {
                 translation: Vec3::new(0.0, 0.0, 10.0),
-                scale: Vec3::new(scale_x, scale_y, 1.0),
+                // the camera is zoomed to fit the board
+                scale: Vec3::new(scale_x * board_scale.0, scale_y * board_scale.0, 1.0),
                 ..default()
             },
             ..default()
@@ -212,9 +320,7 @@ fn spawn_main_menu(mut commands: Commands, asset_server: Res<AssetServer>, windo
                 ..default()
             });
         })
-        .add_child(start_game_button)
-        .add_child(bot_button)
-        .add_child(quit_button);
+        .push_children(&buttons);
 }
 
 pub struct MainMenuPlugin;
@@ -226,7 +332,10 @@ impl Plugin for MainMenuPlugin {
                 SystemSet::on_update(GlobalState::MainMenu)
                     .with_system(handle_start_button)
                     .with_system(handle_quit_button)
-                    .with_system(handle_bot_button),
+                    .with_system(handle_replay_button)
+                    .with_system(handle_bot_button)
+                    .with_system(handle_engine_match_button)
+                    .with_system(handle_settings_button),
             );
     }
 }
@@ -0,0 +1,595 @@
+use crate::board::piece_sprite_bundle;
+use crate::user_input::reset_fields_to_default;
+use crate::*;
+use bevy::input::mouse::{MouseScrollUnit, MouseWheel};
+use bevy::ui::FocusPolicy;
+
+const PANEL_WIDTH: f32 = 280.0;
+const MOVE_WIDTH: f32 = 90.0;
+const ROW_HEIGHT: f32 = 30.0;
+const CAPTURED_PIECE_SIZE: f32 = 24.0;
+// how far a line of the mouse wheel scrolls the list
+const SCROLL_LINE: f32 = ROW_HEIGHT;
+
+// the moves of the game next to the board, with the pieces each side captured
+#[derive(Component)]
+struct MoveHistoryPanel;
+
+// the pieces captured by the color
+#[derive(Component)]
+struct CapturedPieces(PieceColor);
+
+// the list is scrolled up from its bottom, where the last moves are
+#[derive(Component, Default)]
+struct MoveList {
+    offset: f32,
+}
+
+// holds the button back to the current position while an earlier one is shown
+#[derive(Component)]
+struct PanelFooter;
+
+// the number of moves played after the move on the button
+#[derive(Component)]
+struct MoveButton(usize);
+
+#[derive(Component)]
+struct CurrentPositionButton;
+
+#[derive(Component)]
+struct FlipBoardButton;
+
+// the pieces of the position shown instead of the current one, which is hidden meanwhile;
+// they are spawned again for another move or when the board is turned around
+#[derive(Component)]
+struct ViewedPiece {
+    moves: usize,
+    flipped: bool,
+}
+
+// the board after the given number of moves, found by taking back the later ones
+fn position_after(game_state: &GameState, moves: usize) -> Board {
+    let played = game_state.history.played();
+    let mut board = game_state.board.clone();
+    for record in played[moves.min(played.len())..].iter().rev() {
+        board.undo_move(record);
+    }
+    board
+}
+
+fn material(board: &Board, color: PieceColor) -> i32 {
+    board
+        .fields
+        .iter()
+        .flatten()
+        .filter_map(|field| field.piece.as_ref())
+        .filter(|piece| piece.piece_color == color)
+        .filter(|piece| !matches!(piece.piece_type, PieceType::King { .. }))
+        .map(|piece| piece.piece_type.get_value())
+        .sum()
+}
+
+// the moves of white and black with the same number; the first row of a game started with
+// black to move has an empty place for white's move
+struct MoveRow {
+    number: String,
+    // the SAN of each move with the number of moves played after it
+    moves: Vec<Option<(usize, String)>>,
+}
+
+fn move_rows(movetext: &[String]) -> Vec<MoveRow> {
+    let mut rows: Vec<MoveRow> = Vec::new();
+    let mut played = 0;
+    for token in movetext {
+        if token.ends_with('.') {
+            let moves = if token.ends_with("...") {
+                vec![None]
+            } else {
+                Vec::new()
+            };
+            rows.push(MoveRow {
+                number: token.clone(),
+                moves,
+            });
+        } else if let Some(row) = rows.last_mut() {
+            played += 1;
+            row.moves.push(Some((played, token.clone())));
+        }
+    }
+    rows
+}
+
+fn move_button_color(moves: usize, viewed_move: Option<usize>) -> Color {
+    if viewed_move == Some(moves) {
+        BURGUNDY_DARK
+    } else {
+        Color::NONE
+    }
+}
+
+fn text_style(asset_server: &AssetServer) -> TextStyle {
+    TextStyle {
+        font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+        font_size: 22.0,
+        color: Color::WHITE,
+    }
+}
+
+fn spawn_move_history(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let captured_row = |color| {
+        (
+            NodeBundle {
+                style: Style {
+                    size: Size::new(Val::Percent(100.0), Val::Px(ROW_HEIGHT)),
+                    align_items: AlignItems::Center,
+                    flex_shrink: 0.0,
+                    ..default()
+                },
+                focus_policy: FocusPolicy::Pass,
+                ..default()
+            },
+            CapturedPieces(color),
+        )
+    };
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    position: UiRect {
+                        top: Val::Px(100.0),
+                        left: Val::Px(20.0),
+                        ..default()
+                    },
+                    size: Size::new(Val::Px(PANEL_WIDTH), Val::Percent(70.0)),
+                    flex_direction: FlexDirection::Column,
+                    padding: UiRect::all(Val::Px(10.0)),
+                    ..default()
+                },
+                background_color: TRANSPARENT_GRAY.into(),
+                ..default()
+            },
+            // hovered while the cursor is over the panel, which is when the list scrolls
+            Interaction::default(),
+            MoveHistoryPanel,
+        ))
+        .with_children(|panel| {
+            panel.spawn(captured_row(PieceColor::White));
+            panel.spawn(captured_row(PieceColor::Black));
+            // the part of the list that fits into the panel
+            panel
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Column,
+                        justify_content: JustifyContent::FlexEnd,
+                        flex_grow: 1.0,
+                        flex_basis: Val::Px(0.0),
+                        min_size: Size::new(Val::Auto, Val::Px(0.0)),
+                        overflow: Overflow::Hidden,
+                        ..default()
+                    },
+                    focus_policy: FocusPolicy::Pass,
+                    ..default()
+                })
+                .with_children(|view| {
+                    view.spawn((
+                        NodeBundle {
+                            style: Style {
+                                flex_direction: FlexDirection::Column,
+                                flex_shrink: 0.0,
+                                ..default()
+                            },
+                            focus_policy: FocusPolicy::Pass,
+                            ..default()
+                        },
+                        MoveList::default(),
+                    ));
+                });
+            panel.spawn((
+                NodeBundle {
+                    style: Style {
+                        flex_shrink: 0.0,
+                        ..default()
+                    },
+                    focus_policy: FocusPolicy::Pass,
+                    ..default()
+                },
+                PanelFooter,
+            ));
+            panel
+                .spawn((panel_button(BURGUNDY_DARK), FlipBoardButton))
+                .with_children(|button| {
+                    button.spawn(TextBundle::from_section(
+                        FLIP_BOARD_TEXT,
+                        text_style(&asset_server),
+                    ));
+                });
+        });
+}
+
+fn panel_button(color: Color) -> ButtonBundle {
+    ButtonBundle {
+        style: Style {
+            size: Size::new(Val::Px(PANEL_WIDTH - 20.0), Val::Px(40.0)),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            margin: UiRect::top(Val::Px(10.0)),
+            flex_shrink: 0.0,
+            ..default()
+        },
+        background_color: color.into(),
+        ..default()
+    }
+}
+
+fn spawn_captured_pieces(
+    row: &mut ChildBuilder,
+    asset_server: &AssetServer,
+    game_textures: &Res<GameTextures>,
+    color: PieceColor,
+    captured: &[Piece],
+    advantage: i32,
+) {
+    let name = match color {
+        PieceColor::White => "White",
+        PieceColor::Black => "Black",
+    };
+    row.spawn(TextBundle {
+        style: Style {
+            size: Size::new(Val::Px(65.0), Val::Auto),
+            ..default()
+        },
+        text: Text::from_section(name, text_style(asset_server)),
+        ..default()
+    });
+    for piece in captured {
+        row.spawn(ImageBundle {
+            style: Style {
+                size: Size::new(Val::Px(CAPTURED_PIECE_SIZE), Val::Px(CAPTURED_PIECE_SIZE)),
+                ..default()
+            },
+            image: UiImage(get_image(&PieceSprite::new(piece), game_textures)),
+            focus_policy: FocusPolicy::Pass,
+            ..default()
+        });
+    }
+    if advantage > 0 {
+        row.spawn(TextBundle::from_section(
+            format!(" +{}", advantage),
+            text_style(asset_server),
+        ));
+    }
+}
+
+fn spawn_move_row(
+    list: &mut ChildBuilder,
+    asset_server: &AssetServer,
+    move_row: &MoveRow,
+    viewed_move: Option<usize>,
+) {
+    list.spawn(NodeBundle {
+        style: Style {
+            size: Size::new(Val::Percent(100.0), Val::Px(ROW_HEIGHT)),
+            align_items: AlignItems::Center,
+            flex_shrink: 0.0,
+            ..default()
+        },
+        focus_policy: FocusPolicy::Pass,
+        ..default()
+    })
+    .with_children(|row| {
+        row.spawn(TextBundle {
+            style: Style {
+                size: Size::new(Val::Px(60.0), Val::Auto),
+                ..default()
+            },
+            text: Text::from_section(&move_row.number, text_style(asset_server)),
+            ..default()
+        });
+        for logged_move in &move_row.moves {
+            let style = Style {
+                size: Size::new(Val::Px(MOVE_WIDTH), Val::Px(ROW_HEIGHT)),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            };
+            let Some((played, san)) = logged_move else {
+                row.spawn(NodeBundle {
+                    style,
+                    focus_policy: FocusPolicy::Pass,
+                    ..default()
+                });
+                continue;
+            };
+            row.spawn((
+                ButtonBundle {
+                    style,
+                    background_color: move_button_color(*played, viewed_move).into(),
+                    focus_policy: FocusPolicy::Pass,
+                    ..default()
+                },
+                MoveButton(*played),
+            ))
+            .with_children(|button| {
+                button.spawn(TextBundle::from_section(
+                    san.clone(),
+                    text_style(asset_server),
+                ));
+            });
+        }
+    });
+}
+
+// the panel is filled again whenever a move is made or taken back, or another one is viewed
+#[allow(clippy::too_many_arguments)]
+fn update_move_history(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    game_textures: Res<GameTextures>,
+    game_state: Res<GameState>,
+    captured_rows: Query<(Entity, &CapturedPieces)>,
+    move_list: Query<Entity, With<MoveList>>,
+    footer: Query<Entity, With<PanelFooter>>,
+    spawned: Query<(), Added<MoveHistoryPanel>>,
+    mut shown: Local<(usize, Option<usize>)>,
+) {
+    let current = (game_state.move_log.moves.len(), game_state.viewed_move);
+    if *shown == current && spawned.is_empty() {
+        return;
+    }
+    *shown = current;
+
+    let played = game_state.history.played();
+    let moves = game_state
+        .viewed_move
+        .unwrap_or(played.len())
+        .min(played.len());
+    let board = position_after(&game_state, moves);
+    let material_difference =
+        material(&board, PieceColor::White) - material(&board, PieceColor::Black);
+    for (entity, captured_by) in &captured_rows {
+        let mut captured: Vec<Piece> = played[..moves]
+            .iter()
+            .filter_map(|record| record.captured())
+            .filter(|piece| piece.piece_color != captured_by.0)
+            .cloned()
+            .collect();
+        captured.sort_by_key(|piece| -piece.piece_type.get_value());
+        let advantage = match captured_by.0 {
+            PieceColor::White => material_difference,
+            PieceColor::Black => -material_difference,
+        };
+        commands.entity(entity).despawn_descendants();
+        commands.entity(entity).with_children(|row| {
+            spawn_captured_pieces(
+                row,
+                &asset_server,
+                &game_textures,
+                captured_by.0,
+                &captured,
+                advantage,
+            );
+        });
+    }
+
+    let rows = move_rows(&game_state.move_log.movetext());
+    for entity in &move_list {
+        commands.entity(entity).despawn_descendants();
+        commands.entity(entity).with_children(|list| {
+            for move_row in &rows {
+                spawn_move_row(list, &asset_server, move_row, game_state.viewed_move);
+            }
+        });
+    }
+
+    for entity in &footer {
+        commands.entity(entity).despawn_descendants();
+        if game_state.viewed_move.is_none() {
+            continue;
+        }
+        commands.entity(entity).with_children(|footer| {
+            footer
+                .spawn((panel_button(BURGUNDY_DARK), CurrentPositionButton))
+                .with_children(|button| {
+                    button.spawn(TextBundle::from_section(
+                        CURRENT_POSITION_TEXT,
+                        text_style(&asset_server),
+                    ));
+                });
+        });
+    }
+}
+
+fn handle_move_buttons(
+    mut interactions: Query<
+        (&Interaction, &MoveButton, &mut BackgroundColor),
+        Changed<Interaction>,
+    >,
+    mut field_query: Query<(&mut Sprite, &FieldSprite)>,
+    mut game_state: ResMut<GameState>,
+) {
+    for (interaction, button, mut color) in &mut interactions {
+        match *interaction {
+            Interaction::Clicked => {
+                // the last move shows the current position, which can be played on
+                game_state.viewed_move = if button.0 == game_state.history.played().len() {
+                    None
+                } else {
+                    Some(button.0)
+                };
+                // the moves of a selected piece don't belong to the position shown
+                reset_fields_to_default(&mut field_query);
+            }
+            Interaction::Hovered => {
+                *color = LIGHT_GRAY.into();
+            }
+            Interaction::None => {
+                *color = move_button_color(button.0, game_state.viewed_move).into();
+            }
+        }
+    }
+}
+
+fn handle_current_position_button(
+    mut interactions: Query<(&Interaction, &mut BackgroundColor), With<CurrentPositionButton>>,
+    mut game_state: ResMut<GameState>,
+) {
+    for (interaction, mut color) in &mut interactions {
+        match *interaction {
+            Interaction::Clicked => {
+                game_state.viewed_move = None;
+            }
+            Interaction::Hovered => {
+                *color = BURGUNDY_LIGHT.into();
+            }
+            Interaction::None => {
+                *color = BURGUNDY_DARK.into();
+            }
+        }
+    }
+}
+
+fn handle_flip_board_button(
+    mut interactions: Query<(&Interaction, &mut BackgroundColor), With<FlipBoardButton>>,
+    mut game_state: ResMut<GameState>,
+) {
+    for (interaction, mut color) in &mut interactions {
+        match *interaction {
+            Interaction::Clicked => {
+                game_state.flipped = !game_state.flipped;
+            }
+            Interaction::Hovered => {
+                *color = BURGUNDY_LIGHT.into();
+            }
+            Interaction::None => {
+                *color = BURGUNDY_DARK.into();
+            }
+        }
+    }
+}
+
+fn scroll_move_list(
+    mut scroll_events: EventReader<MouseWheel>,
+    panel: Query<&Interaction, With<MoveHistoryPanel>>,
+    mut move_list: Query<(&mut MoveList, &mut Style, &Node, &Parent)>,
+    nodes: Query<&Node>,
+) {
+    let hovered = panel
+        .iter()
+        .any(|interaction| *interaction != Interaction::None);
+    for event in scroll_events.iter() {
+        if !hovered {
+            continue;
+        }
+        let scrolled = match event.unit {
+            MouseScrollUnit::Line => event.y * SCROLL_LINE,
+            MouseScrollUnit::Pixel => event.y,
+        };
+        for (mut list, mut style, node, parent) in &mut move_list {
+            let visible = nodes.get(parent.get()).map_or(0.0, |view| view.size().y);
+            let hidden = (node.size().y - visible).max(0.0);
+            list.offset = (list.offset + scrolled).clamp(0.0, hidden);
+            style.position.bottom = Val::Px(-list.offset);
+        }
+    }
+}
+
+// the pieces on the board are those of the viewed position while an earlier move is looked at;
+// the current ones are only hidden, so that moves made meanwhile still move them
+fn show_viewed_position(
+    mut commands: Commands,
+    game_textures: Res<GameTextures>,
+    game_state: Res<GameState>,
+    viewed_pieces: Query<(Entity, &ViewedPiece)>,
+    mut live_pieces: Query<&mut Visibility, With<PieceSprite>>,
+) {
+    let viewing = game_state.viewed_move.is_some();
+    for mut visibility in &mut live_pieces {
+        if visibility.is_visible == viewing {
+            visibility.is_visible = !viewing;
+        }
+    }
+
+    let shown = viewed_pieces
+        .iter()
+        .next()
+        .map(|(_, piece)| (piece.moves, piece.flipped));
+    let wanted = game_state
+        .viewed_move
+        .map(|moves| (moves, game_state.flipped));
+    if shown == wanted {
+        return;
+    }
+    for (entity, _) in &viewed_pieces {
+        commands.entity(entity).despawn_recursive();
+    }
+    let Some(moves) = game_state.viewed_move else {
+        return;
+    };
+    let board = position_after(&game_state, moves);
+    for field in board.fields.iter().flatten() {
+        if let Some(piece) = &field.piece {
+            let image = get_image(&PieceSprite::new(piece), &game_textures);
+            let translation = field_translation(field.coordinates, game_state.flipped);
+            commands.spawn((
+                piece_sprite_bundle(image, translation),
+                ViewedPiece {
+                    moves,
+                    flipped: game_state.flipped,
+                },
+            ));
+        }
+    }
+}
+
+// the game over screen shows how the game ended
+fn show_current_position(
+    mut commands: Commands,
+    mut game_state: ResMut<GameState>,
+    viewed_pieces: Query<Entity, With<ViewedPiece>>,
+    mut live_pieces: Query<&mut Visibility, With<PieceSprite>>,
+) {
+    game_state.viewed_move = None;
+    for entity in &viewed_pieces {
+        commands.entity(entity).despawn_recursive();
+    }
+    for mut visibility in &mut live_pieces {
+        visibility.is_visible = true;
+    }
+}
+
+fn despawn_move_history(
+    mut commands: Commands,
+    panel: Query<Entity, With<MoveHistoryPanel>>,
+    viewed_pieces: Query<Entity, With<ViewedPiece>>,
+) {
+    for entity in panel.iter().chain(viewed_pieces.iter()) {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+pub struct MoveHistoryPlugin;
+
+impl Plugin for MoveHistoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(
+            SystemSet::on_enter(GlobalState::InGame).with_system(spawn_move_history),
+        )
+        .add_system_set(
+            SystemSet::on_update(GlobalState::InGame)
+                .with_system(update_move_history)
+                .with_system(handle_move_buttons)
+                .with_system(handle_current_position_button)
+                .with_system(handle_flip_board_button)
+                .with_system(scroll_move_list)
+                .with_system(show_viewed_position),
+        )
+        .add_system_set(
+            SystemSet::on_enter(GlobalState::GameOver).with_system(show_current_position),
+        )
+        .add_system_set(
+            SystemSet::on_enter(GlobalState::MainMenu).with_system(despawn_move_history),
+        );
+    }
+}
@@ -0,0 +1,198 @@
+use crate::board::BoardScale;
+use crate::user_input::handle_piece_move;
+use crate::*;
+
+// a pawn move to the last rank waiting for the player to pick a piece
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PendingPromotion {
+    pub entity: Entity,
+    pub to: Coordinates,
+}
+
+#[derive(Component)]
+struct PromotionRoot;
+
+#[derive(Component)]
+struct PromotionButton(PieceType);
+
+// the buttons are as large as the fields of the board
+fn spawn_promotion_button(
+    commands: &mut Commands,
+    image: Handle<Image>,
+    piece_type: PieceType,
+    size: f32,
+) -> Entity {
+    commands
+        .spawn(ButtonBundle {
+            style: Style {
+                size: Size::new(Val::Px(size), Val::Px(size)),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                margin: UiRect::all(Val::Px(10.0)),
+                ..default()
+            },
+            background_color: TRANSPARENT_BURGUNDY.into(),
+            ..default()
+        })
+        .insert(PromotionButton(piece_type))
+        .with_children(|commands| {
+            commands.spawn(ImageBundle {
+                style: Style {
+                    size: Size::new(Val::Px(size * 0.8), Val::Px(size * 0.8)),
+                    ..default()
+                },
+                image: image.into(),
+                ..default()
+            });
+        })
+        .id()
+}
+
+fn spawn_promotion_picker(
+    mut commands: Commands,
+    game_state: Res<GameState>,
+    game_textures: Res<GameTextures>,
+    asset_server: Res<AssetServer>,
+    board_scale: Res<BoardScale>,
+    picker_root: Query<Entity, With<PromotionRoot>>,
+) {
+    if game_state.pending_promotion.is_none() || !picker_root.is_empty() {
+        return;
+    }
+
+    let color = if game_state.white {
+        PieceColor::White
+    } else {
+        PieceColor::Black
+    };
+    let buttons: Vec<Entity> = PROMOTION_PIECES
+        .iter()
+        .map(|piece_type| {
+            let piece = Piece::new(*piece_type, color, Coordinates::default());
+            let image = get_image(&PieceSprite::new(&piece), &game_textures);
+            spawn_promotion_button(
+                &mut commands,
+                image,
+                *piece_type,
+                board_scale.field_pixels(),
+            )
+        })
+        .collect();
+
+    let buttons_row = commands
+        .spawn(NodeBundle {
+            style: Style {
+                align_self: AlignSelf::Center,
+                flex_direction: FlexDirection::Row,
+                ..default()
+            },
+            ..default()
+        })
+        .push_children(&buttons)
+        .id();
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                justify_content: JustifyContent::Center,
+                flex_direction: FlexDirection::Column,
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+            background_color: TRANSPARENT_GRAY.into(),
+            ..default()
+        })
+        .insert(PromotionRoot)
+        .with_children(|commands| {
+            commands.spawn(TextBundle {
+                style: Style {
+                    align_self: AlignSelf::Center,
+                    margin: UiRect::all(Val::Percent(2.0)),
+                    ..default()
+                },
+                text: Text::from_section(
+                    "Promote to",
+                    TextStyle {
+                        font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+                        font_size: 40.0,
+                        color: Color::WHITE,
+                    },
+                ),
+                ..default()
+            });
+        })
+        .add_child(buttons_row);
+}
+
+#[allow(clippy::too_many_arguments)]
+fn handle_promotion_button(
+    mut commands: Commands,
+    mut interactions: Query<
+        (&Interaction, &PromotionButton, &mut BackgroundColor),
+        Changed<Interaction>,
+    >,
+    picker_root: Query<Entity, With<PromotionRoot>>,
+    mut piece_query: Query<(&mut Handle<Image>, &mut Transform, &mut PieceSprite)>,
+    mut game_state: ResMut<GameState>,
+    game_textures: Res<GameTextures>,
+    mut state: ResMut<State<GlobalState>>,
+    mut whose_turn: ResMut<State<WhoseTurn>>,
+) {
+    for (interaction, button, mut color) in &mut interactions {
+        match *interaction {
+            Interaction::Clicked => {
+                let Some(pending) = game_state.pending_promotion.take() else {
+                    return;
+                };
+                for entity in picker_root.iter() {
+                    commands.entity(entity).despawn_recursive();
+                }
+                handle_piece_move(
+                    &mut commands,
+                    &mut game_state,
+                    &mut piece_query,
+                    pending.entity,
+                    pending.to,
+                    &mut state,
+                    &game_textures,
+                    &mut whose_turn,
+                    Some(button.0),
+                );
+                return;
+            }
+            Interaction::Hovered => {
+                *color = BURGUNDY_LIGHT.into();
+            }
+            Interaction::None => {
+                *color = TRANSPARENT_BURGUNDY.into();
+            }
+        }
+    }
+}
+
+fn clear_promotion_picker(
+    mut commands: Commands,
+    mut game_state: ResMut<GameState>,
+    picker_root: Query<Entity, With<PromotionRoot>>,
+) {
+    game_state.pending_promotion = None;
+    for entity in picker_root.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+pub struct PromotionPlugin;
+
+impl Plugin for PromotionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(
+            SystemSet::on_update(GlobalState::InGame)
+                .with_system(spawn_promotion_picker)
+                .with_system(handle_promotion_button),
+        )
+        .add_system_set(
+            SystemSet::on_enter(GlobalState::MainMenu).with_system(clear_promotion_picker),
+        );
+    }
+}
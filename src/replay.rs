@@ -0,0 +1,203 @@
+use crate::board::{spawn_board, spawn_pieces};
+use crate::pgn::{MoveLog, PgnError, PgnGame};
+use crate::*;
+use bevy_kira_audio::AudioControl;
+
+// games loaded from the PGN file given with `--pgn <file>`
+#[derive(Resource)]
+pub struct ReplayGames {
+    pub games: Vec<PgnGame>,
+}
+
+#[derive(Resource, Default)]
+struct ReplayViewer {
+    game: usize,
+    positions: Vec<Board>,
+    log: MoveLog,
+    // number of moves played in the position on screen
+    current: usize,
+    error: Option<PgnError>,
+}
+
+#[derive(Component)]
+struct ReplayText;
+
+impl ReplayViewer {
+    fn load(&mut self, games: &ReplayGames, game: usize) {
+        self.game = game;
+        self.current = 0;
+        match games.games[game].replay(game + 1) {
+            Ok((positions, log)) => {
+                self.positions = positions;
+                self.log = log;
+                self.error = None;
+            }
+            Err(error) => {
+                self.positions = vec![Board::starting()];
+                self.log = MoveLog::default();
+                self.error = Some(error);
+            }
+        }
+    }
+
+    fn description(&self, games: &ReplayGames) -> String {
+        let game = &games.games[self.game];
+        let mut text = format!(
+            "Game {}/{}: {} vs {} ({})\n",
+            self.game + 1,
+            games.games.len(),
+            game.tag("White").unwrap_or("?"),
+            game.tag("Black").unwrap_or("?"),
+            game.result
+        );
+        if let Some(error) = &self.error {
+            text += &format!("Can't replay {}\n", error);
+        } else if self.current == 0 {
+            text += &format!("Start position, {} moves\n", self.log.moves.len());
+        } else {
+            let before = &self.positions[self.current - 1];
+            let number = match before.side_to_move {
+                PieceColor::White => format!("{}.", before.full_move_number()),
+                PieceColor::Black => format!("{}...", before.full_move_number()),
+            };
+            text += &format!(
+                "Move {}/{}: {} {}\n",
+                self.current,
+                self.log.moves.len(),
+                number,
+                self.log.moves[self.current - 1].san
+            );
+        }
+        text += "Left/Right: step, Home/End: jump, Up/Down: change game, Esc: menu";
+        text
+    }
+}
+
+fn show_position(
+    commands: &mut Commands,
+    game_textures: &Res<GameTextures>,
+    viewer: &ReplayViewer,
+    piece_query: &Query<Entity, With<PieceSprite>>,
+) {
+    for entity in piece_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    spawn_pieces(
+        commands,
+        game_textures,
+        &viewer.positions[viewer.current],
+        false,
+    );
+}
+
+fn spawn_replay(
+    mut commands: Commands,
+    game_textures: Res<GameTextures>,
+    asset_server: Res<AssetServer>,
+    games: Res<ReplayGames>,
+    mut viewer: ResMut<ReplayViewer>,
+    audio: Res<bevy_kira_audio::prelude::Audio>,
+) {
+    viewer.load(&games, 0);
+
+    spawn_board(&mut commands, &game_textures, &viewer.positions[0], false);
+
+    commands.spawn((
+        TextBundle::from_section(
+            viewer.description(&games),
+            TextStyle {
+                font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+                font_size: 25.0,
+                color: Color::WHITE,
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                top: Val::Px(5.0),
+                left: Val::Px(50.0),
+                ..default()
+            },
+            ..default()
+        }),
+        ReplayText,
+    ));
+    audio.pause();
+}
+
+#[allow(clippy::too_many_arguments)]
+fn handle_replay_keys(
+    mut commands: Commands,
+    keys: Res<Input<KeyCode>>,
+    game_textures: Res<GameTextures>,
+    games: Res<ReplayGames>,
+    mut viewer: ResMut<ReplayViewer>,
+    piece_query: Query<Entity, With<PieceSprite>>,
+    mut text_query: Query<&mut Text, With<ReplayText>>,
+    mut global_state: ResMut<State<GlobalState>>,
+) {
+    if keys.just_pressed(KeyCode::Escape) {
+        global_state
+            .set(GlobalState::MainMenu)
+            .expect("Error in setting state");
+        return;
+    }
+
+    if viewer.positions.is_empty() {
+        return;
+    }
+    let last = viewer.positions.len() - 1;
+    let current = viewer.current;
+    if keys.just_pressed(KeyCode::Right) && current < last {
+        viewer.current += 1;
+    } else if keys.just_pressed(KeyCode::Left) && current > 0 {
+        viewer.current -= 1;
+    } else if keys.just_pressed(KeyCode::Home) {
+        viewer.current = 0;
+    } else if keys.just_pressed(KeyCode::End) {
+        viewer.current = last;
+    } else if keys.just_pressed(KeyCode::Down) && viewer.game + 1 < games.games.len() {
+        let game = viewer.game + 1;
+        viewer.load(&games, game);
+    } else if keys.just_pressed(KeyCode::Up) && viewer.game > 0 {
+        let game = viewer.game - 1;
+        viewer.load(&games, game);
+    } else {
+        return;
+    }
+
+    show_position(&mut commands, &game_textures, &viewer, &piece_query);
+    for mut text in &mut text_query {
+        text.sections[0].value = viewer.description(&games);
+    }
+}
+
+fn despawn_replay(
+    mut commands: Commands,
+    piece_query: Query<Entity, With<PieceSprite>>,
+    field_query: Query<Entity, With<FieldSprite>>,
+    text_query: Query<Entity, With<ReplayText>>,
+    mut viewer: ResMut<ReplayViewer>,
+) {
+    for entity in piece_query
+        .iter()
+        .chain(field_query.iter())
+        .chain(text_query.iter())
+    {
+        commands.entity(entity).despawn_recursive();
+    }
+    *viewer = ReplayViewer::default();
+}
+
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ReplayViewer>()
+            .add_system_set(SystemSet::on_enter(GlobalState::Replay).with_system(spawn_replay))
+            .add_system_set(
+                SystemSet::on_update(GlobalState::Replay).with_system(handle_replay_keys),
+            )
+            .add_system_set(SystemSet::on_exit(GlobalState::Replay).with_system(despawn_replay));
+    }
+}
@@ -0,0 +1,203 @@
+use crate::animation::AnimationSettings;
+use crate::main_menu::spawn_menu_button;
+use crate::time_control::TimeControlSettings;
+use crate::*;
+use bevy::window::WindowMode;
+
+// the size of the window when leaving fullscreen
+const WINDOWED_SIZE: Vec2 = Vec2::new(1280.0, 800.0);
+
+// the game starts in fullscreen; the board follows the size of the window
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WindowSettings {
+    pub fullscreen: bool,
+}
+
+impl Default for WindowSettings {
+    fn default() -> Self {
+        WindowSettings { fullscreen: true }
+    }
+}
+
+impl WindowSettings {
+    pub fn mode(&self) -> WindowMode {
+        if self.fullscreen {
+            WindowMode::BorderlessFullscreen
+        } else {
+            WindowMode::Windowed
+        }
+    }
+}
+
+#[derive(Component)]
+struct SettingsRoot;
+
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+enum SettingsButton {
+    TimeControl,
+    AnimationDuration,
+    Easing,
+    Window,
+    Back,
+}
+
+impl SettingsButton {
+    fn text(
+        &self,
+        time_control: &TimeControlSettings,
+        animation: &AnimationSettings,
+        window: &WindowSettings,
+    ) -> String {
+        match self {
+            SettingsButton::TimeControl => format!("Time control: {}", time_control.name()),
+            SettingsButton::AnimationDuration => {
+                format!("Move animation: {}", animation.duration_name())
+            }
+            SettingsButton::Easing => format!("Easing: {}", animation.easing),
+            SettingsButton::Window if window.fullscreen => "Window: fullscreen".to_string(),
+            SettingsButton::Window => "Window: windowed".to_string(),
+            SettingsButton::Back => BACK_TO_MENU_TEXT.to_string(),
+        }
+    }
+}
+
+fn spawn_settings(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    time_control: Res<TimeControlSettings>,
+    animation: Res<AnimationSettings>,
+    window: Res<WindowSettings>,
+) {
+    let buttons: Vec<Entity> = [
+        SettingsButton::TimeControl,
+        SettingsButton::AnimationDuration,
+        SettingsButton::Easing,
+        SettingsButton::Window,
+        SettingsButton::Back,
+    ]
+    .into_iter()
+    .map(|button| {
+        let text = button.text(&time_control, &animation, &window);
+        let entity = spawn_menu_button(&mut commands, &asset_server, &text);
+        commands.entity(entity).insert(button);
+        entity
+    })
+    .collect();
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                justify_content: JustifyContent::Center,
+                flex_direction: FlexDirection::Column,
+                ..default()
+            },
+            ..default()
+        })
+        .insert(SettingsRoot)
+        .with_children(|commands| {
+            commands.spawn(TextBundle {
+                style: Style {
+                    align_self: AlignSelf::Center,
+                    margin: UiRect::all(Val::Percent(3.0)),
+                    ..default()
+                },
+                text: Text::from_section(
+                    SETTINGS_TEXT,
+                    TextStyle {
+                        font: asset_server.load("fonts/Aboreto-Regular.ttf"),
+                        font_size: 85.0,
+                        color: Color::BLACK,
+                    },
+                ),
+                ..default()
+            });
+        })
+        .push_children(&buttons);
+}
+
+fn handle_settings_buttons(
+    mut interactions: Query<
+        (&Interaction, &SettingsButton, &mut BackgroundColor),
+        Changed<Interaction>,
+    >,
+    mut animation: ResMut<AnimationSettings>,
+    mut window_settings: ResMut<WindowSettings>,
+    mut windows: ResMut<Windows>,
+    mut global_state: ResMut<State<GlobalState>>,
+) {
+    for (interaction, button, mut color) in &mut interactions {
+        match *interaction {
+            Interaction::Clicked => match button {
+                SettingsButton::TimeControl => {
+                    global_state
+                        .set(GlobalState::TimeControlSettings)
+                        .expect("Error in setting state");
+                }
+                SettingsButton::AnimationDuration => animation.next_duration(),
+                SettingsButton::Easing => animation.easing = animation.easing.next(),
+                SettingsButton::Window => {
+                    window_settings.fullscreen = !window_settings.fullscreen;
+                    let window = windows.primary_mut();
+                    window.set_mode(window_settings.mode());
+                    if !window_settings.fullscreen {
+                        window.set_resolution(WINDOWED_SIZE.x, WINDOWED_SIZE.y);
+                    }
+                }
+                SettingsButton::Back => {
+                    global_state
+                        .set(GlobalState::MainMenu)
+                        .expect("Error in setting state");
+                }
+            },
+            Interaction::Hovered => {
+                *color = LIGHT_GRAY.into();
+            }
+            Interaction::None => {
+                *color = DARK_GRAY.into();
+            }
+        }
+    }
+}
+
+fn update_settings_texts(
+    buttons: Query<(&SettingsButton, &Children)>,
+    mut text_query: Query<&mut Text>,
+    time_control: Res<TimeControlSettings>,
+    animation: Res<AnimationSettings>,
+    window: Res<WindowSettings>,
+) {
+    if !animation.is_changed() && !window.is_changed() {
+        return;
+    }
+    for (button, children) in &buttons {
+        for child in children.iter() {
+            if let Ok(mut text) = text_query.get_mut(*child) {
+                text.sections[0].value = button.text(&time_control, &animation, &window);
+            }
+        }
+    }
+}
+
+fn despawn_settings(mut commands: Commands, root: Query<Entity, With<SettingsRoot>>) {
+    for entity in root.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<WindowSettings>()
+            .add_system_set(SystemSet::on_enter(GlobalState::Settings).with_system(spawn_settings))
+            .add_system_set(
+                SystemSet::on_update(GlobalState::Settings)
+                    .with_system(handle_settings_buttons)
+                    .with_system(update_settings_texts),
+            )
+            .add_system_set(
+                SystemSet::on_exit(GlobalState::Settings).with_system(despawn_settings),
+            );
+    }
+}
@@ -0,0 +1,313 @@
+use crate::bot::BotMoveTask;
+use crate::clock::{ChessClock, TimeControl, TIME_CONTROL_PRESETS};
+use crate::main_menu::spawn_menu_button;
+use crate::user_input::end_game;
+use crate::*;
+use std::time::Duration;
+
+// the custom base times, in minutes, and increments, in seconds, the buttons cycle through
+const CUSTOM_BASES: [u64; 9] = [1, 2, 3, 5, 10, 15, 30, 60, 90];
+const CUSTOM_INCREMENTS: [u64; 8] = [0, 1, 2, 3, 5, 10, 15, 30];
+// below this the tenths of a second are shown as well
+const SHOW_TENTHS_BELOW: Duration = Duration::from_secs(20);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TimeControlChoice {
+    #[default]
+    Untimed,
+    // one of `TIME_CONTROL_PRESETS`
+    Preset(usize),
+    Custom,
+}
+
+// the time control of the next games, of any kind
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeControlSettings {
+    pub choice: TimeControlChoice,
+    pub custom: TimeControl,
+}
+
+impl Default for TimeControlSettings {
+    fn default() -> Self {
+        TimeControlSettings {
+            choice: TimeControlChoice::default(),
+            custom: TimeControl::new(10, 5),
+        }
+    }
+}
+
+impl TimeControlSettings {
+    pub fn time_control(&self) -> Option<TimeControl> {
+        match self.choice {
+            TimeControlChoice::Untimed => None,
+            TimeControlChoice::Preset(index) => Some(TIME_CONTROL_PRESETS[index].1),
+            TimeControlChoice::Custom => Some(self.custom),
+        }
+    }
+
+    // e.g. "Blitz 5+3"
+    pub fn name(&self) -> String {
+        match self.choice {
+            TimeControlChoice::Untimed => "untimed".to_string(),
+            TimeControlChoice::Preset(index) => {
+                let (name, control) = TIME_CONTROL_PRESETS[index];
+                format!("{} {}", name, control)
+            }
+            TimeControlChoice::Custom => format!("Custom {}", self.custom),
+        }
+    }
+
+    // untimed, every preset, then the custom time control
+    fn next_choice(&mut self) {
+        self.choice = match self.choice {
+            TimeControlChoice::Untimed => TimeControlChoice::Preset(0),
+            TimeControlChoice::Preset(index) if index + 1 < TIME_CONTROL_PRESETS.len() => {
+                TimeControlChoice::Preset(index + 1)
+            }
+            TimeControlChoice::Preset(_) => TimeControlChoice::Custom,
+            TimeControlChoice::Custom => TimeControlChoice::Untimed,
+        };
+    }
+
+    // changing a preset turns it into a custom time control
+    fn custom_mut(&mut self) -> &mut TimeControl {
+        if let Some(control) = self.time_control() {
+            self.custom = control;
+        }
+        self.choice = TimeControlChoice::Custom;
+        &mut self.custom
+    }
+
+    // the values the buttons show and change
+    fn shown(&self) -> TimeControl {
+        self.time_control().unwrap_or(self.custom)
+    }
+}
+
+fn next_value(current: u64, values: &[u64]) -> u64 {
+    *values
+        .iter()
+        .find(|value| **value > current)
+        .unwrap_or(&values[0])
+}
+
+// e.g. "4:59", "1:30:00", or "0:12.3" when little time is left
+pub fn format_clock_time(time: Duration) -> String {
+    let seconds = time.as_secs();
+    if seconds >= 3600 {
+        format!(
+            "{}:{:02}:{:02}",
+            seconds / 3600,
+            seconds / 60 % 60,
+            seconds % 60
+        )
+    } else if time < SHOW_TENTHS_BELOW {
+        format!(
+            "{}:{:02}.{}",
+            seconds / 60,
+            seconds % 60,
+            time.subsec_millis() / 100
+        )
+    } else {
+        format!("{}:{:02}", seconds / 60, seconds % 60)
+    }
+}
+
+#[derive(Component)]
+struct TimeControlRoot;
+
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+enum TimeControlButton {
+    Choice,
+    Base,
+    Increment,
+    Mode,
+    Back,
+}
+
+impl TimeControlButton {
+    fn text(&self, settings: &TimeControlSettings) -> String {
+        let control = settings.shown();
+        match self {
+            TimeControlButton::Choice => format!("Time control: {}", settings.name()),
+            TimeControlButton::Base => format!("Base time: {} min", control.base.as_secs() / 60),
+            TimeControlButton::Increment => {
+                format!("Increment: {} s", control.increment.as_secs())
+            }
+            TimeControlButton::Mode => format!("Type: {}", control.mode),
+            TimeControlButton::Back => BACK_TO_SETTINGS_TEXT.to_string(),
+        }
+    }
+}
+
+fn spawn_time_control_settings(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    settings: Res<TimeControlSettings>,
+) {
+    let buttons: Vec<Entity> = [
+        TimeControlButton::Choice,
+        TimeControlButton::Base,
+        TimeControlButton::Increment,
+        TimeControlButton::Mode,
+        TimeControlButton::Back,
+    ]
+    .into_iter()
+    .map(|button| {
+        let text = button.text(&settings);
+        let entity = spawn_menu_button(&mut commands, &asset_server, &text);
+        commands.entity(entity).insert(button);
+        entity
+    })
+    .collect();
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                justify_content: JustifyContent::Center,
+                flex_direction: FlexDirection::Column,
+                ..default()
+            },
+            ..default()
+        })
+        .insert(TimeControlRoot)
+        .with_children(|commands| {
+            commands.spawn(TextBundle {
+                style: Style {
+                    align_self: AlignSelf::Center,
+                    margin: UiRect::all(Val::Percent(3.0)),
+                    ..default()
+                },
+                text: Text::from_section(
+                    "Time control",
+                    TextStyle {
+                        font: asset_server.load("fonts/Aboreto-Regular.ttf"),
+                        font_size: 85.0,
+                        color: Color::BLACK,
+                    },
+                ),
+                ..default()
+            });
+        })
+        .push_children(&buttons);
+}
+
+fn handle_time_control_buttons(
+    mut interactions: Query<
+        (&Interaction, &TimeControlButton, &mut BackgroundColor),
+        Changed<Interaction>,
+    >,
+    mut settings: ResMut<TimeControlSettings>,
+    mut global_state: ResMut<State<GlobalState>>,
+) {
+    for (interaction, button, mut color) in &mut interactions {
+        match *interaction {
+            Interaction::Clicked => match button {
+                TimeControlButton::Choice => settings.next_choice(),
+                TimeControlButton::Base => {
+                    let control = settings.custom_mut();
+                    let minutes = next_value(control.base.as_secs() / 60, &CUSTOM_BASES);
+                    control.base = Duration::from_secs(minutes * 60);
+                }
+                TimeControlButton::Increment => {
+                    let control = settings.custom_mut();
+                    let seconds = next_value(control.increment.as_secs(), &CUSTOM_INCREMENTS);
+                    control.increment = Duration::from_secs(seconds);
+                }
+                TimeControlButton::Mode => {
+                    let control = settings.custom_mut();
+                    control.mode = control.mode.next();
+                }
+                TimeControlButton::Back => {
+                    global_state
+                        .set(GlobalState::Settings)
+                        .expect("Error in setting state");
+                }
+            },
+            Interaction::Hovered => {
+                *color = LIGHT_GRAY.into();
+            }
+            Interaction::None => {
+                *color = DARK_GRAY.into();
+            }
+        }
+    }
+}
+
+fn update_time_control_texts(
+    buttons: Query<(&TimeControlButton, &Children)>,
+    mut text_query: Query<&mut Text>,
+    settings: Res<TimeControlSettings>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+    for (button, children) in &buttons {
+        for child in children.iter() {
+            if let Ok(mut text) = text_query.get_mut(*child) {
+                text.sections[0].value = button.text(&settings);
+            }
+        }
+    }
+}
+
+fn despawn_time_control_settings(
+    mut commands: Commands,
+    root: Query<Entity, With<TimeControlRoot>>,
+) {
+    for entity in root.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+// every game gets a new clock, started for the side to move
+pub fn new_clock(settings: &TimeControlSettings, to_move: PieceColor) -> Option<ChessClock> {
+    settings
+        .time_control()
+        .map(|control| ChessClock::new(control, to_move))
+}
+
+// the clock stops while the player decides what to do about a failed engine; in an engine
+// match it only runs while an engine thinks, not while the last move is shown
+fn run_clock(
+    time: Res<Time>,
+    mut game_state: ResMut<GameState>,
+    mut state: ResMut<State<GlobalState>>,
+    mut whose_turn: ResMut<State<WhoseTurn>>,
+    tasks: Query<(), With<BotMoveTask>>,
+) {
+    let engine_waits = game_state.engine_match.is_some() && tasks.is_empty();
+    if game_state.clock.is_none() || game_state.bot_error.is_some() || engine_waits {
+        return;
+    }
+    let clock = game_state.clock.as_mut().expect("Checked above");
+    clock.tick(time.delta());
+    if let Some(flagged) = clock.flagged() {
+        clock.stop();
+        let result = game_state.board.timeout_result(flagged);
+        end_game(&mut game_state, &mut state, &mut whose_turn, result);
+    }
+}
+
+pub struct TimeControlPlugin;
+
+impl Plugin for TimeControlPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TimeControlSettings>()
+            .add_system_set(
+                SystemSet::on_enter(GlobalState::TimeControlSettings)
+                    .with_system(spawn_time_control_settings),
+            )
+            .add_system_set(
+                SystemSet::on_update(GlobalState::TimeControlSettings)
+                    .with_system(handle_time_control_buttons)
+                    .with_system(update_time_control_texts),
+            )
+            .add_system_set(
+                SystemSet::on_exit(GlobalState::TimeControlSettings)
+                    .with_system(despawn_time_control_settings),
+            )
+            .add_system_set(SystemSet::on_update(GlobalState::InGame).with_system(run_clock));
+    }
+}
@@ -1,5 +1,6 @@
 use bevy::diagnostic::{Diagnostics, FrameTimeDiagnosticsPlugin};
 
+use crate::time_control::format_clock_time;
 use crate::*;
 
 #[derive(Resource, Default)]
@@ -155,15 +156,23 @@ pub struct ColorText;
 
 fn init_next_move_text(mut commands: Commands, asset_server: ResMut<AssetServer>) {
     let font: Handle<Font> = asset_server.load("fonts/FiraSans-Bold.ttf");
+    // the second section shows the clocks in timed games
     commands.spawn((
-        TextBundle::from_section(
-            "Next move: ",
-            TextStyle {
+        TextBundle::from_sections([
+            TextSection::new(
+                "Next move: ",
+                TextStyle {
+                    font: font.clone(),
+                    font_size: 30.0,
+                    color: Color::WHITE,
+                },
+            ),
+            TextSection::from_style(TextStyle {
                 font: font.clone(),
                 font_size: 30.0,
                 color: Color::WHITE,
-            },
-        )
+            }),
+        ])
         .with_text_alignment(TextAlignment::TOP_CENTER)
         .with_style(Style {
             position_type: PositionType::Absolute,
@@ -220,13 +229,24 @@ fn text_color_system(time: Res<Time>, mut query: Query<&mut Text, With<ColorText
 }
 
 fn change_text_system(mut query: Query<&mut Text, With<ColorText>>, game_state: Res<GameState>) {
-    if game_state.white {
-        let mut item = query.single_mut();
-        item.sections[0].value = "Next move: White".to_string();
+    let mut text = if game_state.white {
+        "Next move: White".to_string()
     } else {
-        let mut item = query.single_mut();
-        item.sections[0].value = "Next move: Black".to_string();
+        "Next move: Black".to_string()
+    };
+    if let Some(draw) = game_state.board.claimable_draw() {
+        text += &format!(" (press D to claim a draw by {})", draw.reason);
     }
+    let mut item = query.single_mut();
+    item.sections[0].value = text;
+    item.sections[1].value = match &game_state.clock {
+        Some(clock) => format!(
+            "   White {}   Black {}",
+            format_clock_time(clock.remaining(PieceColor::White)),
+            format_clock_time(clock.remaining(PieceColor::Black))
+        ),
+        None => String::new(),
+    };
 }
 
 fn text_update_system(diagnostics: Res<Diagnostics>, mut query: Query<&mut Text, With<FpsText>>) {
@@ -243,7 +263,7 @@ pub struct UserInterfacePlugin;
 
 impl Plugin for UserInterfacePlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugin(FrameTimeDiagnosticsPlugin::default())
+        app.add_plugin(FrameTimeDiagnosticsPlugin)
             .add_system_set(
                 SystemSet::on_enter(GlobalState::InGame).with_system(init_next_move_text),
             )
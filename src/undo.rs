@@ -0,0 +1,132 @@
+use crate::board::spawn_pieces;
+use crate::field::FieldColor;
+use crate::user_input::end_game;
+use crate::*;
+
+// in games against the bot its reply is taken back together with the player's move
+fn moves_per_step(game_state: &GameState) -> usize {
+    if game_state.vs_bot {
+        2
+    } else {
+        1
+    }
+}
+
+fn can_change_moves(game_state: &GameState) -> bool {
+    game_state.pending_promotion.is_none()
+        && game_state.viewed_move.is_none()
+        && !(game_state.vs_bot && game_state.bot_turn)
+}
+
+// the clocks go back to the times they showed before the moves taken back
+pub fn undo_moves(game_state: &mut GameState) -> bool {
+    let count = moves_per_step(game_state);
+    can_change_moves(game_state)
+        && game_state.history.undo(
+            count,
+            &mut game_state.board,
+            &mut game_state.move_log,
+            &mut game_state.clock,
+        )
+}
+
+pub fn redo_moves(game_state: &mut GameState) -> bool {
+    let count = moves_per_step(game_state);
+    can_change_moves(game_state)
+        && game_state.history.redo(
+            count,
+            &mut game_state.board,
+            &mut game_state.move_log,
+            &mut game_state.clock,
+        )
+}
+
+// replaces all piece sprites with the ones matching the board after moves were taken back
+// or played again
+pub fn restore_position(
+    commands: &mut Commands,
+    game_textures: &Res<GameTextures>,
+    game_state: &mut ResMut<GameState>,
+    piece_query: &Query<Entity, With<PieceSprite>>,
+    field_query: &mut Query<(&mut Sprite, &FieldSprite)>,
+) {
+    for entity in piece_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    game_state.piece_entities = spawn_pieces(
+        commands,
+        game_textures,
+        &game_state.board,
+        game_state.flipped,
+    );
+
+    for (mut sprite, field) in field_query.iter_mut() {
+        sprite.color = match field.color {
+            FieldColor::White => WHITE_FIELD,
+            FieldColor::Black => BLACK_FIELD,
+        };
+    }
+    game_state.selected_entity = None;
+    let side_to_move = game_state.board.side_to_move;
+    game_state.white = side_to_move == PieceColor::White;
+}
+
+// a move played again may end the game
+pub fn check_result(
+    game_state: &mut ResMut<GameState>,
+    state: &mut ResMut<State<GlobalState>>,
+    whose_turn: &mut ResMut<State<WhoseTurn>>,
+) {
+    let to_move = game_state.board.side_to_move;
+    if let Some(result) = game_state.board.game_result(to_move) {
+        end_game(game_state, state, whose_turn, result);
+    }
+}
+
+// Ctrl+Z takes moves back, Ctrl+Y or Ctrl+Shift+Z plays them again
+#[allow(clippy::too_many_arguments)]
+fn undo_redo_on_keys(
+    mut commands: Commands,
+    keys: Res<Input<KeyCode>>,
+    game_textures: Res<GameTextures>,
+    mut game_state: ResMut<GameState>,
+    piece_query: Query<Entity, With<PieceSprite>>,
+    mut field_query: Query<(&mut Sprite, &FieldSprite)>,
+    mut state: ResMut<State<GlobalState>>,
+    mut whose_turn: ResMut<State<WhoseTurn>>,
+) {
+    let ctrl = keys.any_pressed([KeyCode::LControl, KeyCode::RControl]);
+    let shift = keys.any_pressed([KeyCode::LShift, KeyCode::RShift]);
+    if !ctrl {
+        return;
+    }
+
+    let changed = if keys.just_pressed(KeyCode::Y) || (shift && keys.just_pressed(KeyCode::Z)) {
+        redo_moves(&mut game_state)
+    } else if keys.just_pressed(KeyCode::Z) {
+        undo_moves(&mut game_state)
+    } else {
+        false
+    };
+
+    if changed {
+        restore_position(
+            &mut commands,
+            &game_textures,
+            &mut game_state,
+            &piece_query,
+            &mut field_query,
+        );
+        check_result(&mut game_state, &mut state, &mut whose_turn);
+    }
+}
+
+pub struct UndoPlugin;
+
+impl Plugin for UndoPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(
+            SystemSet::on_update(GlobalState::InGame).with_system(undo_redo_on_keys),
+        );
+    }
+}
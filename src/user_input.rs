@@ -1,14 +1,22 @@
-use crate::coordinates::{mouse_pos_to_coordinates, Coordinates};
-use crate::field::Field;
+use crate::animation::{animate_piece, remove_captured_piece, PieceAnimation, DRAGGED_PIECE_Z};
+use crate::board::{cursor_translation, field_translation, mouse_pos_to_coordinates, BoardScale};
+use crate::coordinates::Coordinates;
 use crate::moves::*;
+use crate::pgn::LoggedMove;
+use crate::promotion::PendingPromotion;
 use crate::ui::GameTextures;
 use crate::*;
 use bevy::input::{mouse::*, ButtonState};
 use bevy_kira_audio::AudioControl;
 
-fn move_piece_sprite(mut transform: Mut<Transform>, from: Coordinates, to: Coordinates) {
-    transform.translation.x += (to.x as f32 - from.x as f32) * FIELD_SIZE;
-    transform.translation.y += (to.y as f32 - from.y as f32) * FIELD_SIZE;
+fn move_piece_sprite(
+    commands: &mut Commands,
+    entity: Entity,
+    transform: &Transform,
+    to: Coordinates,
+    flipped: bool,
+) {
+    animate_piece(commands, entity, transform, field_translation(to, flipped));
 }
 
 fn handle_end_of_move(
@@ -17,29 +25,29 @@ fn handle_end_of_move(
     whose_turn: &mut ResMut<State<WhoseTurn>>,
 ) {
     game_state.white = !game_state.white;
-
-    // check for winner or draw
-    let mut color = PieceColor::White;
-    let mut maybe_winner = PieceColor::Black;
-    if !game_state.white {
-        color = PieceColor::Black;
-        maybe_winner = PieceColor::White;
+    if let Some(clock) = game_state.clock.as_mut() {
+        clock.press();
+    }
+    // a move made in the frame in which the clock ran out comes too late
+    if game_state.result.is_some() {
+        return;
     }
 
-    if game_state.board.no_possible_moves(color) {
-        if game_state.board.king_in_danger(color) {
-            game_state.winner = Some(maybe_winner);
-        }
-        state
-            .set(GlobalState::GameOver)
-            .expect("Unexpected error while setting state");
-        // if it is bot turn then we need to set player turn
-        if *whose_turn.current() == WhoseTurn::Bot {
-            whose_turn
-                .set(WhoseTurn::Player)
-                .expect("Unexpected error while setting state");
-        }
-    } else if game_state.vs_bot {
+    // check for winner or draw
+    let color = if game_state.white {
+        PieceColor::White
+    } else {
+        PieceColor::Black
+    };
+
+    // engines playing each other claim draws as soon as they can
+    let claimed_draw = game_state
+        .engine_match
+        .as_ref()
+        .and(game_state.board.claimable_draw());
+    if let Some(result) = game_state.board.game_result(color).or(claimed_draw) {
+        end_game(game_state, state, whose_turn, result);
+    } else if game_state.vs_bot && game_state.engine_match.is_none() {
         if game_state.bot_turn {
             whose_turn
                 .set(WhoseTurn::Player)
@@ -53,24 +61,47 @@ fn handle_end_of_move(
     }
 }
 
+pub fn end_game(
+    game_state: &mut ResMut<GameState>,
+    state: &mut ResMut<State<GlobalState>>,
+    whose_turn: &mut ResMut<State<WhoseTurn>>,
+    result: GameResult,
+) {
+    // the game may have ended in the same frame already, e.g. by the clock running out
+    if game_state.result.is_some() {
+        return;
+    }
+    game_state.result = Some(result);
+    state
+        .set(GlobalState::GameOver)
+        .expect("Unexpected error while setting state");
+    // if it is bot turn then we need to set player turn
+    if *whose_turn.current() == WhoseTurn::Bot {
+        whose_turn
+            .set(WhoseTurn::Player)
+            .expect("Unexpected error while setting state");
+    }
+}
+
 fn promote_pawn(
     image: &mut Handle<Image>,
-    piece: &mut Piece,
+    piece: &mut PieceSprite,
     game_textures: &Res<GameTextures>,
     white: bool,
+    promotion: PieceType,
 ) {
-    piece.piece_type = PieceType::Queen;
+    piece.piece_type = promotion;
     if white {
         *image = game_textures
             .white_images_map
-            .get(&PieceType::Queen)
+            .get(&promotion)
             .expect("Error in getting image")
             .0
             .clone()
     } else {
         *image = game_textures
             .black_images_map
-            .get(&PieceType::Queen)
+            .get(&promotion)
             .expect("Error in getting image")
             .0
             .clone()
@@ -79,20 +110,22 @@ fn promote_pawn(
 
 fn handle_pawn_promotion(
     image: &mut Handle<Image>,
-    piece: &mut Piece,
+    piece: &mut PieceSprite,
     game_textures: &Res<GameTextures>,
     clicked_coords: Coordinates,
+    promotion: Option<PieceType>,
 ) {
+    let promotion = promotion.unwrap_or(PieceType::Queen);
     if (piece.piece_type == PieceType::Pawn { moved: true }) {
         if piece.piece_color == PieceColor::White && clicked_coords.y == 8 {
-            promote_pawn(image, piece, game_textures, true);
+            promote_pawn(image, piece, game_textures, true, promotion);
         } else if piece.piece_color == PieceColor::Black && clicked_coords.y == 1 {
-            promote_pawn(image, piece, game_textures, false);
+            promote_pawn(image, piece, game_textures, false, promotion);
         }
     }
 }
 
-fn check_if_piece_already_moved(piece: &mut Piece) {
+fn check_if_piece_already_moved(piece: &mut PieceSprite) {
     if (piece.piece_type == PieceType::Pawn { moved: false }) {
         piece.piece_type = PieceType::Pawn { moved: true };
     }
@@ -108,56 +141,82 @@ fn check_if_piece_already_moved(piece: &mut Piece) {
 
 fn move_piece_on_board(
     game_state: &mut ResMut<GameState>,
-    piece: &mut Piece,
+    piece: &mut PieceSprite,
     clicked_coords: Coordinates,
+    promotion: Option<PieceType>,
 ) {
-    let _ = &game_state
-        .board
-        .move_piece(piece.coordinates, clicked_coords);
+    log_and_make_move(game_state, piece.coordinates, clicked_coords, promotion);
     piece.coordinates = clicked_coords;
 }
 
+// makes the move on the board, writing it down for the PGN and for taking it back
+fn log_and_make_move(
+    game_state: &mut ResMut<GameState>,
+    from: Coordinates,
+    to: Coordinates,
+    promotion: Option<PieceType>,
+) {
+    let logged_move = LoggedMove::new(&game_state.board, from, to, promotion);
+    let clock = game_state.clock;
+    if let Some(record) = game_state.board.make_move(from, to, promotion) {
+        game_state.move_log.push(logged_move);
+        game_state.history.push(record, clock);
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn handle_piece_move(
     commands: &mut Commands,
     game_state: &mut ResMut<GameState>,
-    piece_query: &mut Query<(&mut Handle<Image>, &mut Transform, &mut Piece)>,
+    piece_query: &mut Query<(&mut Handle<Image>, &mut Transform, &mut PieceSprite)>,
     selected_entity: Entity,
     clicked_coords: Coordinates,
     state: &mut ResMut<State<GlobalState>>,
     game_textures: &Res<GameTextures>,
     whose_turn: &mut ResMut<State<WhoseTurn>>,
+    promotion: Option<PieceType>,
 ) {
     let query_item = piece_query.get_mut(selected_entity);
     let (mut image, transform, mut piece) = query_item.expect("Error in getting piece");
 
-    let new_field = game_state
+    let captured_coords = game_state
         .board
-        .get_field(clicked_coords)
-        .expect("Error in getting field");
-    if let Some(new_piece) = &new_field.piece {
-        if let Some(entity) = new_piece.entity {
-            commands.entity(entity).despawn();
-        }
+        .en_passant_capture(piece.coordinates, clicked_coords)
+        .unwrap_or(clicked_coords);
+    if let Some(entity) = game_state.piece_entities.remove(captured_coords) {
+        remove_captured_piece(commands, entity);
     }
+    game_state
+        .piece_entities
+        .move_entity(piece.coordinates, clicked_coords);
 
     check_if_piece_already_moved(&mut piece);
 
-    handle_pawn_promotion(&mut image, &mut piece, game_textures, clicked_coords);
+    handle_pawn_promotion(
+        &mut image,
+        &mut piece,
+        game_textures,
+        clicked_coords,
+        promotion,
+    );
 
-    move_piece_sprite(transform, piece.coordinates, clicked_coords);
+    move_piece_sprite(
+        commands,
+        selected_entity,
+        &transform,
+        clicked_coords,
+        game_state.flipped,
+    );
 
-    move_piece_on_board(game_state, &mut piece, clicked_coords);
+    move_piece_on_board(game_state, &mut piece, clicked_coords, promotion);
 
-    if !game_state.castling {
-        handle_end_of_move(game_state, state, whose_turn);
-    }
+    handle_end_of_move(game_state, state, whose_turn);
 }
 
 fn handle_piece_choice(
     game_state: &mut ResMut<GameState>,
     game_textures: &Res<GameTextures>,
-    query: &mut Query<(&mut Handle<Image>, &mut Transform, &mut Piece)>,
+    query: &mut Query<(&mut Handle<Image>, &mut Transform, &mut PieceSprite)>,
     entity: Entity,
     select: bool,
 ) {
@@ -179,7 +238,7 @@ fn handle_piece_choice(
 fn unselect_piece(
     game_state: &mut ResMut<GameState>,
     game_textures: &Res<GameTextures>,
-    query: &mut Query<(&mut Handle<Image>, &mut Transform, &mut Piece)>,
+    query: &mut Query<(&mut Handle<Image>, &mut Transform, &mut PieceSprite)>,
     entity: Entity,
 ) {
     handle_piece_choice(game_state, game_textures, query, entity, false);
@@ -188,24 +247,21 @@ fn unselect_piece(
 fn select_piece(
     game_state: &mut ResMut<GameState>,
     game_textures: &Res<GameTextures>,
-    query: &mut Query<(&mut Handle<Image>, &mut Transform, &mut Piece)>,
+    query: &mut Query<(&mut Handle<Image>, &mut Transform, &mut PieceSprite)>,
     entity: Entity,
 ) {
     handle_piece_choice(game_state, game_textures, query, entity, true);
 }
 
-#[allow(clippy::too_many_arguments)]
-fn handle_castling(
+pub fn handle_castling(
     commands: &mut Commands,
     game_state: &mut ResMut<GameState>,
-    piece_query: &mut Query<(&mut Handle<Image>, &mut Transform, &mut Piece)>,
+    piece_query: &mut Query<(&mut Handle<Image>, &mut Transform, &mut PieceSprite)>,
     king_entity: Entity,
     rook_entity: Entity,
     state: &mut ResMut<State<GlobalState>>,
-    game_textures: &Res<GameTextures>,
     whose_turn: &mut ResMut<State<WhoseTurn>>,
 ) {
-    game_state.castling = true;
     let rook_piece = piece_query
         .get_mut(rook_entity)
         .expect("Error in getting piece")
@@ -240,27 +296,23 @@ fn handle_castling(
             y: rook_coords.y,
         };
     }
-    handle_piece_move(
-        commands,
-        game_state,
-        piece_query,
-        king_entity,
-        new_king_coords,
-        state,
-        game_textures,
-        whose_turn,
-    );
-    game_state.castling = false;
-    handle_piece_move(
-        commands,
-        game_state,
-        piece_query,
-        rook_entity,
-        new_rook_coords,
-        state,
-        game_textures,
-        whose_turn,
-    );
+    for (entity, new_coords) in [
+        (king_entity, new_king_coords),
+        (rook_entity, new_rook_coords),
+    ] {
+        let (_, transform, mut piece) =
+            piece_query.get_mut(entity).expect("Error in getting piece");
+        game_state
+            .piece_entities
+            .move_entity(piece.coordinates, new_coords);
+        check_if_piece_already_moved(&mut piece);
+        move_piece_sprite(commands, entity, &transform, new_coords, game_state.flipped);
+        piece.coordinates = new_coords;
+    }
+
+    // on the board castling is a single move of the king onto its rook
+    log_and_make_move(game_state, king_coords, rook_coords, None);
+    handle_end_of_move(game_state, state, whose_turn);
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -269,8 +321,8 @@ fn handle_field_click(
     game_state: &mut ResMut<GameState>,
     game_textures: &Res<GameTextures>,
     clicked_coords: Coordinates,
-    piece_query: &mut Query<(&mut Handle<Image>, &mut Transform, &mut Piece)>,
-    field_query: &mut Query<(&mut Sprite, &mut Field)>,
+    piece_query: &mut Query<(&mut Handle<Image>, &mut Transform, &mut PieceSprite)>,
+    field_query: &mut Query<(&mut Sprite, &mut FieldSprite)>,
     state: &mut ResMut<State<GlobalState>>,
     whose_turn: &mut ResMut<State<WhoseTurn>>,
 ) {
@@ -283,20 +335,19 @@ fn handle_field_click(
             .expect("Error in getting field");
 
         // if clicked field has piece and it's the same color as the player who's turn it is
-        if clicked_field.piece.is_some()
-            && (clicked_field
-                .piece
-                .clone()
-                .expect("Error in cloning piece")
-                .piece_color
-                == PieceColor::White)
-                == game_state.white
+        if clicked_field
+            .piece
+            .as_ref()
+            .is_some_and(|piece| (piece.piece_color == PieceColor::White) == game_state.white)
         {
             let clicked_piece = clicked_field
                 .piece
                 .as_ref()
                 .expect("Error in getting field ref");
-            let clicked_id = clicked_piece.entity.expect("Error in getting piece");
+            let clicked_id = game_state
+                .piece_entities
+                .get(clicked_coords)
+                .expect("Error in getting piece");
             let selected_piece = piece_query
                 .get_mut(selected_id)
                 .expect("Error in getting piece")
@@ -311,7 +362,9 @@ fn handle_field_click(
             if clicked_piece.piece_type == (PieceType::Rook { moved: false })
                 && selected_piece.piece_type == (PieceType::King { moved: false })
             {
-                let possible_moves = get_possible_moves(&selected_piece, &game_state.board, true);
+                let possible_moves = game_state
+                    .board
+                    .legal_moves_from(selected_piece.coordinates);
                 if possible_moves.contains(&clicked_coords) {
                     handle_castling(
                         commands,
@@ -320,7 +373,6 @@ fn handle_field_click(
                         selected_id,
                         clicked_id,
                         state,
-                        game_textures,
                         whose_turn,
                     );
                 } else {
@@ -335,18 +387,30 @@ fn handle_field_click(
                 .expect("Error in getting piece")
                 .2;
 
-            let possible_moves = get_possible_moves(&piece, &game_state.board, true);
+            let possible_moves = game_state.board.legal_moves_from(piece.coordinates);
             if possible_moves.contains(&clicked_coords) {
-                handle_piece_move(
-                    commands,
-                    game_state,
-                    piece_query,
-                    selected_id,
-                    clicked_coords,
-                    state,
-                    game_textures,
-                    whose_turn,
-                );
+                if game_state
+                    .board
+                    .is_promotion(piece.coordinates, clicked_coords)
+                {
+                    // the move is committed once the player picks a piece
+                    game_state.pending_promotion = Some(PendingPromotion {
+                        entity: selected_id,
+                        to: clicked_coords,
+                    });
+                } else {
+                    handle_piece_move(
+                        commands,
+                        game_state,
+                        piece_query,
+                        selected_id,
+                        clicked_coords,
+                        state,
+                        game_textures,
+                        whose_turn,
+                        None,
+                    );
+                }
             }
         }
     } else {
@@ -358,7 +422,10 @@ fn handle_field_click(
 
         if let Some(piece) = clicked_field.piece.as_ref() {
             if (piece.piece_color == PieceColor::White) == game_state.white {
-                let clicked_id = piece.entity.expect("Error in getting piece entity");
+                let clicked_id = game_state
+                    .piece_entities
+                    .get(clicked_coords)
+                    .expect("Error in getting piece entity");
                 select_piece(game_state, game_textures, piece_query, clicked_id);
             }
         }
@@ -368,8 +435,8 @@ fn handle_field_click(
 fn clear_board(
     game_state: &mut ResMut<GameState>,
     game_textures: &Res<GameTextures>,
-    piece_query: &mut Query<(&mut Handle<Image>, &mut Transform, &mut Piece)>,
-    field_query: &mut Query<(&mut Sprite, &mut Field)>,
+    piece_query: &mut Query<(&mut Handle<Image>, &mut Transform, &mut PieceSprite)>,
+    field_query: &mut Query<(&mut Sprite, &mut FieldSprite)>,
 ) {
     for (mut sprite_field, field) in field_query.iter_mut() {
         match field.color {
@@ -383,32 +450,166 @@ fn clear_board(
     }
 }
 
+// a piece held with the mouse; it is dropped on the field the button is released over
+#[derive(Debug, Clone, Copy)]
+struct DraggedPiece {
+    entity: Entity,
+    from: Coordinates,
+    // from the cursor to the centre of the sprite, so that it doesn't jump when picked up
+    offset: Vec2,
+    // a piece that was selected already is unselected when it is only clicked
+    was_selected: bool,
+}
+
+#[derive(Resource, Default)]
+struct Dragging(Option<DraggedPiece>);
+
+// the king can also be dropped on the field it castles to, which on the board is
+// a move onto its rook
+fn castling_drop_target(board: &Board, from: Coordinates, to: Coordinates) -> Coordinates {
+    let is_king = board
+        .get_field(from)
+        .and_then(|field| field.piece.as_ref())
+        .is_some_and(|piece| piece.piece_type == (PieceType::King { moved: false }));
+    if !is_king || to.y != from.y || (to.x - from.x).abs() != 2 {
+        return to;
+    }
+    let rook_coords = Coordinates {
+        x: if to.x > from.x { BOARD_SIZE as i32 } else { 1 },
+        y: from.y,
+    };
+    if board.legal_moves_from(from).contains(&rook_coords) {
+        rook_coords
+    } else {
+        to
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn drop_piece(
+    commands: &mut Commands,
+    game_state: &mut ResMut<GameState>,
+    game_textures: &Res<GameTextures>,
+    dragged: DraggedPiece,
+    dropped_on: Option<Coordinates>,
+    piece_query: &mut Query<(&mut Handle<Image>, &mut Transform, &mut PieceSprite)>,
+    field_query: &mut Query<(&mut Sprite, &mut FieldSprite)>,
+    state: &mut ResMut<State<GlobalState>>,
+    whose_turn: &mut ResMut<State<WhoseTurn>>,
+) {
+    // the position changed while the piece was held, e.g. a move was taken back
+    if game_state.piece_entities.get(dragged.from) != Some(dragged.entity) {
+        return;
+    }
+    let target = dropped_on
+        .filter(|coords| game_state.board.get_field(*coords).is_some())
+        .map(|coords| castling_drop_target(&game_state.board, dragged.from, coords));
+    match target {
+        Some(coords) if coords == dragged.from => {
+            if dragged.was_selected {
+                clear_board(game_state, game_textures, piece_query, field_query);
+            }
+        }
+        Some(coords)
+            if game_state
+                .board
+                .legal_moves_from(dragged.from)
+                .contains(&coords) =>
+        {
+            handle_field_click(
+                commands,
+                game_state,
+                game_textures,
+                coords,
+                piece_query,
+                field_query,
+                state,
+                whose_turn,
+            );
+        }
+        _ => clear_board(game_state, game_textures, piece_query, field_query),
+    }
+
+    // a moved piece slides from the cursor to its field, any other one goes back
+    if game_state.piece_entities.get(dragged.from) != Some(dragged.entity) {
+        return;
+    }
+    let Ok((_, mut transform, _)) = piece_query.get_mut(dragged.entity) else {
+        return;
+    };
+    match game_state.pending_promotion {
+        Some(pending) if pending.entity == dragged.entity => {
+            let translation = field_translation(pending.to, game_state.flipped);
+            transform.translation = translation.extend(PIECE_Z);
+        }
+        _ => {
+            let translation = field_translation(dragged.from, game_state.flipped);
+            animate_piece(commands, dragged.entity, &transform, translation);
+        }
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 fn handle_user_input(
     mut commands: Commands,
     windows: Res<Windows>,
     mut button_evr: EventReader<MouseButtonInput>,
-    mut piece_query: Query<(&mut Handle<Image>, &mut Transform, &mut Piece)>,
-    mut field_query: Query<(&mut Sprite, &mut Field)>,
+    mouse_buttons: Res<Input<MouseButton>>,
+    board_scale: Res<BoardScale>,
+    mut dragging: ResMut<Dragging>,
+    mut piece_query: Query<(&mut Handle<Image>, &mut Transform, &mut PieceSprite)>,
+    mut field_query: Query<(&mut Sprite, &mut FieldSprite)>,
     game_textures: Res<GameTextures>,
     mut game_state: ResMut<GameState>,
     mut state: ResMut<State<GlobalState>>,
     mut whose_turn: ResMut<State<WhoseTurn>>,
 ) {
+    // the position of an earlier move can only be looked at
+    if game_state.pending_promotion.is_some() || game_state.viewed_move.is_some() {
+        button_evr.clear();
+        return;
+    }
+
     if !game_state.vs_bot || !game_state.bot_turn {
         let window = windows.get_primary().expect("Error in getting windows");
         let (height, width) = (window.height(), window.width());
 
         for event in button_evr.iter() {
-            if let ButtonState::Pressed = event.state {
-                if event.button != MouseButton::Left {
-                    continue;
-                }
-
-                if let Some(pos) = window.cursor_position() {
-                    let clicked_coords = mouse_pos_to_coordinates(pos.x, pos.y, width, height);
+            if event.button != MouseButton::Left {
+                continue;
+            }
+            let cursor = window.cursor_position();
+            let clicked_coords = cursor.map(|pos| {
+                mouse_pos_to_coordinates(
+                    pos.x,
+                    pos.y,
+                    width,
+                    height,
+                    *board_scale,
+                    game_state.flipped,
+                )
+            });
+
+            match event.state {
+                ButtonState::Pressed => {
+                    let (Some(pos), Some(clicked_coords)) = (cursor, clicked_coords) else {
+                        continue;
+                    };
+                    if game_state.board.get_field(clicked_coords).is_none() {
+                        // clicked outside of the board
+                        clear_board(
+                            &mut game_state,
+                            &game_textures,
+                            &mut piece_query,
+                            &mut field_query,
+                        );
+                        continue;
+                    }
 
-                    if game_state.board.get_field(clicked_coords).is_some() {
+                    let clicked_entity = game_state.piece_entities.get(clicked_coords);
+                    let was_selected =
+                        clicked_entity.is_some() && game_state.selected_entity == clicked_entity;
+                    if !was_selected {
                         handle_field_click(
                             &mut commands,
                             &mut game_state,
@@ -419,22 +620,87 @@ fn handle_user_input(
                             &mut state,
                             &mut whose_turn,
                         );
-                    } else {
-                        // clicked outside of the board
-                        clear_board(
+                    }
+
+                    // a piece of the side to move is picked up together with being selected
+                    let Some(entity) = game_state
+                        .selected_entity
+                        .filter(|entity| clicked_entity == Some(*entity))
+                    else {
+                        continue;
+                    };
+                    if let Ok((_, transform, _)) = piece_query.get(entity) {
+                        let cursor = cursor_translation(pos.x, pos.y, width, height, *board_scale);
+                        dragging.0 = Some(DraggedPiece {
+                            entity,
+                            from: clicked_coords,
+                            offset: transform.translation.truncate() - cursor,
+                            was_selected,
+                        });
+                        commands.entity(entity).remove::<PieceAnimation>();
+                    }
+                }
+                ButtonState::Released => {
+                    if let Some(dragged) = dragging.0.take() {
+                        drop_piece(
+                            &mut commands,
                             &mut game_state,
                             &game_textures,
+                            dragged,
+                            clicked_coords,
                             &mut piece_query,
                             &mut field_query,
+                            &mut state,
+                            &mut whose_turn,
                         );
                     }
                 }
             }
         }
+
+        let Some(dragged) = dragging.0 else {
+            return;
+        };
+        if !mouse_buttons.pressed(MouseButton::Left) {
+            // the button was released while the game was paused
+            dragging.0 = None;
+            drop_piece(
+                &mut commands,
+                &mut game_state,
+                &game_textures,
+                dragged,
+                None,
+                &mut piece_query,
+                &mut field_query,
+                &mut state,
+                &mut whose_turn,
+            );
+        } else if let Some(pos) = window.cursor_position() {
+            if let Ok((_, mut transform, _)) = piece_query.get_mut(dragged.entity) {
+                let cursor = cursor_translation(pos.x, pos.y, width, height, *board_scale);
+                transform.translation = (cursor + dragged.offset).extend(DRAGGED_PIECE_Z);
+            }
+        }
+    }
+}
+
+// a piece still held when the game ends goes back to its field
+fn put_back_dragged_piece(
+    mut commands: Commands,
+    mut dragging: ResMut<Dragging>,
+    piece_query: Query<&Transform, With<PieceSprite>>,
+    game_state: Res<GameState>,
+) {
+    let Some(dragged) = dragging.0.take() else {
+        return;
+    };
+    if let Ok(transform) = piece_query.get(dragged.entity) {
+        let translation = field_translation(dragged.from, game_state.flipped);
+        animate_piece(&mut commands, dragged.entity, transform, translation);
     }
 }
 
-fn reset_fields_to_default(field_query: &mut Query<(&mut Sprite, &Field)>) {
+pub fn reset_fields_to_default(field_query: &mut Query<(&mut Sprite, &FieldSprite)>) {
     for (mut sprite_field, field) in field_query.iter_mut() {
         match field.color {
             field::FieldColor::White => sprite_field.color = WHITE_FIELD,
@@ -445,7 +711,7 @@ fn reset_fields_to_default(field_query: &mut Query<(&mut Sprite, &Field)>) {
 
 fn highlight_fields(
     piece: &Piece,
-    field_query: &mut Query<(&mut Sprite, &Field)>,
+    field_query: &mut Query<(&mut Sprite, &FieldSprite)>,
     game_state: &Res<GameState>,
 ) {
     let possible_moves = get_possible_moves(piece, &game_state.board, true);
@@ -461,12 +727,18 @@ fn highlight_fields(
 
 fn highlight_moves_on_click(
     windows: Res<Windows>,
+    board_scale: Res<BoardScale>,
     mut button_evr: EventReader<MouseButtonInput>,
-    mut field_query: Query<(&mut Sprite, &Field)>,
+    mut field_query: Query<(&mut Sprite, &FieldSprite)>,
     game_state: Res<GameState>,
 ) {
     let window = windows.get_primary().expect("Error in getting windows");
     let (height, width) = (window.height(), window.width());
+    // the position of an earlier move can only be looked at
+    if game_state.pending_promotion.is_some() || game_state.viewed_move.is_some() {
+        button_evr.clear();
+        return;
+    }
     if !game_state.vs_bot || !game_state.bot_turn {
         for event in button_evr.iter() {
             if let ButtonState::Pressed = event.state {
@@ -480,7 +752,14 @@ fn highlight_moves_on_click(
                 }
 
                 if let Some(pos) = window.cursor_position() {
-                    let clicked_coords = mouse_pos_to_coordinates(pos.x, pos.y, width, height);
+                    let clicked_coords = mouse_pos_to_coordinates(
+                        pos.x,
+                        pos.y,
+                        width,
+                        height,
+                        *board_scale,
+                        game_state.flipped,
+                    );
                     if let Some(clicked_field) = game_state.board.get_field(clicked_coords) {
                         if let Some(piece) = &clicked_field.piece {
                             if (piece.piece_color == PieceColor::White) == game_state.white {
@@ -494,6 +773,30 @@ fn highlight_moves_on_click(
     }
 }
 
+// fifty-move rule and threefold repetition draws have to be claimed by the player to move
+fn claim_draw_on_key(
+    keys: Res<Input<KeyCode>>,
+    mut game_state: ResMut<GameState>,
+    mut state: ResMut<State<GlobalState>>,
+    mut whose_turn: ResMut<State<WhoseTurn>>,
+) {
+    if !keys.just_pressed(KeyCode::D)
+        || (game_state.vs_bot && game_state.bot_turn)
+        || game_state.viewed_move.is_some()
+    {
+        return;
+    }
+    if let Some(result) = game_state.board.claimable_draw() {
+        end_game(&mut game_state, &mut state, &mut whose_turn, result);
+    }
+}
+
+fn flip_board_on_key(keys: Res<Input<KeyCode>>, mut game_state: ResMut<GameState>) {
+    if keys.just_pressed(KeyCode::F) {
+        game_state.flipped = !game_state.flipped;
+    }
+}
+
 fn pause_on_escape(
     keys: Res<Input<KeyCode>>,
     mut state: ResMut<State<GlobalState>>,
@@ -511,11 +814,17 @@ pub struct UserInputPlugin;
 
 impl Plugin for UserInputPlugin {
     fn build(&self, app: &mut App) {
-        app.add_system_set(
-            SystemSet::on_update(GlobalState::InGame)
-                .with_system(handle_user_input)
-                .with_system(highlight_moves_on_click.after(handle_user_input))
-                .with_system(pause_on_escape),
-        );
+        app.init_resource::<Dragging>()
+            .add_system_set(
+                SystemSet::on_update(GlobalState::InGame)
+                    .with_system(handle_user_input)
+                    .with_system(highlight_moves_on_click.after(handle_user_input))
+                    .with_system(claim_draw_on_key)
+                    .with_system(flip_board_on_key)
+                    .with_system(pause_on_escape),
+            )
+            .add_system_set(
+                SystemSet::on_exit(GlobalState::InGame).with_system(put_back_dragged_piece),
+            );
     }
 }